@@ -8,25 +8,71 @@ use poly_commit_benches::{
 
 const LOG_MIN_DEG: usize = 6;
 const LOG_MAX_DEG: usize = 12;
+// `3 * 2^k` sizes, which need a mixed radix domain
+const MIXED_RADIX_SIZES: [usize; 6] = [48, 96, 192, 384, 768, 1536];
 
 pub fn enc_bench(c: &mut Criterion) {
+    let sizes: Vec<_> = (LOG_MIN_DEG..LOG_MAX_DEG).map(|i| 2usize.pow(i as u32)).collect();
     {
         let mut g_scalar = c.benchmark_group("scalar_enc_bench");
-        do_enc_bench::<ark::Bls12_381ScalarEncBench, _>(&mut g_scalar, "ark_bls12_381_scalar");
-        do_enc_bench::<ark::Bn254ScalarEncBench, _>(&mut g_scalar, "ark_bn_254_scalar");
-        do_enc_bench::<PlonkEncBench, _>(&mut g_scalar, "plonk_scalar");
+        do_enc_bench::<ark::Bls12_381ScalarEncBench, _>(
+            &mut g_scalar,
+            "ark_bls12_381_scalar",
+            &sizes,
+        );
+        do_enc_bench::<ark::Bn254ScalarEncBench, _>(&mut g_scalar, "ark_bn_254_scalar", &sizes);
+        do_enc_bench::<PlonkEncBench, _>(&mut g_scalar, "plonk_scalar", &sizes);
     }
     {
         let mut g_pt = c.benchmark_group("pt_enc_bench");
-        do_enc_bench::<ark::Bls12_381G1EncBench, _>(&mut g_pt, "ark_bls12_381_g1");
+        do_enc_bench::<ark::Bls12_381G1EncBench, _>(&mut g_pt, "ark_bls12_381_g1", &sizes);
     }
+    {
+        let mut g_mixed = c.benchmark_group("mixed_radix_enc_bench");
+        do_enc_bench::<ark::Bn254MixedRadixScalarEncBench, _>(
+            &mut g_mixed,
+            "ark_bn254_mixed_radix_scalar",
+            &MIXED_RADIX_SIZES,
+        );
+    }
+}
+
+// Scalars and G1 points side by side at each size, so the cost of extending
+// commitments can be read against extending the data they commit to
+pub fn enc_compare_bench(c: &mut Criterion) {
+    let mut g = c.benchmark_group("enc_compare");
+    for size in (LOG_MIN_DEG..LOG_MAX_DEG).map(|i| 2usize.pow(i as u32)) {
+        g.throughput(criterion::Throughput::Elements(size as u64));
+        do_enc_compare::<ark::Bls12_381ScalarEncBench, _>(&mut g, "bls12_381_scalar", size);
+        do_enc_compare::<ark::Bls12_381G1EncBench, _>(&mut g, "bls12_381_g1", size);
+    }
+}
+
+fn do_enc_compare<B: ErasureEncodeBench, M: Measurement>(
+    g: &mut BenchmarkGroup<'_, M>,
+    label: &str,
+    size: usize,
+) {
+    let s1 = B::make_domain(size);
+    let s2 = B::make_domain(2 * size);
+    let pts = B::rand_points(size);
+    let mut encoded = pts.clone();
+    B::erasure_encode(&mut encoded, &s1, &s2);
+    assert_eq!(encoded.len(), 2 * size, "{} encoded to the wrong size", label);
+    g.bench_with_input(BenchmarkId::new(label, size), &size, |b, &_| {
+        b.iter(|| {
+            let mut pt2 = pts.clone();
+            B::erasure_encode(&mut pt2, &s1, &s2)
+        })
+    });
 }
 
 pub fn do_enc_bench<B: ErasureEncodeBench, M: Measurement>(
     g: &mut BenchmarkGroup<'_, M>,
     suite_name: &str,
+    sizes: &[usize],
 ) {
-    for size in (LOG_MIN_DEG..LOG_MAX_DEG).map(|i| 2usize.pow(i as u32)) {
+    for &size in sizes {
         g.throughput(criterion::Throughput::Elements(size as u64));
         let s1 = B::make_domain(size);
         let s2 = B::make_domain(2 * size);
@@ -40,5 +86,5 @@ pub fn do_enc_bench<B: ErasureEncodeBench, M: Measurement>(
     }
 }
 
-criterion_group!(enc_benches, enc_bench);
+criterion_group!(enc_benches, enc_bench, enc_compare_bench);
 criterion_main!(enc_benches);
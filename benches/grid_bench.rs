@@ -2,10 +2,17 @@ use criterion::{
     criterion_group, criterion_main, measurement::Measurement, BenchmarkGroup, BenchmarkId,
     Criterion,
 };
-use poly_commit_benches::{ark::grid_bench::KzgGridBenchBls12_381, GridBench, plonk_kzg::grid_bench::PlonkGridBench};
+use rand::seq::index::sample;
+use poly_commit_benches::{ark::grid_bench::KzgGridBenchBls12_381, bench_rng_for, GridBench, RngTag, plonk_kzg::grid_bench::PlonkGridBench};
+use poly_commit_benches::ark::grid_bench::GridVerifyContext;
 
 const GRID_MIN_LOG_SIZE: usize = 4;
 const GRID_MAX_LOG_SIZE: usize = 8;
+// Number of columns a sampling client opens at once
+const SAMPLED_COLS: [usize; 2] = [16, 32];
+// Column verifications per iteration of the cached verification bench, all drawn
+// from `SAMPLED_COLS[0]` distinct columns
+const REPEATED_SAMPLES: usize = 100;
 
 pub fn grid_bench(c: &mut Criterion) {
     {
@@ -13,15 +20,36 @@ pub fn grid_bench(c: &mut Criterion) {
         do_extend_bench::<KzgGridBenchBls12_381, _>(&mut g_extend, "ark_bls12_381");
         do_extend_bench::<PlonkGridBench, _>(&mut g_extend, "plonk");
     }
+    {
+        let mut g_layout = c.benchmark_group("grid_extend_layout");
+        do_extend_layout_bench(&mut g_layout);
+    }
     {
         let mut g_commit = c.benchmark_group("grid_commit");
         do_commit_bench::<KzgGridBenchBls12_381, _>(&mut g_commit, "ark_bls12_381");
         do_commit_bench::<PlonkGridBench, _>(&mut g_commit, "plonk");
     }
+    {
+        let mut g_storage = c.benchmark_group("grid_storage");
+        do_storage_bench(&mut g_storage);
+    }
     {
         let mut g_open = c.benchmark_group("grid_open_col");
-        do_open_bench::<KzgGridBenchBls12_381, _>(&mut g_open, "ark_bls12_381");
-        do_open_bench::<PlonkGridBench, _>(&mut g_open, "plonk");
+        do_open_bench::<KzgGridBenchBls12_381, _>(&mut g_open, "ark_bls12_381", 1);
+        do_open_bench::<PlonkGridBench, _>(&mut g_open, "plonk", 1);
+    }
+    for n_cols in SAMPLED_COLS {
+        let mut g_open = c.benchmark_group(format!("grid_open_{}_cols", n_cols));
+        do_open_bench::<KzgGridBenchBls12_381, _>(&mut g_open, "ark_bls12_381", n_cols);
+        do_open_bench::<PlonkGridBench, _>(&mut g_open, "plonk", n_cols);
+    }
+    {
+        let mut g_verify = c.benchmark_group("grid_verify_repeated_cols");
+        do_verify_cached_bench(&mut g_verify);
+    }
+    {
+        let mut g_reconstruct = c.benchmark_group("grid_reconstruct");
+        do_reconstruct_bench::<KzgGridBenchBls12_381, _>(&mut g_reconstruct, "ark_bls12_381");
     }
 }
 
@@ -41,6 +69,25 @@ pub fn do_extend_bench<B: GridBench, M: Measurement>(
     }
 }
 
+// Column-major `FlatGrid` against the nested `Vec<Vec<_>>` rows it replaced
+pub fn do_extend_layout_bench<M: Measurement>(g: &mut BenchmarkGroup<'_, M>) {
+    type B = KzgGridBenchBls12_381;
+    for size in (GRID_MIN_LOG_SIZE..=GRID_MAX_LOG_SIZE).map(|i| 2usize.pow(i as u32)) {
+        g.throughput(criterion::Throughput::Bytes(
+            (size * size * B::bytes_per_elem()) as u64,
+        ));
+        let s = B::do_setup(size);
+        let grid = B::rand_grid(size);
+        let rows = grid.to_rows();
+        g.bench_with_input(BenchmarkId::new("flat", size), &size, |b, &_| {
+            b.iter(|| B::extend_grid(&s, &grid))
+        });
+        g.bench_with_input(BenchmarkId::new("nested", size), &size, |b, &_| {
+            b.iter(|| B::extend_nested_grid(&s, &rows))
+        });
+    }
+}
+
 pub fn do_commit_bench<B: GridBench, M: Measurement>(
     g: &mut BenchmarkGroup<'_, M>,
     suite_name: &str,
@@ -58,19 +105,118 @@ pub fn do_commit_bench<B: GridBench, M: Measurement>(
     }
 }
 
+// Extending and committing with every commitment converted to affine on its own against
+// keeping them projective until one batch normalization
+pub fn do_storage_bench<M: Measurement>(g: &mut BenchmarkGroup<'_, M>) {
+    type B = KzgGridBenchBls12_381;
+    for size in [64, 128] {
+        g.throughput(criterion::Throughput::Bytes(
+            (size * size * B::bytes_per_elem()) as u64,
+        ));
+        let s = B::do_setup(size);
+        let grid = B::rand_grid(size);
+        let eg = B::extend_grid(&s, &grid);
+        assert_eq!(
+            B::make_commits_affine(&s, &eg),
+            B::make_commits_projective(&s, &eg)
+        );
+        g.bench_with_input(BenchmarkId::new("affine", size), &size, |b, &_| {
+            b.iter(|| B::make_commits_affine(&s, &B::extend_grid(&s, &grid)))
+        });
+        g.bench_with_input(BenchmarkId::new("projective", size), &size, |b, &_| {
+            b.iter(|| B::make_commits_projective(&s, &B::extend_grid(&s, &grid)))
+        });
+    }
+}
+
 pub fn do_open_bench<B: GridBench, M: Measurement>(
     g: &mut BenchmarkGroup<'_, M>,
     suite_name: &str,
+    n_cols: usize,
 ) {
-    for size in (GRID_MIN_LOG_SIZE..=GRID_MAX_LOG_SIZE).map(|i| 2usize.pow(i as u32)) {
+    // Can't sample more distinct columns than the grid has
+    for size in (GRID_MIN_LOG_SIZE..=GRID_MAX_LOG_SIZE)
+        .map(|i| 2usize.pow(i as u32))
+        .filter(|&size| size >= n_cols)
+    {
         g.throughput(criterion::Throughput::Bytes(
-            (size * B::bytes_per_elem()) as u64,
+            (n_cols * size * B::bytes_per_elem()) as u64,
         ));
         let s = B::do_setup(size);
         let grid = B::rand_grid(size);
         let eg = B::extend_grid(&s, &grid);
+        let cols = sample(&mut bench_rng_for(RngTag::Sample), size, n_cols).into_vec();
+        // Make sure we're benchmarking openings that actually verify
+        let commits = B::make_commits(&s, &eg);
+        for (opens, &col) in B::open_columns(&s, &eg, &cols).iter().zip(cols.iter()) {
+            assert!(B::verify_column(&s, &commits, opens, &eg, col));
+        }
+        g.bench_with_input(BenchmarkId::new(suite_name, size), &size, |b, &_| {
+            b.iter(|| B::open_columns(&s, &eg, &cols))
+        });
+    }
+}
+
+// `REPEATED_SAMPLES` column checks cycling over the same few columns, with and without
+// a `GridVerifyContext` kept across iterations
+pub fn do_verify_cached_bench<M: Measurement>(g: &mut BenchmarkGroup<'_, M>) {
+    type B = KzgGridBenchBls12_381;
+    let n_cols = SAMPLED_COLS[0];
+    for size in (GRID_MIN_LOG_SIZE..=GRID_MAX_LOG_SIZE)
+        .map(|i| 2usize.pow(i as u32))
+        .filter(|&size| size >= n_cols)
+    {
+        let s = B::do_setup(size);
+        let eg = B::extend_grid(&s, &B::rand_grid(size));
+        let commits = B::make_commits(&s, &eg);
+        let cols = sample(&mut bench_rng_for(RngTag::Sample), size, n_cols).into_vec();
+        let samples: Vec<_> = B::open_columns(&s, &eg, &cols)
+            .into_iter()
+            .zip(cols.iter())
+            .map(|(opens, &col)| (opens, B::column_values(&s, &eg, col), col))
+            .cycle()
+            .take(REPEATED_SAMPLES)
+            .collect();
+
+        g.bench_with_input(BenchmarkId::new("uncached", size), &size, |b, &_| {
+            b.iter(|| {
+                for (opens, values, col) in &samples {
+                    assert!(B::verify_column_by_index(&s, &commits, opens, values, *col));
+                }
+            })
+        });
+        let mut ctx = GridVerifyContext::new(&s);
+        g.bench_with_input(BenchmarkId::new("cached", size), &size, |b, &_| {
+            b.iter(|| {
+                for (opens, values, col) in &samples {
+                    assert!(ctx.verify_column(&commits, opens, values, *col));
+                }
+            })
+        });
+    }
+}
+
+// Recovering the whole extended grid from exactly half of its rows, as a DAS node
+// does once it has sampled enough of them
+pub fn do_reconstruct_bench<B, M: Measurement>(g: &mut BenchmarkGroup<'_, M>, suite_name: &str)
+where
+    B: GridBench<ExtendedGrid = Vec<Vec<<B as GridBench>::Point>>>,
+    B::Point: PartialEq + std::fmt::Debug,
+{
+    for size in (GRID_MIN_LOG_SIZE..=GRID_MAX_LOG_SIZE).map(|i| 2usize.pow(i as u32)) {
+        g.throughput(criterion::Throughput::Bytes(
+            (2 * size * size * B::bytes_per_elem()) as u64,
+        ));
+        let s = B::do_setup(size);
+        let eg = B::extend_grid(&s, &B::rand_grid(size));
+        let mut partial = vec![None; 2 * size];
+        for i in sample(&mut bench_rng_for(RngTag::Sample), 2 * size, size) {
+            partial[i] = Some(eg[i].clone());
+        }
+        // Make sure we're benchmarking a reconstruction that recovers the grid
+        assert_eq!(B::reconstruct(&s, &partial), Ok(eg));
         g.bench_with_input(BenchmarkId::new(suite_name, size), &size, |b, &_| {
-            b.iter(|| B::open_column(&s, &eg))
+            b.iter(|| B::reconstruct(&s, &partial))
         });
     }
 }
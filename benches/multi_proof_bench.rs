@@ -1,11 +1,17 @@
-use ark_bls12_381_04::Bls12_381;
+use ark_bls12_381_04::{Bls12_381, Fr};
+use ark_std_04::UniformRand;
 use criterion::{
     criterion_group, criterion_main, measurement::Measurement, BenchmarkGroup, BenchmarkId,
     Criterion, Throughput,
 };
 use poly_commit_benches::{
-    ark::kzg_multiproof_bench::{Multiproof1Bench, Multiproof2Bench},
-    PcBench,
+    ark::{
+        kzg_multiproof::method1,
+        kzg_multiproof_bench::{Multiproof1Bench, Multiproof2Bench},
+    },
+    bench_rng_for,
+    report::ProofSizeReport,
+    PcBench, RngTag,
 };
 
 pub fn open_bench(c: &mut Criterion) {
@@ -42,12 +48,144 @@ pub fn verify_bench(c: &mut Criterion) {
     do_verify_bench::<Multiproof2Bench<Bls12_381, 128, 128>, _>(&mut group, "mp2_128_128", &[256]);
 }
 
+// Proof sizes, which stay constant in the number of points and polynomials
+pub fn proof_size_bench(_c: &mut Criterion) {
+    let mut report = ProofSizeReport::default();
+    report.record_scheme::<Multiproof1Bench<Bls12_381, 4, 4>>("mp1_4_4", &[256]);
+    report.record_scheme::<Multiproof1Bench<Bls12_381, 64, 64>>("mp1_64_64", &[256]);
+    report.record_scheme::<Multiproof2Bench<Bls12_381, 4, 4>>("mp2_4_4", &[256]);
+    report.record_scheme::<Multiproof2Bench<Bls12_381, 64, 64>>("mp2_64_64", &[256]);
+    report.print();
+}
+
+// Many polynomials at one shared point, through the general path and the
+// synthetic division one
+pub fn single_point_open_bench(c: &mut Criterion) {
+    const DEGREE: usize = 256;
+    const N_POLY: usize = 10;
+    let mut group = c.benchmark_group("single_point_open");
+    let setup = method1::Setup::<Bls12_381>::new(DEGREE, 1, &mut bench_rng_for(RngTag::Setup));
+    let mut rng = bench_rng_for(RngTag::Inputs);
+    let polys: Vec<Vec<Fr>> = (0..N_POLY)
+        .map(|_| (0..=DEGREE).map(|_| Fr::rand(&mut rng)).collect())
+        .collect();
+    let point = Fr::rand(&mut rng);
+    let challenge = Fr::rand(&mut rng);
+
+    group.bench_function("mp1_open", |b| {
+        b.iter(|| setup.open(&polys, &[point], challenge).unwrap())
+    });
+    group.bench_function("mp1_open_single_point", |b| {
+        b.iter(|| setup.open_single_point(&polys, point, challenge).unwrap())
+    });
+}
+
+// Many independent method1 proofs, one by one and folded into one pairing product
+pub fn batch_verify_bench(c: &mut Criterion) {
+    const DEGREE: usize = 256;
+    const N_PTS: usize = 4;
+    const N_POLY: usize = 4;
+    const N_PROOFS: usize = 32;
+    let mut group = c.benchmark_group("batch_verify");
+    group.throughput(Throughput::Elements(N_PROOFS as u64));
+    let setup = method1::Setup::<Bls12_381>::new(DEGREE, N_PTS, &mut bench_rng_for(RngTag::Setup));
+    let mut rng = bench_rng_for(RngTag::Inputs);
+    let claims: Vec<_> = (0..N_PROOFS)
+        .map(|_| {
+            let polys: Vec<Vec<Fr>> = (0..N_POLY)
+                .map(|_| (0..=DEGREE).map(|_| Fr::rand(&mut rng)).collect())
+                .collect();
+            let points: Vec<Fr> = (0..N_PTS).map(|_| Fr::rand(&mut rng)).collect();
+            let evals: Vec<Vec<Fr>> = polys
+                .iter()
+                .map(|p| points.iter().map(|x| evaluate(p, *x)).collect())
+                .collect();
+            let commits: Vec<_> = polys.iter().map(|p| setup.commit(p).unwrap()).collect();
+            let challenge = Fr::rand(&mut rng);
+            let proof = setup.open(&polys, &points, challenge).unwrap();
+            (commits, points, evals, proof, challenge)
+        })
+        .collect();
+    let instances: Vec<_> = claims
+        .iter()
+        .map(|(commits, points, evals, proof, challenge)| method1::Instance {
+            commits,
+            points,
+            evals,
+            proof,
+            challenge: *challenge,
+        })
+        .collect();
+    assert!(setup.verify_batch(&instances, &mut rng).unwrap());
+
+    group.bench_function("mp1_verify_each", |b| {
+        b.iter(|| {
+            for i in &instances {
+                setup
+                    .verify(i.commits, i.points, i.evals, i.proof, i.challenge)
+                    .unwrap();
+            }
+        })
+    });
+    group.bench_function("mp1_verify_batch", |b| {
+        b.iter(|| setup.verify_batch(&instances, &mut rng).unwrap())
+    });
+}
+
+// The vanishing polynomial of the points is committed to in G2 by the verifier, so
+// its cost grows with the number of points, while the polynomial count stays fixed
+macro_rules! point_sweep {
+    ($group:expr, $n_poly:literal, [$($n_pts:literal),*]) => {
+        $(do_point_scaling_bench::<Multiproof1Bench<Bls12_381, $n_pts, $n_poly>, _>(
+            $group, $n_pts, 256,
+        );)*
+    };
+}
+
+pub fn multi_point_scaling_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("multi_point_scaling");
+    point_sweep!(&mut group, 4, [1, 2, 4, 8, 16, 32]);
+}
+
+fn do_point_scaling_bench<B: PcBench, M: Measurement>(
+    g: &mut BenchmarkGroup<'_, M>,
+    n_pts: usize,
+    poly_degree: usize,
+) {
+    let mut setup = B::setup(poly_degree);
+    let trim = B::trim(&setup, poly_degree);
+    let (poly, point, value) = B::rand_poly(&mut setup, poly_degree);
+    let commit = B::commit(&trim, &mut setup, &poly);
+    let open = B::open(&trim, &mut setup, &poly, &point);
+    assert!(
+        B::verify(&trim, &commit, &open, &value, &point),
+        "{} points don't verify",
+        n_pts
+    );
+    g.bench_with_input(BenchmarkId::new("mp1_open", n_pts), &n_pts, |b, &_| {
+        b.iter(|| B::open(&trim, &mut setup, &poly, &point))
+    });
+    g.bench_with_input(BenchmarkId::new("mp1_verify", n_pts), &n_pts, |b, &_| {
+        b.iter(|| B::verify(&trim, &commit, &open, &value, &point))
+    });
+}
+
+fn evaluate(coeffs: &[Fr], x: Fr) -> Fr {
+    coeffs.iter().rev().fold(Fr::from(0u64), |acc, c| acc * x + c)
+}
+
 pub fn do_open_bench<B: PcBench, M: Measurement>(
     g: &mut BenchmarkGroup<'_, M>,
     suite_name: &str,
     poly_degrees: &[usize],
 ) {
     let mut setup = B::setup(256);
+    println!(
+        "{}: open {}, proof size {}",
+        suite_name,
+        B::OPEN_COMPLEXITY,
+        B::PROOF_SIZE_CLASS
+    );
     for s in poly_degrees {
         g.throughput(open_throughput::<B>());
         let trim = B::trim(&setup, *s);
@@ -97,5 +235,13 @@ fn open_throughput<B: PcBench>() -> Throughput {
     Throughput::Bytes(B::bytes_per_elem() as u64)
 }
 
-criterion_group!(benches, open_bench, verify_bench);
+criterion_group!(
+    benches,
+    open_bench,
+    verify_bench,
+    single_point_open_bench,
+    batch_verify_bench,
+    multi_point_scaling_bench,
+    proof_size_bench
+);
 criterion_main!(benches);
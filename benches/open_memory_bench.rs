@@ -0,0 +1,34 @@
+//! Peak resident set size while opening, for the provers whose memory use grows
+//! differently with the degree. Only Linux can reset the peak between schemes, so
+//! elsewhere every row is at least the largest one before it.
+use poly_commit_benches::{
+    ark::{fri_bench::*, kzg_bench::*, ligero_bench::*},
+    rss, PcBench,
+};
+
+const LOG_DEGREES: [u32; 3] = [10, 13, 16];
+
+// Peak RSS in MiB while opening a random polynomial of degree `deg`
+fn open_peak_mib<B: PcBench>(deg: usize) -> f64 {
+    let mut setup = B::setup(deg);
+    let trim = B::trim(&setup, deg);
+    let (poly, point, _) = B::rand_poly(&mut setup, deg);
+    B::measure_open_memory(&trim, &mut setup, &poly, &point) as f64 / (1 << 20) as f64
+}
+
+fn report<B: PcBench>(name: &str) {
+    for log_deg in LOG_DEGREES {
+        let deg = 1 << log_deg;
+        println!("{:<20} 2^{:<4} {:>10.1}", name, log_deg, open_peak_mib::<B>(deg));
+    }
+}
+
+fn main() {
+    if !rss::reset_peak_rss() {
+        println!("Can't reset the peak RSS here, so it only grows from row to row");
+    }
+    println!("{:<20} {:<6} {:>10}", "scheme", "degree", "peak MiB");
+    report::<KzgBls12_381Bench>("ark_kzg_bls12_381");
+    report::<LigeroBls12_381Bench>("ligero_bls12_381");
+    report::<FriBls12_381Bench>("fri_bls12_381");
+}
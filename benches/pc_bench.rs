@@ -1,16 +1,46 @@
+use std::{sync::Mutex, time::Instant};
+
+use ark_bls12_381::Bls12_381;
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::One;
+use ark_ff::Zero;
+use ark_ff::{FftField, UniformRand};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Polynomial, Radix2EvaluationDomain,
+    UVPolynomial,
+};
 use criterion::{
-    criterion_group, criterion_main, measurement::Measurement, BenchmarkGroup, BenchmarkId,
-    Criterion, Throughput,
+    criterion_group, criterion_main,
+    measurement::{Measurement, WallTime},
+    BenchmarkGroup, BenchmarkId, Criterion, Throughput,
 };
+use rand::seq::index::sample;
 use poly_commit_benches::{
-    ark::{kzg_bench::*, marlin_bench::*},
+    ark::{
+        fri_bench::*,
+        kzg::{Commitment, ConstantTimePowers, PreparedCommitment, Proof, VerifierKey, KZG10},
+        ipa_bench::*,
+        kzg_bench::*,
+        ligero_bench::*,
+        pedersen_bench::*,
+        marlin_bench::*,
+    },
     plonk_kzg::PlonkKZG,
-    PcBench,
+    bench_rng_for,
+    report::{
+        poly_data_bytes, ElemsPerSecondReport, Op, ProofSizeReport, RatioReport,
+        DEFAULT_SAMPLE_SIZE,
+    },
+    PcBench, PreEvaluated, RngTag,
 };
 
 const LOG_MIN_DEG: usize = 5;
 const LOG_MAX_DEG: usize = 12;
 const MAX_DEG: usize = 2usize.pow(LOG_MAX_DEG as u32);
+const FIXED_COMMITMENT_PROOFS: usize = 1000;
+// Degree and nonzero coefficient counts for `commit_sparsity_bench`
+const SPARSE_DEG: usize = 4096;
+const SPARSE_NONZEROS: [usize; 4] = [64, 256, 1024, 4096];
 
 pub fn open_bench(c: &mut Criterion) {
     let mut group = c.benchmark_group("open");
@@ -18,11 +48,17 @@ pub fn open_bench(c: &mut Criterion) {
         .into_iter()
         .map(|s| 2usize.pow(s as u32))
         .collect();
-    do_open_bench::<MarlinBls12_381Bench, _>(&mut group, "ark_marlin_bls12_381", &poly_degrees);
-    do_open_bench::<MarlinBn254Bench, _>(&mut group, "ark_marlin_bn254", &poly_degrees);
-    do_open_bench::<KzgBls12_381Bench, _>(&mut group, "ark_kzg_bls12_381", &poly_degrees);
-    do_open_bench::<KzgBn254Bench, _>(&mut group, "ark_kzg_bn254", &poly_degrees);
-    do_open_bench::<PlonkKZG, _>(&mut group, "plonk_kzg_bls12_381", &poly_degrees);
+    do_open_bench::<MarlinBls12_381Bench, _>(&mut group, &WallTime, "ark_marlin_bls12_381", &poly_degrees);
+    do_open_bench::<MarlinBn254Bench, _>(&mut group, &WallTime, "ark_marlin_bn254", &poly_degrees);
+    do_open_bench::<MarlinBw6_761Bench, _>(&mut group, &WallTime, "ark_marlin_bw6_761", &poly_degrees);
+    do_open_bench::<KzgBls12_381Bench, _>(&mut group, &WallTime, "ark_kzg_bls12_381", &poly_degrees);
+    do_open_bench::<KzgBn254Bench, _>(&mut group, &WallTime, "ark_kzg_bn254", &poly_degrees);
+    do_open_bench::<KzgBw6_761Bench, _>(&mut group, &WallTime, "ark_kzg_bw6_761", &poly_degrees);
+    do_open_bench::<IpaPallasBench, _>(&mut group, &WallTime, "ark_ipa_pallas", &poly_degrees);
+    do_open_bench::<LigeroBls12_381Bench, _>(&mut group, &WallTime, "ligero_bls12_381", &poly_degrees);
+    do_open_bench::<FriBls12_381Bench, _>(&mut group, &WallTime, "fri_bls12_381", &poly_degrees);
+    do_open_bench::<InCircuitCommitBench, _>(&mut group, &WallTime, "pedersen_jubjub", &poly_degrees);
+    do_open_bench::<PlonkKZG, _>(&mut group, &WallTime, "plonk_kzg_bls12_381", &poly_degrees);
 }
 
 pub fn commit_bench(c: &mut Criterion) {
@@ -31,11 +67,20 @@ pub fn commit_bench(c: &mut Criterion) {
         .into_iter()
         .map(|s| 2usize.pow(s as u32))
         .collect();
-    do_commit_bench::<MarlinBls12_381Bench, _>(&mut group, "ark_marlin_bls12_381", &poly_degrees);
-    do_commit_bench::<MarlinBn254Bench, _>(&mut group, "ark_marlin_bn254", &poly_degrees);
-    do_commit_bench::<KzgBls12_381Bench, _>(&mut group, "ark_kzg_bls12_381", &poly_degrees);
-    do_commit_bench::<KzgBn254Bench, _>(&mut group, "ark_kzg_bn254", &poly_degrees);
-    do_commit_bench::<PlonkKZG, _>(&mut group, "plonk_kzg_bls12_381", &poly_degrees);
+    let mut report = ElemsPerSecondReport::default();
+    do_commit_bench::<MarlinBls12_381Bench>(&mut group, &mut report, "ark_marlin_bls12_381", &poly_degrees);
+    do_commit_bench::<MarlinBn254Bench>(&mut group, &mut report, "ark_marlin_bn254", &poly_degrees);
+    do_commit_bench::<MarlinBw6_761Bench>(&mut group, &mut report, "ark_marlin_bw6_761", &poly_degrees);
+    do_commit_bench::<KzgBls12_381Bench>(&mut group, &mut report, "ark_kzg_bls12_381", &poly_degrees);
+    do_commit_bench::<KzgBn254Bench>(&mut group, &mut report, "ark_kzg_bn254", &poly_degrees);
+    do_commit_bench::<KzgBw6_761Bench>(&mut group, &mut report, "ark_kzg_bw6_761", &poly_degrees);
+    do_commit_bench::<IpaPallasBench>(&mut group, &mut report, "ark_ipa_pallas", &poly_degrees);
+    do_commit_bench::<LigeroBls12_381Bench>(&mut group, &mut report, "ligero_bls12_381", &poly_degrees);
+    do_commit_bench::<FriBls12_381Bench>(&mut group, &mut report, "fri_bls12_381", &poly_degrees);
+    do_commit_bench::<InCircuitCommitBench>(&mut group, &mut report, "pedersen_jubjub", &poly_degrees);
+    do_commit_bench::<PlonkKZG>(&mut group, &mut report, "plonk_kzg_bls12_381", &poly_degrees);
+    group.finish();
+    report.print();
 }
 
 pub fn verify_bench(c: &mut Criterion) {
@@ -44,19 +89,251 @@ pub fn verify_bench(c: &mut Criterion) {
         .into_iter()
         .map(|s| 2usize.pow(s as u32))
         .collect();
-    do_verify_bench::<MarlinBls12_381Bench, _>(&mut group, "ark_marlin_bls12_381", &poly_degrees);
-    do_verify_bench::<MarlinBn254Bench, _>(&mut group, "ark_marlin_bn254", &poly_degrees);
-    do_verify_bench::<KzgBls12_381Bench, _>(&mut group, "ark_kzg_bls12_381", &poly_degrees);
-    do_verify_bench::<KzgBn254Bench, _>(&mut group, "ark_kzg_bn254", &poly_degrees);
-    do_verify_bench::<PlonkKZG, _>(&mut group, "plonk_kzg_bls12_381", &poly_degrees);
+    do_verify_bench::<MarlinBls12_381Bench, _>(&mut group, &WallTime, "ark_marlin_bls12_381", &poly_degrees);
+    do_verify_bench::<MarlinBn254Bench, _>(&mut group, &WallTime, "ark_marlin_bn254", &poly_degrees);
+    do_verify_bench::<MarlinBw6_761Bench, _>(&mut group, &WallTime, "ark_marlin_bw6_761", &poly_degrees);
+    do_verify_bench::<KzgBls12_381Bench, _>(&mut group, &WallTime, "ark_kzg_bls12_381", &poly_degrees);
+    do_verify_bench::<KzgBn254Bench, _>(&mut group, &WallTime, "ark_kzg_bn254", &poly_degrees);
+    do_verify_bench::<KzgBw6_761Bench, _>(&mut group, &WallTime, "ark_kzg_bw6_761", &poly_degrees);
+    do_verify_bench::<IpaPallasBench, _>(&mut group, &WallTime, "ark_ipa_pallas", &poly_degrees);
+    do_verify_bench::<LigeroBls12_381Bench, _>(&mut group, &WallTime, "ligero_bls12_381", &poly_degrees);
+    do_verify_bench::<FriBls12_381Bench, _>(&mut group, &WallTime, "fri_bls12_381", &poly_degrees);
+    do_verify_bench::<InCircuitCommitBench, _>(&mut group, &WallTime, "pedersen_jubjub", &poly_degrees);
+    do_verify_bench::<PlonkKZG, _>(&mut group, &WallTime, "plonk_kzg_bls12_381", &poly_degrees);
+}
+
+// Proof sizes, printed as a table rather than timed
+pub fn proof_size_bench(_c: &mut Criterion) {
+    let mut report = ProofSizeReport::default();
+    let poly_degrees: Vec<_> = (LOG_MIN_DEG..LOG_MAX_DEG)
+        .map(|s| 2usize.pow(s as u32))
+        .collect();
+    report.record_scheme::<MarlinBls12_381Bench>("ark_marlin_bls12_381", &poly_degrees);
+    report.record_scheme::<MarlinBn254Bench>("ark_marlin_bn254", &poly_degrees);
+    report.record_scheme::<MarlinBw6_761Bench>("ark_marlin_bw6_761", &poly_degrees);
+    report.record_scheme::<KzgBls12_381Bench>("ark_kzg_bls12_381", &poly_degrees);
+    report.record_scheme::<KzgBn254Bench>("ark_kzg_bn254", &poly_degrees);
+    report.record_scheme::<KzgBw6_761Bench>("ark_kzg_bw6_761", &poly_degrees);
+    report.record_scheme::<IpaPallasBench>("ark_ipa_pallas", &poly_degrees);
+    report.record_scheme::<LigeroBls12_381Bench>("ligero_bls12_381", &poly_degrees);
+    report.record_scheme::<FriBls12_381Bench>("fri_bls12_381", &poly_degrees);
+    report.record_scheme::<InCircuitCommitBench>("pedersen_jubjub", &poly_degrees);
+    report.record_scheme::<PlonkKZG>("plonk_kzg_bls12_381", &poly_degrees);
+    report.print();
+}
+
+// How much caching the prepared G2 elements in the `VerifierKey` saves
+pub fn verify_prep_bench(c: &mut Criterion) {
+    type B = KzgBls12_381Bench;
+    type Kzg = KZG10<Bls12_381, DensePolynomial<<Bls12_381 as PairingEngine>::Fr>>;
+
+    let mut group = c.benchmark_group("verify_prep");
+    let deg = 2usize.pow(LOG_MIN_DEG as u32);
+    let mut setup = B::setup(deg);
+    let trim = B::trim(&setup, deg);
+    let (poly, point, value) = B::rand_poly(&mut setup, deg);
+    let commit = B::commit(&trim, &mut setup, &poly);
+    let proof = B::open(&trim, &mut setup, &poly, &point);
+    let vk = &trim.1;
+    // All of them must agree, on both valid and invalid openings
+    for v in [value, value + <Bls12_381 as PairingEngine>::Fr::one()] {
+        let expected = Kzg::check(vk, &commit, point, v, &proof).unwrap();
+        assert_eq!(expected, Kzg::check_prepared(vk, &commit, point, v, &proof).unwrap());
+        assert_eq!(expected, Kzg::check_low_mem(vk, &commit, point, v, &proof).unwrap());
+        assert_eq!(expected, check_preparing_each_call(vk, &commit, point, v, &proof));
+    }
+
+    group.bench_function("ark_kzg_bls12_381_check", |b| {
+        b.iter(|| Kzg::check(vk, &commit, point, value, &proof))
+    });
+    group.bench_function("ark_kzg_bls12_381_check_prepared", |b| {
+        b.iter(|| Kzg::check_prepared(vk, &commit, point, value, &proof))
+    });
+    group.bench_function("ark_kzg_bls12_381_check_low_mem", |b| {
+        b.iter(|| Kzg::check_low_mem(vk, &commit, point, value, &proof))
+    });
+    group.bench_function("ark_kzg_bls12_381_check_fresh_prep", |b| {
+        b.iter(|| check_preparing_each_call(vk, &commit, point, value, &proof))
+    });
+}
+
+// Verifying many proofs against one commitment, as a state channel does. Both
+// variants batch-check all the proofs, so they only differ in whether the
+// commitment is prepared once up front
+pub fn fixed_commitment_verify_bench(c: &mut Criterion) {
+    type B = KzgBls12_381Bench;
+    type Fr = <Bls12_381 as PairingEngine>::Fr;
+    type Kzg = KZG10<Bls12_381, DensePolynomial<Fr>>;
+
+    let mut group = c.benchmark_group("fixed_commitment_verify");
+    group.throughput(Throughput::Elements(FIXED_COMMITMENT_PROOFS as u64));
+    let deg = 2usize.pow(LOG_MIN_DEG as u32);
+    let mut setup = B::setup(deg);
+    let trim = B::trim(&setup, deg);
+    let (poly, _, _) = B::rand_poly(&mut setup, deg);
+    let commit = B::commit(&trim, &mut setup, &poly);
+    let vk = &trim.1;
+
+    let mut rng = bench_rng_for(RngTag::Inputs);
+    let points: Vec<Fr> = (0..FIXED_COMMITMENT_PROOFS)
+        .map(|_| Fr::rand(&mut rng))
+        .collect();
+    let values: Vec<Fr> = points.iter().map(|z| poly.evaluate(z)).collect();
+    let proofs: Vec<_> = points
+        .iter()
+        .map(|z| B::open(&trim, &mut setup, &poly, z))
+        .collect();
+    let commits = vec![commit.clone(); FIXED_COMMITMENT_PROOFS];
+    let prepared = vec![PreparedCommitment::prepare(&commit); FIXED_COMMITMENT_PROOFS];
+    for ((z, v), proof) in points.iter().zip(values.iter()).zip(proofs.iter()) {
+        assert!(Kzg::check(vk, &commit, *z, *v, proof).unwrap());
+    }
+    assert!(Kzg::batch_check(vk, &commits, &points, &values, &proofs, &mut rng).unwrap());
+    assert!(
+        Kzg::batch_check_prepared(vk, &prepared, &points, &values, &proofs, &mut rng).unwrap()
+    );
+
+    group.bench_function("ark_kzg_bls12_381_unprepared_commitment", |b| {
+        b.iter(|| Kzg::batch_check(vk, &commits, &points, &values, &proofs, &mut rng))
+    });
+    group.bench_function("ark_kzg_bls12_381_prepared_commitment", |b| {
+        b.iter(|| Kzg::batch_check_prepared(vk, &prepared, &points, &values, &proofs, &mut rng))
+    });
+}
+
+// `KZG10::commit_sparse` at a fixed degree, which should scale with the number of
+// nonzero coefficients rather than the degree
+pub fn commit_sparsity_bench(c: &mut Criterion) {
+    type B = KzgBls12_381Bench;
+    type Fr = <Bls12_381 as PairingEngine>::Fr;
+    type Kzg = KZG10<Bls12_381, DensePolynomial<Fr>>;
+
+    let mut group = c.benchmark_group("commit_sparsity");
+    let setup = B::setup(SPARSE_DEG);
+    let (powers, _) = B::trim(&setup, SPARSE_DEG);
+    let mut rng = bench_rng_for(RngTag::Inputs);
+    for nonzeros in SPARSE_NONZEROS {
+        group.throughput(Throughput::Elements(nonzeros as u64));
+        let terms: Vec<_> = sample(&mut rng, SPARSE_DEG + 1, nonzeros)
+            .into_iter()
+            .map(|i| (i, Fr::rand(&mut rng)))
+            .collect();
+        let mut coeffs = vec![Fr::zero(); SPARSE_DEG + 1];
+        for &(i, c) in &terms {
+            coeffs[i] = c;
+        }
+        let dense = DensePolynomial::from_coefficients_vec(coeffs);
+        assert_eq!(
+            Kzg::commit_sparse(&powers, &terms).unwrap(),
+            Kzg::commit(&powers, &dense).unwrap()
+        );
+        group.bench_with_input(
+            BenchmarkId::new("ark_kzg_bls12_381_commit_sparse", nonzeros),
+            &nonzeros,
+            |b, &_| b.iter(|| Kzg::commit_sparse(&powers, &terms)),
+        );
+    }
 }
 
+// `KZG10::commit_coset` against `KZG10::commit_lagrange`, i.e. the cost of scaling the
+// coefficients by the powers of the inverse shift, as in DEEP-ALI
+pub fn commit_coset_bench(c: &mut Criterion) {
+    type B = KzgBls12_381Bench;
+    type Fr = <Bls12_381 as PairingEngine>::Fr;
+    type Kzg = KZG10<Bls12_381, DensePolynomial<Fr>>;
+
+    let mut group = c.benchmark_group("commit_coset");
+    let setup = B::setup(MAX_DEG);
+    let mut rng = bench_rng_for(RngTag::Inputs);
+    let shift = Fr::multiplicative_generator();
+    for n in (LOG_MIN_DEG..=LOG_MAX_DEG).map(|s| 2usize.pow(s as u32)) {
+        group.throughput(throughput::<B>(n - 1));
+        let (powers, _) = B::trim(&setup, n - 1);
+        let domain = Radix2EvaluationDomain::<Fr>::new(n).unwrap();
+        let evals: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+        assert_eq!(
+            Kzg::commit_coset(&powers, &domain, &evals, shift).unwrap(),
+            Kzg::commit(&powers, &DensePolynomial::from_coefficients_vec(domain.coset_ifft(&evals)))
+                .unwrap()
+        );
+        group.bench_with_input(BenchmarkId::new("ark_kzg_bls12_381_natural", n), &n, |b, &_| {
+            b.iter(|| Kzg::commit_lagrange(&powers, &domain, &evals))
+        });
+        group.bench_with_input(BenchmarkId::new("ark_kzg_bls12_381_coset", n), &n, |b, &_| {
+            b.iter(|| Kzg::commit_coset(&powers, &domain, &evals, shift))
+        });
+    }
+}
+
+// `KZG10::commit_constant_time` against `KZG10::commit` on a dense polynomial and on
+// one whose coefficients are all zero but the top one, which `commit` skips
+pub fn commit_constant_time_bench(c: &mut Criterion) {
+    type B = KzgBls12_381Bench;
+    type Fr = <Bls12_381 as PairingEngine>::Fr;
+    type Kzg = KZG10<Bls12_381, DensePolynomial<Fr>>;
+
+    let mut group = c.benchmark_group("commit_constant_time");
+    let setup = B::setup(MAX_DEG);
+    let mut rng = bench_rng_for(RngTag::Inputs);
+    for n in (LOG_MIN_DEG..=LOG_MAX_DEG).map(|s| 2usize.pow(s as u32)) {
+        group.throughput(throughput::<B>(n));
+        let (powers, _) = B::trim(&setup, n);
+        let ct_powers = ConstantTimePowers::prepare(&powers);
+        let dense = DensePolynomial::<Fr>::rand(n, &mut rng);
+        let mut coeffs = vec![Fr::zero(); n];
+        coeffs.push(dense.coeffs[n]);
+        let leading_zeros = DensePolynomial::from_coefficients_vec(coeffs);
+        for (name, p) in [("dense", &dense), ("leading_zeros", &leading_zeros)] {
+            assert_eq!(
+                Kzg::commit_constant_time(&ct_powers, p).unwrap(),
+                Kzg::commit(&powers, p).unwrap()
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("ark_kzg_bls12_381_skipping_{}", name), n),
+                &n,
+                |b, &_| b.iter(|| Kzg::commit(&powers, p)),
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("ark_kzg_bls12_381_constant_time_{}", name), n),
+                &n,
+                |b, &_| b.iter(|| Kzg::commit_constant_time(&ct_powers, p)),
+            );
+        }
+    }
+}
+
+// `KZG10::check_prepared`, but preparing `h` and `beta_h` on every call
+fn check_preparing_each_call<E: PairingEngine>(
+    vk: &VerifierKey<E>,
+    comm: &Commitment<E>,
+    point: E::Fr,
+    value: E::Fr,
+    proof: &Proof<E>,
+) -> bool {
+    let inner = comm.0.into_projective() - vk.g.mul(value) + proof.w.mul(point);
+    let affine_points =
+        E::G1Projective::batch_normalization_into_affine(&[inner, -proof.w.into_projective()]);
+    E::product_of_pairings(&[
+        (affine_points[0].into(), vk.h.into()),
+        (affine_points[1].into(), vk.beta_h.into()),
+    ])
+    .is_one()
+}
+
+// `measurement` is the one `g` measures with, to record the ratio report in its units
 pub fn do_open_bench<B: PcBench, M: Measurement>(
     g: &mut BenchmarkGroup<'_, M>,
+    measurement: &M,
     suite_name: &str,
     poly_degrees: &[usize],
 ) {
     let mut setup = B::setup(MAX_DEG.try_into().unwrap());
+    println!(
+        "{}: open {}, proof size {}",
+        suite_name,
+        B::OPEN_COMPLEXITY,
+        B::PROOF_SIZE_CLASS
+    );
     for s in poly_degrees {
         g.throughput(open_throughput::<B>());
         let trim = B::trim(&setup, *s);
@@ -65,16 +342,25 @@ pub fn do_open_bench<B: PcBench, M: Measurement>(
             BenchmarkId::new(format!("{}_{}", suite_name, "open"), s),
             &s,
             |b, &_| {
-                b.iter(|| {
-                    B::open(&trim, &mut setup, &poly, &point);
+                b.iter_custom(|iters| {
+                    let start = measurement.start();
+                    for _ in 0..iters {
+                        B::open(&trim, &mut setup, &poly, &point);
+                    }
+                    let value = measurement.end(start);
+                    record_ratio(suite_name, *s, Op::Open, iters, measurement.to_f64(&value));
+                    value
                 })
             },
         );
     }
 }
 
-pub fn do_commit_bench<B: PcBench, M: Measurement>(
-    g: &mut BenchmarkGroup<'_, M>,
+// Also records field elements committed per second, so curves with different
+// scalar field sizes can be compared directly
+pub fn do_commit_bench<B: PcBench>(
+    g: &mut BenchmarkGroup<'_, WallTime>,
+    report: &mut ElemsPerSecondReport,
     suite_name: &str,
     poly_degrees: &[usize],
 ) {
@@ -83,20 +369,32 @@ pub fn do_commit_bench<B: PcBench, M: Measurement>(
         g.throughput(throughput::<B>(*s));
         let trim = B::trim(&setup, *s);
         let (poly, _, _) = B::rand_poly(&mut setup, *s);
-        g.bench_with_input(
-            BenchmarkId::new(format!("{}_{}", suite_name, "commit"), s),
-            &s,
-            |b, &_| {
-                b.iter(|| {
+        let id = format!("{}_{}", suite_name, "commit");
+        let bytes = throughput_bytes::<B>(*s);
+        g.bench_with_input(BenchmarkId::new(&id, s), &s, |b, &_| {
+            b.iter_custom(|iters| {
+                let start = Instant::now();
+                for _ in 0..iters {
                     B::commit(&trim, &mut setup, &poly);
-                })
-            },
-        );
+                }
+                let elapsed = start.elapsed();
+                record_ratio(suite_name, *s, Op::Commit, iters, WallTime.to_f64(&elapsed));
+                report.record(
+                    &format!("{}/{}", id, s),
+                    bytes,
+                    B::bytes_per_elem() - 1,
+                    iters,
+                    elapsed,
+                );
+                elapsed
+            })
+        });
     }
 }
 
 pub fn do_verify_bench<B: PcBench, M: Measurement>(
     g: &mut BenchmarkGroup<'_, M>,
+    measurement: &M,
     suite_name: &str,
     poly_degrees: &[usize],
 ) {
@@ -104,29 +402,68 @@ pub fn do_verify_bench<B: PcBench, M: Measurement>(
     for s in poly_degrees {
         g.throughput(throughput::<B>(*s));
         let trim = B::trim(&setup, *s);
-        let (poly, point, value) = B::rand_poly(&mut setup, *s);
+        let PreEvaluated { poly, point, value } =
+            B::rand_poly_with_cached_eval(&mut setup, *s);
         let commit = B::commit(&trim, &mut setup, &poly);
         let open = B::open(&trim, &mut setup, &poly, &point);
         g.bench_with_input(
             BenchmarkId::new(format!("{}_{}", suite_name, "verify"), s),
             &s,
             |b, &_| {
-                b.iter(|| {
-                    B::verify(&trim, &commit, &open, &value, &point);
+                b.iter_custom(|iters| {
+                    let start = measurement.start();
+                    for _ in 0..iters {
+                        B::verify(&trim, &commit, &open, &value, &point);
+                    }
+                    let measured = measurement.end(start);
+                    record_ratio(suite_name, *s, Op::Verify, iters, measurement.to_f64(&measured));
+                    measured
                 })
             },
         );
     }
 }
 
+// Medians of the commit, open and verify benches, shared across the bench functions.
+// The groups keep criterion's default sample size.
+static RATIOS: Mutex<RatioReport> = Mutex::new(RatioReport::new(DEFAULT_SAMPLE_SIZE));
+
+fn record_ratio(scheme: &str, degree: usize, op: Op, iters: u64, value: f64) {
+    RATIOS
+        .lock()
+        .unwrap()
+        .record(scheme, degree, op, iters, value);
+}
+
+// Runs after the commit, open and verify benches to print their time ratios
+pub fn ratio_report(_c: &mut Criterion) {
+    RATIOS.lock().unwrap().print();
+}
+
 fn throughput<B: PcBench>(poly_deg: usize) -> Throughput {
-    let a = (poly_deg + 1) * (B::bytes_per_elem() - 1);
-    Throughput::Bytes(a as u64)
+    Throughput::Bytes(throughput_bytes::<B>(poly_deg))
+}
+
+fn throughput_bytes<B: PcBench>(poly_deg: usize) -> u64 {
+    poly_data_bytes(poly_deg, B::bytes_per_elem())
+        .unwrap_or_else(|| panic!("Throughput of a degree {} polynomial overflows u64", poly_deg))
 }
 
 fn open_throughput<B: PcBench>() -> Throughput {
     Throughput::Bytes(B::bytes_per_elem() as u64)
 }
 
-criterion_group!(benches, open_bench, commit_bench, verify_bench);
+criterion_group!(
+    benches,
+    open_bench,
+    commit_bench,
+    verify_bench,
+    ratio_report,
+    verify_prep_bench,
+    fixed_commitment_verify_bench,
+    commit_sparsity_bench,
+    proof_size_bench,
+    commit_coset_bench,
+    commit_constant_time_bench
+);
 criterion_main!(benches);
@@ -0,0 +1,40 @@
+use criterion::{
+    criterion_group, criterion_main, measurement::Measurement, BatchSize, BenchmarkGroup,
+    BenchmarkId, Criterion,
+};
+use poly_commit_benches::{
+    ark::{kzg_bench::*, marlin_bench::*},
+    plonk_kzg::PlonkKZG,
+    PcBench,
+};
+
+const LOG_MIN_DEG: usize = 10;
+const LOG_MAX_DEG: usize = 16;
+
+pub fn setup_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("setup");
+    // Each setup takes seconds at the larger degrees
+    group.sample_size(10);
+    do_setup_bench::<KzgBls12_381Bench, _>(&mut group, "ark_kzg_bls12_381");
+    do_setup_bench::<MarlinBls12_381Bench, _>(&mut group, "ark_marlin_bls12_381");
+    do_setup_bench::<PlonkKZG, _>(&mut group, "plonk_kzg_bls12_381");
+}
+
+pub fn do_setup_bench<B: PcBench, M: Measurement>(
+    g: &mut BenchmarkGroup<'_, M>,
+    suite_name: &str,
+) {
+    for max_degree in (LOG_MIN_DEG..=LOG_MAX_DEG).map(|i| 2usize.pow(i as u32)) {
+        g.bench_with_input(
+            BenchmarkId::new(format!("{}_{}", suite_name, "setup"), max_degree),
+            &max_degree,
+            |b, &max_degree| {
+                // Returning the setup keeps dropping it out of the measurement
+                b.iter_batched(|| (), |_| B::setup(max_degree), BatchSize::PerIteration)
+            },
+        );
+    }
+}
+
+criterion_group!(setup_benches, setup_bench);
+criterion_main!(setup_benches);
@@ -0,0 +1,76 @@
+//! Heap usage of the KZG verifiers. Timings are in the `verify_prep` group of
+//! `pc_bench`, this only counts what each call allocates.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ark_bls12_381::Bls12_381;
+use ark_ec::PairingEngine;
+use ark_poly::univariate::DensePolynomial;
+use poly_commit_benches::{
+    ark::{kzg::KZG10, kzg_bench::*},
+    PcBench,
+};
+
+struct CountingAlloc;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+// Returns the total bytes allocated by `f` and the peak heap growth while it ran
+fn measure<T>(f: impl FnOnce() -> T) -> (usize, usize) {
+    let start = CURRENT.load(Ordering::Relaxed);
+    ALLOCATED.store(0, Ordering::Relaxed);
+    PEAK.store(start, Ordering::Relaxed);
+    std::hint::black_box(f());
+    (
+        ALLOCATED.load(Ordering::Relaxed),
+        PEAK.load(Ordering::Relaxed) - start,
+    )
+}
+
+fn main() {
+    type B = KzgBls12_381Bench;
+    type Kzg = KZG10<Bls12_381, DensePolynomial<<Bls12_381 as PairingEngine>::Fr>>;
+
+    let deg = 32;
+    let mut setup = B::setup(deg);
+    let trim = B::trim(&setup, deg);
+    let (poly, point, value) = B::rand_poly(&mut setup, deg);
+    let commit = B::commit(&trim, &mut setup, &poly);
+    let proof = B::open(&trim, &mut setup, &poly, &point);
+    let vk = &trim.1;
+
+    let (check_total, check_peak) = measure(|| Kzg::check(vk, &commit, point, value, &proof));
+    let (prepared_total, prepared_peak) =
+        measure(|| Kzg::check_prepared(vk, &commit, point, value, &proof));
+    let (low_mem_total, low_mem_peak) =
+        measure(|| Kzg::check_low_mem(vk, &commit, point, value, &proof));
+    println!("{:<16} {:>12} {:>12}", "verifier", "allocated", "peak");
+    println!("{:<16} {:>12} {:>12}", "check", check_total, check_peak);
+    println!(
+        "{:<16} {:>12} {:>12}",
+        "check_prepared", prepared_total, prepared_peak
+    );
+    println!(
+        "{:<16} {:>12} {:>12}",
+        "check_low_mem", low_mem_total, low_mem_peak
+    );
+}
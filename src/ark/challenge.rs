@@ -0,0 +1,111 @@
+//! Fiat-Shamir challenge derivation, specified exactly so that verifiers written in
+//! other languages derive the same challenges.
+//!
+//! A challenge for the domain separation tag `dst` (at most 255 bytes) over the
+//! message `msg` is
+//!
+//! ```text
+//! prefix = len(dst) as one byte || dst
+//! h0     = SHA-256(prefix || 0x00 || msg)
+//! h1     = SHA-256(prefix || 0x01 || msg)
+//! challenge = (h0 || h1) read as a little-endian integer, reduced mod r
+//! ```
+//!
+//! Hashing to 64 bytes keeps the bias of the reduction negligible for the 255 bit
+//! scalar fields used here. Messages are the concatenation of the arkworks
+//! compressed serializations of the inputs, in the order listed by each caller.
+use ark_ff::PrimeField;
+use sha2::{Digest, Sha256};
+
+/// Tag for [`KZG10::batch_check_shared_points`](super::kzg::KZG10::batch_check_shared_points),
+/// over the commitments, the points and the evaluation matrix.
+pub const SHARED_POINTS_DST: &[u8] = b"POLY-COMMIT-BENCHES-V1-KZG-SHARED-POINTS";
+/// Tag for [`KZG10::check_shift_relation`](super::kzg::KZG10::check_shift_relation),
+/// over the commitment, the rotated commitment and `omega`.
+pub const SHIFT_RELATION_DST: &[u8] = b"POLY-COMMIT-BENCHES-V1-KZG-SHIFT-RELATION";
+/// Tag for the opening point of [`KZG10::open_over_coset`](super::kzg::KZG10::open_over_coset),
+/// over the commitment, the coset shift, the evaluations and the quotient commitment.
+pub const COSET_OPENING_DST: &[u8] = b"POLY-COMMIT-BENCHES-V1-KZG-COSET-OPENING";
+/// Tag for the row challenge of
+/// [`KzgGridBench::verify_extension`](super::grid_bench::KzgGridBench::verify_extension),
+/// over the original row commitments and then the extended ones.
+pub const EXTENSION_ROW_DST: &[u8] = b"POLY-COMMIT-BENCHES-V1-GRID-EXTENSION-ROW";
+/// Tag for the opening point of the same check, over the row challenge.
+pub const EXTENSION_POINT_DST: &[u8] = b"POLY-COMMIT-BENCHES-V1-GRID-EXTENSION-POINT";
+/// Tag for the combination of the rows checked by
+/// [`GridVerifyContext::verify_column`](super::grid_bench::GridVerifyContext::verify_column),
+/// over the row commitments, the openings, the values and the column as a `u64`.
+pub const GRID_COLUMN_DST: &[u8] = b"POLY-COMMIT-BENCHES-V1-GRID-COLUMN";
+/// Tag for the coefficient revealed by
+/// [`KZG10::open_with_merkle_coefficient`](super::kzg::KZG10::open_with_merkle_coefficient),
+/// over the commitment, the point and the Merkle root.
+pub const MERKLE_COEFFICIENT_DST: &[u8] = b"POLY-COMMIT-BENCHES-V1-KZG-MERKLE-COEFFICIENT";
+/// Tag for the combination of the two component polynomials in
+/// [`KZG10::open_extension_field`](super::kzg::KZG10::open_extension_field), over
+/// both component commitments, the point and the two component values.
+pub const EXTENSION_FIELD_DST: &[u8] = b"POLY-COMMIT-BENCHES-V1-KZG-EXTENSION-FIELD";
+/// Tag for the combination of the equations checked by
+/// [`UniversalParams::verify_well_formed`](super::kzg::UniversalParams::verify_well_formed),
+/// over the serialized parameters.
+pub const SRS_CHECK_DST: &[u8] = b"POLY-COMMIT-BENCHES-V1-KZG-SRS-CHECK";
+/// Tag for the fixed mask added to every coefficient by
+/// [`KZG10::commit_constant_time`](super::kzg::KZG10::commit_constant_time), over an
+/// empty message. It is public, not a challenge.
+pub const CONSTANT_TIME_MASK_DST: &[u8] = b"POLY-COMMIT-BENCHES-V1-KZG-CONSTANT-TIME-MASK";
+/// Tag for the challenge of a [`Pedersen`](super::pedersen::Pedersen) opening, over
+/// the commitment, the point, the value and the prover's first message.
+pub const PEDERSEN_OPENING_DST: &[u8] = b"POLY-COMMIT-BENCHES-V1-PEDERSEN-OPENING";
+/// Tag for the folding challenges of a [`Fri`](super::fri::Fri) opening, over the
+/// commitment, the point, the value and the roots of the layers folded so far.
+pub const FRI_FOLDING_DST: &[u8] = b"POLY-COMMIT-BENCHES-V1-FRI-FOLDING";
+/// Tag for the query positions of the same opening, over everything the folding
+/// challenges are, the final constant and the query's index as 8 little-endian bytes.
+pub const FRI_QUERY_DST: &[u8] = b"POLY-COMMIT-BENCHES-V1-FRI-QUERY";
+/// Tag for the proximity test coefficients of a [`Ligero`](super::ligero::Ligero)
+/// opening, over the commitment and the row's index as 8 little-endian bytes.
+pub const LIGERO_ROW_DST: &[u8] = b"POLY-COMMIT-BENCHES-V1-LIGERO-ROW";
+/// Tag for the columns opened by the same opening, over the commitment, the point,
+/// the two combined rows and the column's index as 8 little-endian bytes.
+pub const LIGERO_COLUMN_DST: &[u8] = b"POLY-COMMIT-BENCHES-V1-LIGERO-COLUMN";
+
+pub trait ChallengeGen {
+    /// Maps `msg` to a field element under the tag `dst`, see the module docs.
+    fn hash_to_field<F: PrimeField>(dst: &[u8], msg: &[u8]) -> F {
+        let dst_len = u8::try_from(dst.len()).expect("Tags are at most 255 bytes");
+        let mut bytes = Vec::with_capacity(64);
+        for counter in [0u8, 1] {
+            let mut hasher = Sha256::new();
+            hasher.update([dst_len]);
+            hasher.update(dst);
+            hasher.update([counter]);
+            hasher.update(msg);
+            bytes.extend_from_slice(&hasher.finalize());
+        }
+        F::from_le_bytes_mod_order(&bytes)
+    }
+}
+
+/// The default SHA-256 challenges.
+pub struct Sha256ChallengeGen;
+
+impl ChallengeGen for Sha256ChallengeGen {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Fr;
+    use std::str::FromStr;
+
+    // Test vector for other implementations, on the BLS12-381 scalar field
+    #[test]
+    fn test_hash_to_field_vector() {
+        let challenge: Fr = Sha256ChallengeGen::hash_to_field(b"TEST-DST", b"abc");
+        let expected =
+            "28341476091484498532063571741753728213116766013148892074579567490636576714446";
+        assert_eq!(challenge, Fr::from_str(expected).unwrap());
+        assert_ne!(
+            challenge,
+            Sha256ChallengeGen::hash_to_field::<Fr>(b"OTHER-DST", b"abc")
+        );
+    }
+}
@@ -1,30 +1,93 @@
 use std::marker::PhantomData;
 
-use ark_ff::{FftField, UniformRand};
-use ark_poly::{domain::DomainCoeff, EvaluationDomain, Radix2EvaluationDomain};
-use rand::thread_rng;
+use ark_ff::{
+    biginteger::BigInteger256, FftField, FftParameters, Fp256, Fp256Parameters, FpParameters,
+    UniformRand,
+};
+use ark_poly::{
+    domain::DomainCoeff, EvaluationDomain, GeneralEvaluationDomain, MixedRadixEvaluationDomain,
+};
+use crate::{
+    bench_rng_for,
+    recovery::{first_known, Barycentric},
+    ErasureDecodeBench, ErasureEncodeBench, RecoveryError, RngTag,
+};
 
-use crate::ErasureEncodeBench;
+use super::representation::extend_evals_in_place;
 
 pub type Bls12_381ScalarEncBench = ArkEncFieldBench<ark_bls12_381::Fr, ark_bls12_381::Fr>;
 pub type Bls12_381G1EncBench = ArkEncFieldBench<ark_bls12_381::Fr, ark_bls12_381::G1Projective>;
 pub type Bn254ScalarEncBench = ArkEncFieldBench<ark_bn254::Fr, ark_bn254::Fr>;
+pub type Bn254MixedRadixScalarEncBench = ArkEncFieldBench<Bn254MixedRadixFr, Bn254MixedRadixFr>;
+
+/// The BN254 scalar field, with the subgroup of order `3^2` that `ark_bn254::Fr`
+/// doesn't declare, so there are domains of every size `2^a 3^b` with `b <= 2`.
+pub type Bn254MixedRadixFr = Fp256<Bn254MixedRadixFrParameters>;
+
+pub struct Bn254MixedRadixFrParameters;
+
+type Bn254FrParameters = ark_bn254::FrParameters;
+
+impl Fp256Parameters for Bn254MixedRadixFrParameters {}
+impl FftParameters for Bn254MixedRadixFrParameters {
+    type BigInt = BigInteger256;
+
+    const TWO_ADICITY: u32 = Bn254FrParameters::TWO_ADICITY;
+    const TWO_ADIC_ROOT_OF_UNITY: BigInteger256 = Bn254FrParameters::TWO_ADIC_ROOT_OF_UNITY;
+    const SMALL_SUBGROUP_BASE: Option<u32> = Some(3);
+    const SMALL_SUBGROUP_BASE_ADICITY: Option<u32> = Some(2);
+
+    // GENERATOR^((MODULUS - 1) / (2^28 3^2)) in Montgomery form. Its 9th power is
+    // TWO_ADIC_ROOT_OF_UNITY, so power of two domains are the same as over
+    // `ark_bn254::Fr`
+    #[rustfmt::skip]
+    const LARGE_SUBGROUP_ROOT_OF_UNITY: Option<BigInteger256> = Some(BigInteger256([
+        0xbc5be4f2ccf2b0bb,
+        0x8110aa5596b2ad36,
+        0x407546e6bbd9a38d,
+        0x15c508b295e63a97,
+    ]));
+}
+impl FpParameters for Bn254MixedRadixFrParameters {
+    const MODULUS: BigInteger256 = Bn254FrParameters::MODULUS;
+    const MODULUS_BITS: u32 = Bn254FrParameters::MODULUS_BITS;
+    const CAPACITY: u32 = Bn254FrParameters::CAPACITY;
+    const REPR_SHAVE_BITS: u32 = Bn254FrParameters::REPR_SHAVE_BITS;
+    const R: BigInteger256 = Bn254FrParameters::R;
+    const R2: BigInteger256 = Bn254FrParameters::R2;
+    const INV: u64 = Bn254FrParameters::INV;
+    const GENERATOR: BigInteger256 = Bn254FrParameters::GENERATOR;
+    const MODULUS_MINUS_ONE_DIV_TWO: BigInteger256 = Bn254FrParameters::MODULUS_MINUS_ONE_DIV_TWO;
+    const T: BigInteger256 = Bn254FrParameters::T;
+    const T_MINUS_ONE_DIV_TWO: BigInteger256 = Bn254FrParameters::T_MINUS_ONE_DIV_TWO;
+}
 
 pub struct ArkEncFieldBench<Fr, Dc>(PhantomData<(Fr, Dc)>);
 
 impl<Fr: FftField, Dc: DomainCoeff<Fr> + UniformRand> ErasureEncodeBench
     for ArkEncFieldBench<Fr, Dc>
 {
-    type Domain = Radix2EvaluationDomain<Fr>;
+    type Domain = GeneralEvaluationDomain<Fr>;
     type Point = Dc;
 
-    // Size should be a power of 2 here
+    // Other sizes than powers of 2 need a field with a small subgroup, such as
+    // `Bn254MixedRadixFr`. `GeneralEvaluationDomain::new` would round them up to a
+    // power of 2, so the mixed radix domain is made directly
     fn make_domain(size: usize) -> Self::Domain {
-        Radix2EvaluationDomain::new(size).expect("Failed to construct evaluation domain")
+        let domain = if size.is_power_of_two() {
+            GeneralEvaluationDomain::new(size)
+        } else {
+            MixedRadixEvaluationDomain::new(size).map(GeneralEvaluationDomain::MixedRadix)
+        };
+        match domain {
+            Some(domain) if domain.size() == size => domain,
+            _ => panic!("Failed to construct evaluation domain of size {}", size),
+        }
     }
 
     fn rand_points(size: usize) -> Vec<Self::Point> {
-        (0..size).map(|_| Dc::rand(&mut thread_rng())).collect()
+        let mut rng = bench_rng_for(RngTag::Inputs);
+        (0..size).map(|_| Dc::rand(&mut rng)).collect()
     }
 
     // `pts` must be the same size as `sub_domain`
@@ -35,23 +98,68 @@ impl<Fr: FftField, Dc: DomainCoeff<Fr> + UniformRand> ErasureEncodeBench
         sub_domain: &Self::Domain,
         big_domain: &Self::Domain,
     ) {
-        assert_eq!(sub_domain.size(), pts.len());
-        assert_eq!(sub_domain.size() % sub_domain.size(), 0); // Domain a must divide domain b
-        sub_domain.ifft_in_place(pts);
-        pts.resize(big_domain.size(), Dc::zero());
-        big_domain.fft_in_place(pts);
+        extend_evals_in_place(pts, sub_domain, big_domain);
+    }
+}
+
+impl<Fr: FftField, Dc: DomainCoeff<Fr> + UniformRand> ErasureDecodeBench
+    for ArkEncFieldBench<Fr, Dc>
+{
+    // Interpolates the first `sub_domain.size()` present points, see
+    // [`crate::recovery`], at each missing point of the sub domain
+    fn erasure_decode(
+        pts: &mut Vec<Self::Point>,
+        present: &[bool],
+        sub_domain: &Self::Domain,
+        big_domain: &Self::Domain,
+    ) -> Result<(), RecoveryError> {
+        let (n, m) = (sub_domain.size(), big_domain.size());
+        assert_eq!(m % n, 0, "The domain size must divide the larger domain size");
+        if let Some(&got) = [pts.len(), present.len()].iter().find(|&&len| len != m) {
+            return Err(RecoveryError::WrongLength { expected: m, got });
+        }
+        let points: Vec<Fr> = big_domain.elements().collect();
+        let values: Vec<Option<Dc>> =
+            pts.iter().zip(present).map(|(p, &k)| k.then_some(*p)).collect();
+        let known = first_known(&points, &values, n)?;
+        let interp = Barycentric::new(known.iter().map(|(x, _)| *x).collect(), |x| {
+            x.inverse().expect("Missing points aren't known points")
+        });
+
+        let scale = m / n;
+        *pts = (0..n)
+            .map(|i| {
+                if let Some(pt) = values[i * scale] {
+                    return pt;
+                }
+                let coeffs = interp.coeffs_at(points[i * scale]);
+                let mut sum = Dc::zero();
+                for ((_, yk), lk) in known.iter().zip(coeffs) {
+                    let mut term = **yk;
+                    term *= lk;
+                    sum += term;
+                }
+                sum
+            })
+            .collect();
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use ark_bls12_381::Fr;
-    use ark_ff::{UniformRand, Zero};
+    use ark_ff::{PrimeField, UniformRand};
     use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
-    use rand::thread_rng;
+    use ark_serialize::CanonicalSerialize;
+    use rand::{seq::index::sample, thread_rng};
+    use sha2::{Digest, Sha256};
 
     use super::*;
-    use crate::test_enc_works;
+    use crate::ark::representation::{coeffs_to_evals, evals_to_coeffs};
+    use crate::{
+        test_dec_works, test_enc_index_mapping, test_enc_non_dividing_domains, test_enc_works,
+    };
 
     #[test]
     fn test_interp_bench() {
@@ -60,18 +168,134 @@ mod tests {
         test_enc_works::<Bn254ScalarEncBench>();
     }
 
+    #[test]
+    fn test_decode() {
+        test_dec_works::<Bls12_381G1EncBench>();
+        test_dec_works::<Bls12_381ScalarEncBench>();
+        test_dec_works::<Bn254ScalarEncBench>();
+    }
+
+    #[test]
+    fn test_index_mapping() {
+        test_enc_index_mapping::<Bls12_381G1EncBench>();
+        test_enc_index_mapping::<Bls12_381ScalarEncBench>();
+        test_enc_index_mapping::<Bn254ScalarEncBench>();
+    }
+
+    #[test]
+    #[should_panic(expected = "must divide")]
+    fn test_non_dividing_domains() {
+        test_enc_non_dividing_domains::<Bls12_381ScalarEncBench>();
+    }
+
+    // Encodes points over a small domain and checks them against the interpolating
+    // polynomial evaluated directly at each big domain element, with the Lagrange
+    // coefficients of the small domain
+    fn check_low_degree_extension<F, Dc>(small_size: usize, big_size: usize)
+    where
+        F: FftField,
+        Dc: DomainCoeff<F> + UniformRand + PartialEq + std::fmt::Debug,
+    {
+        type B<F, Dc> = ArkEncFieldBench<F, Dc>;
+        let small = B::<F, Dc>::make_domain(small_size);
+        let big = B::<F, Dc>::make_domain(big_size);
+        let pts = B::<F, Dc>::rand_points(small_size);
+        let mut encoded = pts.clone();
+        B::<F, Dc>::erasure_encode(&mut encoded, &small, &big);
+        assert_eq!(encoded.len(), big.size());
+        for (x, y) in big.elements().zip(&encoded) {
+            let expected = pts
+                .iter()
+                .zip(small.evaluate_all_lagrange_coefficients(x))
+                .fold(Dc::zero(), |acc, (p, l)| {
+                    let mut term = *p;
+                    term *= l;
+                    acc + term
+                });
+            assert_eq!(*y, expected);
+        }
+    }
+
+    #[test]
+    fn test_encoding_is_low_degree_extension() {
+        check_low_degree_extension::<Fr, Fr>(8, 32);
+        check_low_degree_extension::<Fr, ark_bls12_381::G1Projective>(8, 32);
+        check_low_degree_extension::<ark_bn254::Fr, ark_bn254::Fr>(8, 32);
+        check_low_degree_extension::<Bn254MixedRadixFr, Bn254MixedRadixFr>(8, 32);
+        check_low_degree_extension::<Bn254MixedRadixFr, Bn254MixedRadixFr>(12, 36);
+    }
+
+    #[test]
+    fn test_mixed_radix_encoding() {
+        type B = Bn254MixedRadixScalarEncBench;
+        let (small, big) = (B::make_domain(12), B::make_domain(36));
+        assert_eq!((small.size(), big.size()), (12, 36));
+        let pts = B::rand_points(12);
+        let mut encoded = pts.clone();
+        B::erasure_encode(&mut encoded, &small, &big);
+        assert_eq!(encoded.len(), 36);
+        for (i, pt) in pts.iter().enumerate() {
+            assert_eq!(*pt, encoded[3 * i]);
+        }
+
+        let mut present = vec![false; 36];
+        for i in sample(&mut thread_rng(), 36, 12) {
+            present[i] = true;
+        }
+        B::erasure_decode(&mut encoded, &present, &small, &big).unwrap();
+        assert_eq!(encoded, pts);
+    }
+
+    // Power of 2 domains must be the same as over `ark_bn254::Fr`, for which the
+    // large subgroup root of unity has to be a 9th root of the 2-adic one
+    #[test]
+    fn test_mixed_radix_field_matches_bn254() {
+        for log_size in [1, 5, 28] {
+            let size = 1 << log_size;
+            let root = Bn254MixedRadixFr::get_root_of_unity(size).unwrap();
+            let expected = ark_bn254::Fr::get_root_of_unity(size).unwrap();
+            assert_eq!(root.into_repr(), expected.into_repr());
+        }
+        assert!(Bn254MixedRadixFr::get_root_of_unity(27).is_none());
+    }
+
+    fn encoding_digest<B: ErasureEncodeBench>(size: usize) -> String
+    where
+        B::Point: CanonicalSerialize,
+    {
+        let small = B::make_domain(size);
+        let big = B::make_domain(2 * size);
+        let mut pts = B::rand_points(size);
+        B::erasure_encode(&mut pts, &small, &big);
+        let mut bytes = Vec::new();
+        pts.serialize(&mut bytes).unwrap();
+        let mut hasher = Sha256::new();
+        hasher.update(&bytes);
+        hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    // Pins the encodings of the bench inputs, so that runs with `fast-field` are
+    // checked to give exactly the same output as the portable field arithmetic
+    #[test]
+    fn test_encoding_known_answer() {
+        assert_eq!(
+            encoding_digest::<Bls12_381ScalarEncBench>(64),
+            "c9e3f8f83af1a466eab18f43fe30414b5680664dc2fa7cfb72ac730a784ef612"
+        );
+        assert_eq!(
+            encoding_digest::<Bn254ScalarEncBench>(64),
+            "5556ab4ead2ceb68a5cea62804bec25aa2030c87dc25377a441134c5fa886bb8"
+        );
+    }
+
     #[test]
     fn test_domain_encoding() {
         let domain_4 = <Radix2EvaluationDomain<Fr>>::new(4).unwrap();
         let domain_8 = <Radix2EvaluationDomain<Fr>>::new(8).unwrap();
 
         let d4_evals: Vec<_> = (0..4).map(|_| Fr::rand(&mut thread_rng())).collect();
-        let d4_coeffs = domain_4.ifft(&d4_evals);
-        let mut d8_coeffs = vec![Fr::zero(); 8];
-        for (i, coeff) in d4_coeffs.iter().enumerate() {
-            d8_coeffs[i] = *coeff;
-        }
-        let d8_evals = domain_8.fft(&d8_coeffs);
+        let d4_coeffs = evals_to_coeffs(&d4_evals, &domain_4);
+        let d8_evals = coeffs_to_evals(&d4_coeffs, &domain_8);
         let size_scale = domain_8.size() / domain_4.size();
         for (j, d4_eval) in d4_evals.iter().enumerate() {
             assert_eq!(d4_eval, &d8_evals[size_scale * j]);
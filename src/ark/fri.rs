@@ -0,0 +1,344 @@
+//! A FRI based polynomial commitment: no trusted setup and no pairings, only
+//! Reed-Solomon codes and SHA-256.
+//!
+//! A polynomial `f` with `n` coefficients, `n` a power of two, is committed to by its
+//! evaluations over a domain `L` of size `blowup * n`, Merkle hashed in pairs
+//! `(f(x), f(-x))`. To show `f(z) = v`, the prover runs FRI on the quotient
+//! `q(X) = (f(X) - v) / (X - z)`, which is a polynomial of degree below `n` only if
+//! `f(z) = v`. The verifier never needs a commitment to `q`, since it can compute
+//! `q(x)` from `f(x)` at any queried `x`.
+//!
+//! Each FRI round folds `g` into `g'(x^2) = (g(x) + g(-x)) / 2 + a (g(x) - g(-x)) / 2x`
+//! for a challenge `a`, which halves both the domain and the degree bound, and commits
+//! to the result. After `log n` rounds `g'` is a constant, sent in the clear. The
+//! verifier then checks each round at random positions of `L`.
+use ark_ff::{batch_inversion, Field, PrimeField};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::CanonicalSerialize;
+
+use super::challenge::{ChallengeGen, Sha256ChallengeGen, FRI_FOLDING_DST, FRI_QUERY_DST};
+use super::merkle::{hash_leaf, Hash, MerkleTree};
+use super::representation::coeffs_to_evals;
+
+#[derive(Debug, Clone)]
+pub struct Fri<F: PrimeField> {
+    // The degree bound `n`, at least 2 so there is always a round
+    num_coeffs: usize,
+    num_queries: usize,
+    domain: Radix2EvaluationDomain<F>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commitment {
+    pub root: Hash,
+}
+
+/// The pairs `(g(x), g(-x))` a query opens in every round, with their Merkle paths.
+#[derive(Debug, Clone)]
+pub struct Query<F: PrimeField> {
+    pub pairs: Vec<(F, F)>,
+    pub paths: Vec<Vec<Hash>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Proof<F: PrimeField> {
+    /// Roots of the folded layers. The first layer is the commitment itself
+    pub layer_roots: Vec<Hash>,
+    /// The constant the last round folds to
+    pub final_value: F,
+    pub queries: Vec<Query<F>>,
+}
+
+impl<F: PrimeField> Proof<F> {
+    /// Size of the serialized proof in bytes
+    pub fn size_in_bytes(&self) -> usize {
+        let num_elems = 1 + 2 * self.queries.iter().map(|q| q.pairs.len()).sum::<usize>();
+        let num_hashes = self.layer_roots.len()
+            + self
+                .queries
+                .iter()
+                .flat_map(|q| &q.paths)
+                .map(Vec::len)
+                .sum::<usize>();
+        num_elems * F::one().serialized_size() + num_hashes * std::mem::size_of::<Hash>()
+    }
+}
+
+impl<F: PrimeField> Fri<F> {
+    /// Parameters for polynomials up to degree `max_degree`, evaluated over a domain
+    /// `blowup` times larger, with enough queries for `security_bits` bits of soundness.
+    pub fn new(max_degree: usize, blowup: usize, security_bits: usize) -> Self {
+        assert!(
+            blowup >= 2 && blowup.is_power_of_two(),
+            "Blowup must be a power of two, at least 2"
+        );
+        let num_coeffs = (max_degree + 1).next_power_of_two().max(2);
+        let domain = Radix2EvaluationDomain::new(blowup * num_coeffs)
+            .expect("Failed to make evaluation domain");
+        // A query catches a word far from the code with probability at least `delta`,
+        // the unique decoding radius
+        let delta = (1.0 - 1.0 / blowup as f64) / 2.0;
+        let num_queries = (security_bits as f64 / -(1.0 - delta).log2()).ceil() as usize;
+        Fri {
+            num_coeffs,
+            num_queries,
+            domain,
+        }
+    }
+
+    pub fn num_queries(&self) -> usize {
+        self.num_queries
+    }
+
+    // One round per halving of the degree bound
+    fn num_rounds(&self) -> usize {
+        self.num_coeffs.trailing_zeros() as usize
+    }
+
+    pub fn commit(&self, coeffs: &[F]) -> Commitment {
+        Commitment {
+            root: layer_tree(&self.encode(coeffs)).root(),
+        }
+    }
+
+    /// Opens `coeffs` at `point`, which must not be in the evaluation domain. The
+    /// evaluations are recomputed from `coeffs`, so this costs as much as a commitment
+    /// on top of the opening itself.
+    pub fn open(&self, coeffs: &[F], point: F) -> Proof<F> {
+        let evals = self.encode(coeffs);
+        let first_tree = layer_tree(&evals);
+        let value = coeffs.iter().rev().fold(F::zero(), |acc, c| acc * point + c);
+
+        let mut denominators: Vec<F> = self.domain.elements().map(|x| x - point).collect();
+        assert!(
+            denominators.iter().all(|d| !d.is_zero()),
+            "Opening point is in the evaluation domain"
+        );
+        batch_inversion(&mut denominators);
+        let quotient: Vec<F> = evals
+            .iter()
+            .zip(&denominators)
+            .map(|(e, d)| (*e - value) * d)
+            .collect();
+
+        let mut transcript = transcript_start(&first_tree.root(), point, value);
+        let mut layers = vec![quotient];
+        let mut trees = vec![first_tree];
+        let mut layer_roots = Vec::new();
+        let mut generator = self.domain.group_gen;
+        for round in 0..self.num_rounds() {
+            let alpha: F = Sha256ChallengeGen::hash_to_field(FRI_FOLDING_DST, &transcript);
+            let folded = fold_layer(&layers[round], generator, alpha);
+            generator.square_in_place();
+            if round + 1 < self.num_rounds() {
+                let tree = layer_tree(&folded);
+                transcript.extend_from_slice(&tree.root());
+                layer_roots.push(tree.root());
+                trees.push(tree);
+            }
+            layers.push(folded);
+        }
+        // The last fold is constant over its whole domain
+        let final_value = layers.pop().unwrap()[0];
+        write(&mut transcript, &final_value);
+
+        let queries = self
+            .query_positions(&transcript)
+            .into_iter()
+            .map(|position| {
+                let (pairs, paths) = layers
+                    .iter()
+                    .zip(&trees)
+                    .enumerate()
+                    .map(|(round, (layer, tree))| {
+                        let half = layer.len() / 2;
+                        let k = position % half;
+                        // The commitment opens `f`, the verifier derives `q` from it
+                        let pair = if round == 0 {
+                            (evals[k], evals[k + half])
+                        } else {
+                            (layer[k], layer[k + half])
+                        };
+                        (pair, tree.open(k))
+                    })
+                    .unzip();
+                Query { pairs, paths }
+            })
+            .collect();
+        Proof {
+            layer_roots,
+            final_value,
+            queries,
+        }
+    }
+
+    pub fn verify(&self, comm: &Commitment, point: F, value: F, proof: &Proof<F>) -> bool {
+        let num_rounds = self.num_rounds();
+        if proof.layer_roots.len() != num_rounds - 1
+            || proof.queries.len() != self.num_queries
+            || proof
+                .queries
+                .iter()
+                .any(|q| q.pairs.len() != num_rounds || q.paths.len() != num_rounds)
+        {
+            return false;
+        }
+
+        let mut transcript = transcript_start(&comm.root, point, value);
+        let mut alphas = Vec::with_capacity(num_rounds);
+        for round in 0..num_rounds {
+            alphas.push(Sha256ChallengeGen::hash_to_field::<F>(
+                FRI_FOLDING_DST,
+                &transcript,
+            ));
+            if let Some(root) = proof.layer_roots.get(round) {
+                transcript.extend_from_slice(root);
+            }
+        }
+        write(&mut transcript, &proof.final_value);
+        let roots: Vec<&Hash> = std::iter::once(&comm.root)
+            .chain(&proof.layer_roots)
+            .collect();
+
+        self.query_positions(&transcript)
+            .into_iter()
+            .zip(&proof.queries)
+            .all(|(position, query)| {
+                let mut generator = self.domain.group_gen;
+                let mut size = self.domain.size();
+                let mut expected = None;
+                for round in 0..num_rounds {
+                    let half = size / 2;
+                    let k = position % half;
+                    let (a, b) = query.pairs[round];
+                    let leaf = hash_pair(a, b);
+                    if !MerkleTree::verify(roots[round], &leaf, k, &query.paths[round]) {
+                        return false;
+                    }
+                    let x = generator.pow([k as u64]);
+                    let (a, b) = if round == 0 {
+                        match ((x - point).inverse(), (-x - point).inverse()) {
+                            (Some(da), Some(db)) => ((a - value) * da, (b - value) * db),
+                            _ => return false,
+                        }
+                    } else {
+                        (a, b)
+                    };
+                    // The previous round folded to the position in this one
+                    let opened = if position % size < half { a } else { b };
+                    if expected.is_some_and(|e| e != opened) {
+                        return false;
+                    }
+                    expected = Some(fold_pair(a, b, x, alphas[round]));
+                    generator.square_in_place();
+                    size = half;
+                }
+                expected == Some(proof.final_value)
+            })
+    }
+
+    fn encode(&self, coeffs: &[F]) -> Vec<F> {
+        assert!(
+            coeffs.len() <= self.num_coeffs,
+            "Polynomial is too large for these parameters"
+        );
+        coeffs_to_evals(coeffs, &self.domain)
+    }
+
+    fn query_positions(&self, transcript: &[u8]) -> Vec<usize> {
+        (0..self.num_queries)
+            .map(|i| {
+                let mut msg = transcript.to_vec();
+                msg.extend_from_slice(&(i as u64).to_le_bytes());
+                let c: F = Sha256ChallengeGen::hash_to_field(FRI_QUERY_DST, &msg);
+                // The domain size is a power of two, so this is uniform
+                (c.into_repr().as_ref()[0] % self.domain.size() as u64) as usize
+            })
+            .collect()
+    }
+}
+
+// `g'(x^2)` from `g(x)` and `g(-x)`
+fn fold_pair<F: Field>(a: F, b: F, x: F, alpha: F) -> F {
+    let two_inv = F::from(2u64).inverse().unwrap();
+    ((a + b) + alpha * (a - b) * x.inverse().unwrap()) * two_inv
+}
+
+// Folds the evaluations over the domain generated by `generator`, whose second half
+// is the negation of the first
+fn fold_layer<F: PrimeField>(layer: &[F], generator: F, alpha: F) -> Vec<F> {
+    let half = layer.len() / 2;
+    let mut x_invs: Vec<F> = std::iter::successors(Some(F::one()), |x| Some(*x * generator))
+        .take(half)
+        .collect();
+    batch_inversion(&mut x_invs);
+    let two_inv = F::from(2u64).inverse().unwrap();
+    (0..half)
+        .map(|k| {
+            let (a, b) = (layer[k], layer[k + half]);
+            ((a + b) + alpha * (a - b) * x_invs[k]) * two_inv
+        })
+        .collect()
+}
+
+fn hash_pair<F: PrimeField>(a: F, b: F) -> Hash {
+    let mut bytes = Vec::new();
+    write(&mut bytes, &a);
+    write(&mut bytes, &b);
+    hash_leaf(&bytes)
+}
+
+// Leaf `k` is the pair at `k` and `k + half`, which fold together
+fn layer_tree<F: PrimeField>(layer: &[F]) -> MerkleTree {
+    let half = layer.len() / 2;
+    MerkleTree::new((0..half).map(|k| hash_pair(layer[k], layer[k + half])).collect())
+}
+
+fn transcript_start<F: PrimeField>(root: &Hash, point: F, value: F) -> Vec<u8> {
+    let mut transcript = root.to_vec();
+    write(&mut transcript, &point);
+    write(&mut transcript, &value);
+    transcript
+}
+
+fn write<T: CanonicalSerialize>(bytes: &mut Vec<u8>, value: &T) {
+    value
+        .serialize(bytes)
+        .expect("Serializing to a Vec can't fail");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng;
+    use ark_bls12_381::Fr;
+    use ark_ff::{One, UniformRand};
+    use ark_poly::{univariate::DensePolynomial, Polynomial, UVPolynomial};
+
+    #[test]
+    fn test_open_verify() {
+        let rng = &mut test_rng();
+        let degree = 255;
+        let fri = Fri::<Fr>::new(degree, 4, 128);
+        let p = DensePolynomial::<Fr>::rand(degree, rng);
+        let comm = fri.commit(&p.coeffs);
+        let point = Fr::rand(rng);
+        let value = p.evaluate(&point);
+        let proof = fri.open(&p.coeffs, point);
+        assert!(fri.verify(&comm, point, value, &proof));
+        assert!(!fri.verify(&comm, point, value + Fr::one(), &proof));
+
+        let mut bad_pair = proof.clone();
+        bad_pair.queries[0].pairs[1].0 += Fr::one();
+        assert!(!fri.verify(&comm, point, value, &bad_pair));
+
+        let mut bad_final = proof.clone();
+        bad_final.final_value += Fr::one();
+        assert!(!fri.verify(&comm, point, value, &bad_final));
+
+        // Opening a different polynomial against the same commitment
+        let q = DensePolynomial::<Fr>::rand(degree, rng);
+        let q_proof = fri.open(&q.coeffs, point);
+        assert!(!fri.verify(&comm, point, q.evaluate(&point), &q_proof));
+    }
+}
@@ -0,0 +1,98 @@
+use std::marker::PhantomData;
+
+use ark_ff::PrimeField;
+use ark_poly::{univariate::DensePolynomial, Polynomial, UVPolynomial};
+use rand::rngs::StdRng;
+
+use crate::{bench_rng_for, PcBench, RngTag};
+
+use super::fri::{Commitment, Fri, Proof};
+
+pub type FriBls12_381Bench = FriBench<ark_bls12_381::Fr, 4>;
+pub type FriBn254Bench = FriBench<ark_bn254::Fr, 4>;
+
+const SECURITY_BITS: usize = 128;
+
+/// FRI with the polynomial evaluated over a domain `BLOWUP` times its degree bound
+pub struct FriBench<F, const BLOWUP: usize>(PhantomData<F>);
+
+impl<F: PrimeField, const BLOWUP: usize> PcBench for FriBench<F, BLOWUP> {
+    // There's no trusted setup, only randomness for the polynomials
+    type Setup = StdRng;
+    type Trimmed = Fri<F>;
+    type Poly = DensePolynomial<F>;
+    type Point = F;
+    type Eval = F;
+    type Commit = Commitment;
+    type Proof = Proof<F>;
+    // Opening re-encodes the polynomial
+    const OPEN_COMPLEXITY: &'static str = "O(n log n)";
+    // A Merkle path of O(log n) hashes per round and query
+    const PROOF_SIZE_CLASS: &'static str = "O(log^2 n)";
+
+    fn setup(_max_degree: usize) -> Self::Setup {
+        bench_rng_for(RngTag::Setup)
+    }
+
+    fn trim(_: &Self::Setup, supported_degree: usize) -> Self::Trimmed {
+        Fri::new(supported_degree, BLOWUP, SECURITY_BITS)
+    }
+
+    fn rand_poly(s: &mut Self::Setup, d: usize) -> (Self::Poly, Self::Point, Self::Eval) {
+        let poly = Self::Poly::rand(d, s);
+        let pt = F::rand(s);
+        let eval = poly.evaluate(&pt);
+        (poly, pt, eval)
+    }
+
+    fn bytes_per_elem() -> usize {
+        F::one().serialized_size() - 1
+    }
+
+    fn proof_size(proof: &Self::Proof) -> usize {
+        proof.size_in_bytes()
+    }
+
+    fn commit(t: &Self::Trimmed, _: &mut Self::Setup, p: &Self::Poly) -> Self::Commit {
+        t.commit(&p.coeffs)
+    }
+
+    fn open(
+        t: &Self::Trimmed,
+        _: &mut Self::Setup,
+        p: &Self::Poly,
+        pt: &Self::Point,
+    ) -> Self::Proof {
+        t.open(&p.coeffs, *pt)
+    }
+
+    fn verify(
+        t: &Self::Trimmed,
+        c: &Self::Commit,
+        proof: &Self::Proof,
+        value: &Self::Eval,
+        pt: &Self::Point,
+    ) -> bool {
+        t.verify(c, *pt, *value, proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_works;
+
+    #[test]
+    fn bls12_381_works() {
+        test_works::<super::FriBls12_381Bench>();
+    }
+
+    #[test]
+    fn bn254_works() {
+        test_works::<super::FriBn254Bench>();
+    }
+
+    #[test]
+    fn blowup_8_works() {
+        test_works::<super::FriBench<ark_bls12_381::Fr, 8>>();
+    }
+}
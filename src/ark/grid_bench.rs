@@ -1,26 +1,108 @@
-use std::marker::PhantomData;
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+};
 
 use ark_bls12_381::Bls12_381;
-use ark_ec::{PairingEngine, AffineCurve};
-use ark_ff::UniformRand;
+use ark_ec::{msm::VariableBaseMSM, PairingEngine, AffineCurve, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField, UniformRand};
 use ark_poly::{
-    domain::DomainCoeff, univariate::DensePolynomial, EvaluationDomain, Radix2EvaluationDomain,
+    domain::DomainCoeff, univariate::DensePolynomial, EvaluationDomain, Polynomial,
+    Radix2EvaluationDomain, UVPolynomial,
 };
-use ark_serialize::CanonicalSerialize;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 use ark_std::Zero;
-use crate::test_rng;
-use rand::distributions::uniform::SampleRange;
+use crate::{bench_rng_for, RngTag};
+use rand::{distributions::uniform::SampleRange, RngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{recovery::reconstruct_rows, GridBench, MatrixCommit, RecoveryError};
+
+use super::challenge::{
+    ChallengeGen, Sha256ChallengeGen, EXTENSION_POINT_DST, EXTENSION_ROW_DST, GRID_COLUMN_DST,
+};
+use super::kzg::{Commitment, Powers, Proof, VerifierKey, KZG10};
+use super::representation::{extend_evals_in_place, DomainPoint};
+
+/// A `rows` by `cols` grid stored column-major in a single buffer, so gathering a
+/// column for erasure coding is a contiguous read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlatGrid<F> {
+    data: Vec<F>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<F: Clone> FlatGrid<F> {
+    /// A grid with every entry set to `fill`
+    pub fn new(rows: usize, cols: usize, fill: F) -> Self {
+        Self {
+            data: vec![fill; rows * cols],
+            rows,
+            cols,
+        }
+    }
+
+    /// Builds a grid from a list of equal length rows
+    pub fn from_rows(rows: &[Vec<F>]) -> Self {
+        let cols = rows.first().map_or(0, |r| r.len());
+        assert!(rows.iter().all(|r| r.len() == cols), "Rows must have equal lengths");
+        let data = (0..cols)
+            .flat_map(|j| rows.iter().map(move |r| r[j].clone()))
+            .collect();
+        Self {
+            data,
+            rows: rows.len(),
+            cols,
+        }
+    }
+
+    pub fn to_rows(&self) -> Vec<Vec<F>> {
+        (0..self.rows)
+            .map(|i| (0..self.cols).map(|j| self[(i, j)].clone()).collect())
+            .collect()
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn column(&self, j: usize) -> &[F] {
+        &self.data[j * self.rows..(j + 1) * self.rows]
+    }
+
+    pub fn column_mut(&mut self, j: usize) -> &mut [F] {
+        &mut self.data[j * self.rows..(j + 1) * self.rows]
+    }
+}
 
-use crate::GridBench;
+impl<F> Index<(usize, usize)> for FlatGrid<F> {
+    type Output = F;
 
-use super::kzg::{Powers, KZG10};
+    fn index(&self, (i, j): (usize, usize)) -> &F {
+        &self.data[j * self.rows + i]
+    }
+}
+
+impl<F> IndexMut<(usize, usize)> for FlatGrid<F> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut F {
+        &mut self.data[j * self.rows + i]
+    }
+}
 
 pub struct KzgGridBench<E>(PhantomData<E>);
 pub type KzgGridBenchBls12_381 = KzgGridBench<Bls12_381>;
 
 #[derive(Debug, Clone)]
 pub struct Setup<E: PairingEngine> {
-    powers: Powers<E>,
+    powers: Powers<'static, E>,
+    vk: VerifierKey<E>,
     domain_n: Radix2EvaluationDomain<E::Fr>,
     domain_2n: Radix2EvaluationDomain<E::Fr>,
 }
@@ -33,40 +115,221 @@ where
     E::G1Projective: DomainCoeff<E::Fr>,
 {
     type Setup = Setup<E>;
-    type Grid = Vec<Vec<E::Fr>>;
+    type Point = E::Fr;
+    type Grid = FlatGrid<E::Fr>;
     type ExtendedGrid = Vec<Vec<E::Fr>>;
     type Commits = Vec<E::G1Projective>;
     type Opens = Vec<E::G1Projective>;
 
+    fn required_srs_degree(grid_size: usize) -> usize {
+        // Rows are committed as polynomials of degree `grid_size - 1`. The extended rows
+        // are interpolated from the commitments to the original rows, and rows extended
+        // by `extend_grid_2d` are still evaluations of those polynomials, so they never
+        // need a larger SRS.
+        grid_size.saturating_sub(1)
+    }
+
     fn do_setup(size: usize) -> Self::Setup {
-        let up = <KZGFor<E>>::setup(size - 1, &mut test_rng()).unwrap();
-        let (powers, _) = <KZGFor<E>>::trim(&up, size - 1).unwrap();
+        let degree = Self::required_srs_degree(size);
+        let up = <KZGFor<E>>::setup(degree, &mut bench_rng_for(RngTag::Setup)).unwrap();
+        let (powers, vk) = <KZGFor<E>>::trim(&up, degree).unwrap();
         Self::Setup {
             powers,
+            vk,
             domain_n: Radix2EvaluationDomain::new(size).expect("Failed to make n domain"),
             domain_2n: Radix2EvaluationDomain::new(2 * size).expect("Failed to make 2n domain"),
         }
     }
 
     fn rand_grid(size: usize) -> Self::Grid {
-        let mut grid = vec![vec![Zero::zero(); size]; size];
-        for i in 0..size {
-            for j in 0..size {
-                grid[i][j] = UniformRand::rand(&mut test_rng());
+        let mut rng = bench_rng_for(RngTag::Inputs);
+        let mut grid = FlatGrid::new(size, size, Zero::zero());
+        for j in 0..size {
+            for i in 0..size {
+                grid[(i, j)] = UniformRand::rand(&mut rng);
             }
         }
         grid
     }
 
     fn extend_grid(s: &Self::Setup, g: &Self::Grid) -> Self::ExtendedGrid {
+        let mut eg = vec![vec![Zero::zero(); g.cols()]; 2 * g.rows()];
+        Self::extend_columns_into(s, g, &mut eg);
+        eg
+    }
+
+    fn extend_rows(s: &Self::Setup, g: &mut Self::ExtendedGrid) {
+        // Rows are in coefficient form, so extending one is evaluating it over `domain_2n`
+        ark_std::cfg_iter_mut!(g).for_each(|row| {
+            row.resize(s.domain_2n.size(), Zero::zero());
+            s.domain_2n.fft_in_place(row);
+        });
+    }
+
+    fn extend_grid_2d(s: &Self::Setup, g: &Self::Grid) -> Self::ExtendedGrid {
+        // Rows get their extended capacity up front, so extending them doesn't reallocate
+        let mut eg: Vec<Vec<E::Fr>> = (0..2 * g.rows())
+            .map(|_| {
+                let mut row = Vec::with_capacity(2 * g.cols());
+                row.resize(g.cols(), Zero::zero());
+                row
+            })
+            .collect();
+        Self::extend_columns_into(s, g, &mut eg);
+        Self::extend_rows(s, &mut eg);
+        eg
+    }
+
+    fn make_commits(s: &Self::Setup, g: &Self::ExtendedGrid) -> Self::Commits {
+        // Collect commits to original rows
+        let mut commits: Vec<_> = <KZGFor<E>>::batch_commit(&s.powers, &Self::row_polys(s, g))
+            .expect("Failed to commit")
+            .into_iter()
+            .map(|c| c.0.into_projective())
+            .collect();
+        // Extend commits
+        extend_evals_in_place(&mut commits, &s.domain_n, &s.domain_2n);
+        commits
+    }
+
+    fn open_column(s: &Self::Setup, g: &Self::ExtendedGrid) -> Self::Opens {
+        let n = g.len() / 2;
+        let j = (0..n).sample_single(&mut bench_rng_for(RngTag::Sample));
+        Self::open_column_at(s, &Self::row_polys(s, g), j)
+    }
+
+    fn open_columns(s: &Self::Setup, g: &Self::ExtendedGrid, cols: &[usize]) -> Vec<Self::Opens> {
+        let polys = Self::row_polys(s, g);
+        cols.iter()
+            .map(|&j| Self::open_column_at(s, &polys, j))
+            .collect()
+    }
+
+    fn verify_column(
+        s: &Self::Setup,
+        commits: &Self::Commits,
+        opens: &Self::Opens,
+        g: &Self::ExtendedGrid,
+        col: usize,
+    ) -> bool {
+        if col >= s.domain_n.size() {
+            return false;
+        }
+        let values = Self::column_values(s, g, col);
+        Self::verify_column_by_index(s, commits, opens, &values, col)
+    }
+
+    fn reconstruct(
+        s: &Self::Setup,
+        partial: &[Option<Vec<E::Fr>>],
+    ) -> Result<Self::ExtendedGrid, RecoveryError> {
+        let points: Vec<_> = s.domain_2n.elements().collect();
+        reconstruct_rows(&points, partial, s.domain_n.size(), |x| {
+            x.inverse().expect("Inverting zero")
+        })
+    }
+
+    fn bytes_per_elem() -> usize {
+        E::Fr::zero().serialized_size() - 1
+    }
+}
+
+impl<E> MatrixCommit for KzgGridBench<E>
+where
+    E: PairingEngine,
+    E::G1Projective: DomainCoeff<E::Fr>,
+{
+    type Elem = E::Fr;
+    type CellProof = Proof<E>;
+
+    fn cell(s: &Self::Setup, g: &Self::ExtendedGrid, row: usize, col: usize) -> E::Fr {
+        Self::row_value(s, &g[row], col)
+    }
+
+    fn open_cell(s: &Self::Setup, g: &Self::ExtendedGrid, row: usize, col: usize) -> Proof<E> {
+        // The extension is linear, so extended rows hold the coefficients of the
+        // polynomials their interpolated commitments commit to
+        let poly = Self::row_poly(s, &g[row]);
+        let pt = DomainPoint::new(&s.domain_2n, col);
+        <KZGFor<E>>::open_at_domain_point(&s.powers, &poly, pt).expect("Failed to open")
+    }
+
+    fn verify_cell(
+        s: &Self::Setup,
+        commits: &Self::Commits,
+        row: usize,
+        col: usize,
+        value: &E::Fr,
+        proof: &Proof<E>,
+    ) -> bool {
+        let (commit, pt) = match (commits.get(row), DomainPoint::try_new(&s.domain_2n, col)) {
+            (Some(commit), Some(pt)) => (commit, pt),
+            _ => return false,
+        };
+        <KZGFor<E>>::check_at_domain_point(
+            &s.vk,
+            &Commitment(commit.into_affine()),
+            pt,
+            *value,
+            proof,
+        )
+        .expect("Failed to check")
+    }
+}
+
+/// Rows of the grid are committed in coefficient form, and column `j` is opened at
+/// `domain_n.element(j)`, i.e. columns are indexed in natural order. Data laid out
+/// in bit-reversed order has to be permuted with
+/// [`bit_reverse_permutation`](super::kzg::bit_reverse_permutation) first.
+///
+/// Rows extended by [`GridBench::extend_grid_2d`] hold the evaluations of the same
+/// polynomials over `domain_2n` instead, so column `j` of the original grid is their
+/// entry `2j`. [`MatrixCommit`] cells index `domain_2n` for either kind of row.
+impl<E> KzgGridBench<E>
+where
+    E: PairingEngine,
+    E::G1Projective: DomainCoeff<E::Fr>,
+{
+    /// Suggests a `(rows, cols)` grid shape, both powers of two, that holds `data_bytes`
+    /// of data at [`GridBench::bytes_per_elem`] bytes per element for this curve.
+    ///
+    /// Each extended row is a commitment in the header and each sampled column is one
+    /// cell and one proof per extended row, so the shape is kept as square as possible
+    /// to keep both small. There are at least `target_columns` columns, so that many
+    /// distinct columns can be sampled.
+    pub fn suggest_dimensions(data_bytes: usize, target_columns: usize) -> (usize, usize) {
+        let num_elems = data_bytes.div_ceil(Self::bytes_per_elem()).max(1);
+        let square = ((num_elems as f64).sqrt().ceil() as usize).next_power_of_two();
+        let cols = square.max(target_columns.next_power_of_two());
+        let rows = num_elems.div_ceil(cols).next_power_of_two();
+        (rows, cols)
+    }
+
+    /// Writes the extension of each column of `g` into `eg`, which has `2n` rows
+    fn extend_columns_into(s: &Setup<E>, g: &FlatGrid<E::Fr>, eg: &mut [Vec<E::Fr>]) {
+        // for each column
+        for j in 0..g.cols() {
+            // the column is already contiguous
+            let mut col = g.column(j).to_vec();
+            // erasure encode
+            extend_evals_in_place(&mut col, &s.domain_n, &s.domain_2n);
+            // copy into extended grid
+            for (row, x) in eg.iter_mut().zip(col) {
+                row[j] = x;
+            }
+        }
+    }
+
+    /// [`GridBench::extend_grid`] for a grid stored as a list of rows, which has to
+    /// gather each column with a strided read. Kept to benchmark against [`FlatGrid`].
+    pub fn extend_nested_grid(s: &Setup<E>, g: &[Vec<E::Fr>]) -> Vec<Vec<E::Fr>> {
         let mut eg = vec![vec![Zero::zero(); g.len()]; 2 * g.len()];
         // for each column
         for j in 0..g.len() {
             // collect into a vec
             let mut col = (0..g.len()).map(|i| g[i][j]).collect::<Vec<_>>();
             // erasure encode
-            s.domain_n.ifft_in_place(&mut col);
-            s.domain_2n.fft_in_place(&mut col);
+            extend_evals_in_place(&mut col, &s.domain_n, &s.domain_2n);
             // copy into extended grid
             for i in 0..col.len() {
                 eg[i][j] = col[i];
@@ -75,51 +338,866 @@ where
         eg
     }
 
-    fn make_commits(s: &Self::Setup, g: &Self::ExtendedGrid) -> Self::Commits {
-        let mut commits = Vec::new();
-        // Collect commits to original rows
-        for i in 0..g.len() / 2 {
-            let c = <KZGFor<E>>::commit(
-                &s.powers,
-                &DensePolynomial {
-                    coeffs: g[2 * i].clone(), //TODO: rewrite KZG api to bypass clone
-                },
-            )
-            .expect("Failed to commit");
-            commits.push(c.0.into_projective());
-        }
-        // Extend commits
-        s.domain_n.ifft_in_place(&mut commits);
-        s.domain_2n.fft_in_place(&mut commits);
-        commits
+    /// The extended row commitments of [`GridBench::make_commits`] in affine form,
+    /// converting each commitment on its own: to affine after its MSM, back to
+    /// projective for the extension and to affine again at the end. Kept to benchmark
+    /// against [`Self::make_commits_projective`].
+    pub fn make_commits_affine(s: &Setup<E>, g: &[Vec<E::Fr>]) -> Vec<E::G1Affine> {
+        let mut commits: Vec<_> = <KZGFor<E>>::batch_commit(&s.powers, &Self::row_polys(s, g))
+            .expect("Failed to commit")
+            .into_iter()
+            .map(|c| c.0.into_projective())
+            .collect();
+        extend_evals_in_place(&mut commits, &s.domain_n, &s.domain_2n);
+        commits.iter().map(|c| c.into_affine()).collect()
     }
 
-    fn open_column(s: &Self::Setup, g: &Self::ExtendedGrid) -> Self::Opens {
-        let n = g.len() / 2;
-        // Collect underlying polys
-        let polys: Vec<_> = (0..n)
-            .map(|i| DensePolynomial {
-                coeffs: g[2 * i].clone(),
+    /// Same as [`Self::make_commits_affine`], but the commitments stay projective from
+    /// the MSMs through the extension and are normalized in one batch, which shares a
+    /// single field inversion between all of them. The MSMs run in parallel like
+    /// [`KZG10::batch_commit`], so only the normalization differs.
+    pub fn make_commits_projective(s: &Setup<E>, g: &[Vec<E::Fr>]) -> Vec<E::G1Affine> {
+        let polys = Self::row_polys(s, g);
+        let mut commits: Vec<E::G1Projective> = ark_std::cfg_iter!(polys)
+            .map(|p| {
+                let scalars: Vec<_> = p.coeffs.iter().map(|c| c.into_repr()).collect();
+                VariableBaseMSM::multi_scalar_mul(&s.powers.powers_of_g, &scalars)
             })
             .collect();
-        let j = (0..n).sample_single(&mut test_rng());
-        let pt = s.domain_n.element(j);
+        extend_evals_in_place(&mut commits, &s.domain_n, &s.domain_2n);
+        E::G1Projective::batch_normalization_into_affine(&commits)
+    }
+
+    /// Checks that `values[i]` is the evaluation of the row committed in `commits[i]`
+    /// at the point of column `col`, for every row.
+    pub fn verify_column_by_index(
+        s: &Setup<E>,
+        commits: &[E::G1Projective],
+        opens: &[E::G1Projective],
+        values: &[E::Fr],
+        col: usize,
+    ) -> bool {
+        let pt = match DomainPoint::try_new(&s.domain_n, col) {
+            Some(pt) => pt,
+            None => return false,
+        };
+        commits.len() == values.len()
+            && opens.len() == values.len()
+            && commits
+                .iter()
+                .zip(opens.iter())
+                .zip(values.iter())
+                .all(|((c, w), value)| {
+                    <KZGFor<E>>::check_at_domain_point(
+                        &s.vk,
+                        &Commitment(c.into_affine()),
+                        pt,
+                        *value,
+                        &Proof { w: w.into_affine() },
+                    )
+                    .expect("Failed to check")
+                })
+    }
+
+    /// Checks the openings `opens[k]` of every row at column `cols[k]` against `g`,
+    /// like [`GridBench::verify_column`] for each column, with one pairing product.
+    /// This is the grid analog of [`KZG10::batch_check`]: each row check
+    /// `e(c_i - v_{ik} g + z_k w_{ik}, h) == e(w_{ik}, \beta h)` is scaled by a random
+    /// 128-bit `r_{ik}` and they're all summed, so the sum only holds by chance unless
+    /// every check does.
+    pub fn verify_columns_batched<R: RngCore>(
+        s: &Setup<E>,
+        commits: &[E::G1Projective],
+        opens: &[Vec<E::G1Projective>],
+        g: &[Vec<E::Fr>],
+        cols: &[usize],
+        rng: &mut R,
+    ) -> bool {
+        if g.len() != commits.len()
+            || opens.len() != cols.len()
+            || opens.iter().any(|o| o.len() != commits.len())
+            || cols.iter().any(|&col| col >= s.domain_n.size())
+        {
+            return false;
+        }
+        let commits = E::G1Projective::batch_normalization_into_affine(commits);
+        let opens: Vec<_> = opens.iter().flatten().copied().collect();
+        let opens = E::G1Projective::batch_normalization_into_affine(&opens);
+
+        // Instead of scaling each commitment and g in each check, accumulate their
+        // coefficients and do one MSM at the end, as in `batch_check`
+        let mut commit_scalars = vec![E::Fr::zero(); commits.len()];
+        let mut g_multiplier = E::Fr::zero();
+        let mut point_scalars = Vec::with_capacity(opens.len());
+        let mut w_scalars = Vec::with_capacity(opens.len());
+        for &col in cols {
+            let pt = s.domain_n.element(col);
+            for (c, v) in commit_scalars.iter_mut().zip(Self::column_values(s, g, col)) {
+                let r = E::Fr::from(u128::rand(rng));
+                *c += r;
+                g_multiplier += r * v;
+                point_scalars.push((r * pt).into_repr());
+                w_scalars.push(r.into_repr());
+            }
+        }
+        let commit_scalars: Vec<_> = commit_scalars.iter().map(|c| c.into_repr()).collect();
+        let total_c = VariableBaseMSM::multi_scalar_mul(&commits, &commit_scalars)
+            + VariableBaseMSM::multi_scalar_mul(&opens, &point_scalars)
+            - s.vk.g.mul(g_multiplier);
+        let total_w = VariableBaseMSM::multi_scalar_mul(&opens, &w_scalars);
+
+        let points = E::G1Projective::batch_normalization_into_affine(&[total_c, -total_w]);
+        E::product_of_pairings(&[
+            (points[0].into(), s.vk.prepared_h.clone()),
+            (points[1].into(), s.vk.prepared_beta_h.clone()),
+        ])
+        .is_one()
+    }
+
+    /// Serializes a DAS sample, the row commitments and the openings `opens[k]` of every
+    /// row at column `cols[k]`, into one blob for posting to a data layer. It holds the
+    /// grid size, then the commitments, the column indices and the openings, each as
+    /// an arkworks `Vec` (a `u64` length then the compressed elements). Read it back
+    /// with [`Self::import_sample`].
+    pub fn export_sample(
+        s: &Setup<E>,
+        commits: &[E::G1Projective],
+        opens: &[Vec<E::G1Projective>],
+        cols: &[usize],
+    ) -> Vec<u8> {
+        let commits = E::G1Projective::batch_normalization_into_affine(commits);
+        let opens: Vec<_> = opens
+            .iter()
+            .map(|o| E::G1Projective::batch_normalization_into_affine(o))
+            .collect();
+        let mut bytes = Vec::new();
+        s.domain_n
+            .size()
+            .serialize(&mut bytes)
+            .and_then(|_| commits.serialize(&mut bytes))
+            .and_then(|_| cols.serialize(&mut bytes))
+            .and_then(|_| opens.serialize(&mut bytes))
+            .expect("Serializing to a Vec can't fail");
+        bytes
+    }
+
+    /// Reads a sample written by [`Self::export_sample`]. A truncated blob, or one with
+    /// bytes left over, is an error.
+    pub fn import_sample(mut bytes: &[u8]) -> Result<GridSample<E>, SerializationError> {
+        let size = usize::deserialize(&mut bytes)?;
+        let commits = Vec::<E::G1Affine>::deserialize(&mut bytes)?;
+        let cols = Vec::<usize>::deserialize(&mut bytes)?;
+        let opens = Vec::<Vec<E::G1Affine>>::deserialize(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(GridSample {
+            size,
+            commits: commits.iter().map(|c| c.into_projective()).collect(),
+            cols,
+            opens: opens
+                .iter()
+                .map(|o| o.iter().map(|w| w.into_projective()).collect())
+                .collect(),
+        })
+    }
+
+    /// Checks every column opening of an imported `sample` against `g`, like
+    /// [`GridBench::verify_column`], after checking the sample is for a grid of the
+    /// size of `s`.
+    pub fn verify_imported_sample(
+        s: &Setup<E>,
+        sample: &GridSample<E>,
+        g: &[Vec<E::Fr>],
+    ) -> bool {
+        let n = s.domain_n.size();
+        sample.size == n
+            && sample.opens.len() == sample.cols.len()
+            && sample.opens.iter().zip(sample.cols.iter()).all(|(o, &col)| {
+                col < n
+                    && Self::verify_column_by_index(
+                        s,
+                        &sample.commits,
+                        o,
+                        &Self::column_values(s, g, col),
+                        col,
+                    )
+            })
+    }
+
+    /// The values [`GridBench::verify_column`] checks the openings of column `col`
+    /// against, i.e. each row of `g` evaluated at the point of column `col`
+    pub fn column_values(s: &Setup<E>, g: &[Vec<E::Fr>], col: usize) -> Vec<E::Fr> {
+        // `\omega_n^j = \omega_{2n}^{2j}`
+        g.iter().map(|row| Self::row_value(s, row, 2 * col)).collect()
+    }
+
+    /// Whether `row` was extended by [`GridBench::extend_rows`], i.e. holds evaluations
+    /// over `domain_2n` rather than coefficients
+    fn is_extended_row(s: &Setup<E>, row: &[E::Fr]) -> bool {
+        row.len() == s.domain_2n.size()
+    }
+
+    /// The row polynomial evaluated at `domain_2n.element(col)`
+    fn row_value(s: &Setup<E>, row: &[E::Fr], col: usize) -> E::Fr {
+        if Self::is_extended_row(s, row) {
+            return row[col];
+        }
+        let pt = s.domain_2n.element(col);
+        row.iter()
+            .rev()
+            .fold(E::Fr::zero(), |acc, coeff| acc * pt + coeff)
+    }
+
+    /// The polynomial underlying `row`, in either form
+    fn row_poly(s: &Setup<E>, row: &[E::Fr]) -> DensePolynomial<E::Fr> {
+        if Self::is_extended_row(s, row) {
+            DensePolynomial::from_coefficients_vec(s.domain_2n.ifft(row))
+        } else {
+            DensePolynomial {
+                coeffs: row.to_vec(),
+            }
+        }
+    }
+
+    /// The polynomials underlying the original rows of `g`
+    fn row_polys(s: &Setup<E>, g: &[Vec<E::Fr>]) -> Vec<DensePolynomial<E::Fr>> {
+        (0..g.len() / 2).map(|i| Self::row_poly(s, &g[2 * i])).collect()
+    }
+
+    /// Opens every row of the extended grid at column `j`
+    fn open_column_at(
+        s: &Setup<E>,
+        polys: &[DensePolynomial<E::Fr>],
+        j: usize,
+    ) -> Vec<E::G1Projective> {
+        let pt = DomainPoint::new(&s.domain_n, j);
         let mut col_opens = Vec::new();
         // for each row
-        for i in 0..n {
+        for poly in polys {
             // open at (row, column)
-            let open = <KZGFor<E>>::open(&s.powers, &polys[i], pt)
-                .expect("Failed to open");
+            let open =
+                <KZGFor<E>>::open_at_domain_point(&s.powers, poly, pt).expect("Failed to open");
             col_opens.push(open.w.into_projective());
         }
         // fft to get all opens
-        s.domain_n.ifft_in_place(&mut col_opens);
-        s.domain_2n.fft_in_place(&mut col_opens);
-        // copy in to bigger opens matrix
+        extend_evals_in_place(&mut col_opens, &s.domain_n, &s.domain_2n);
         col_opens
     }
 
-    fn bytes_per_elem() -> usize {
-        E::Fr::zero().serialized_size() - 1
+    /// Proves that the rows of `extended` are the erasure extension of the rows of
+    /// `original`, i.e. that each column of `extended` is the extension of the same
+    /// column of `original`, see [`Self::verify_extension`].
+    pub fn prove_extension(
+        s: &Setup<E>,
+        original: &FlatGrid<E::Fr>,
+        extended: &[Vec<E::Fr>],
+    ) -> ExtensionProof<E> {
+        let original_rows = original.to_rows();
+        let original_commits: Vec<_> = original_rows
+            .iter()
+            .map(|row| Self::commit_row(s, row))
+            .collect();
+        let extended_commits: Vec<_> = extended
+            .iter()
+            .map(|row| Self::commit_row(s, row))
+            .collect();
+
+        let (row_challenge, point) = extension_challenges(&original_commits, &extended_commits);
+        let weights = s.domain_2n.evaluate_all_lagrange_coefficients(row_challenge);
+        let mut combined = vec![E::Fr::zero(); original.cols()];
+        for (row, w) in extended.iter().zip(weights) {
+            for (c, x) in combined.iter_mut().zip(row) {
+                *c += w * x;
+            }
+        }
+        let combined = DensePolynomial { coeffs: combined };
+        ExtensionProof {
+            value: combined.evaluate(&point),
+            proof: <KZGFor<E>>::open(&s.powers, &combined, point).expect("Failed to open"),
+            extended_commits,
+        }
+    }
+
+    /// Checks a proof from [`Self::prove_extension`] against the commitments to the
+    /// rows of the original grid. Every column of a correct extension has degree less
+    /// than `n` over `domain_2n`, so interpolating the original rows over `domain_n`
+    /// and the extended rows over `domain_2n` at a random row challenge gives the same
+    /// row. Both combinations must open to `value` at a random point with the same
+    /// proof, which costs two MSMs of the row commitments instead of the FFTs. A KZG
+    /// check fixes the commitment given the proof, value and point, so that's the same
+    /// as the combinations being equal and one of them passing a single pairing product.
+    ///
+    /// The extended data still has to match `proof.extended_commits`, e.g. through
+    /// [`MatrixCommit::verify_cell`] on sampled cells.
+    pub fn verify_extension(
+        s: &Setup<E>,
+        original_commits: &[Commitment<E>],
+        proof: &ExtensionProof<E>,
+    ) -> bool {
+        if original_commits.len() != s.domain_n.size()
+            || proof.extended_commits.len() != s.domain_2n.size()
+        {
+            return false;
+        }
+        let (row_challenge, point) =
+            extension_challenges(original_commits, &proof.extended_commits);
+        let combine = |commits: &[Commitment<E>], weights: Vec<E::Fr>| {
+            let bases: Vec<_> = commits.iter().map(|c| c.0).collect();
+            let scalars: Vec<_> = weights.iter().map(|w| w.into_repr()).collect();
+            Commitment(VariableBaseMSM::multi_scalar_mul(&bases, &scalars).into_affine())
+        };
+        let original = combine(
+            original_commits,
+            s.domain_n.evaluate_all_lagrange_coefficients(row_challenge),
+        );
+        let extended = combine(
+            &proof.extended_commits,
+            s.domain_2n.evaluate_all_lagrange_coefficients(row_challenge),
+        );
+        original == extended
+            && <KZGFor<E>>::check_prepared(&s.vk, &original, point, proof.value, &proof.proof)
+                .expect("Failed to check")
+    }
+
+    fn commit_row(s: &Setup<E>, row: &[E::Fr]) -> Commitment<E> {
+        let poly = Self::row_poly(s, row);
+        <KZGFor<E>>::commit(&s.powers, &poly).expect("Failed to commit")
+    }
+}
+
+/// Proof from [`KzgGridBench::prove_extension`]
+#[derive(Debug, Clone)]
+pub struct ExtensionProof<E: PairingEngine> {
+    /// Commitments to the rows of the extended grid
+    pub extended_commits: Vec<Commitment<E>>,
+    /// The row at the row challenge, evaluated at the opening point
+    pub value: E::Fr,
+    pub proof: Proof<E>,
+}
+
+/// A DAS sample read by [`KzgGridBench::import_sample`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridSample<E: PairingEngine> {
+    /// Size of the original grid
+    pub size: usize,
+    /// Commitments to the rows of the extended grid
+    pub commits: Vec<E::G1Projective>,
+    /// The sampled columns
+    pub cols: Vec<usize>,
+    /// `opens[k]` opens every row at column `cols[k]`
+    pub opens: Vec<Vec<E::G1Projective>>,
+}
+
+/// Verifies column openings like [`KzgGridBench::verify_column_by_index`], for a
+/// sampling client that checks the same columns of many grids over one domain.
+///
+/// Each row check is rearranged as `e(c - v g, h) * e(-w, \beta h - \omega^j h) == 1`.
+/// The second G2 element only depends on the column, so it's computed and prepared
+/// the first time column `j` is verified and reused afterwards. The rows are combined
+/// with the powers of a challenge `r`, so a whole column is checked with the single
+/// multi-Miller loop and final exponentiation of
+/// `e(\sum r^i (c_i - v_i g), h) * e(-\sum r^i w_i, \beta h - \omega^j h) == 1`.
+pub struct GridVerifyContext<E: PairingEngine> {
+    vk: VerifierKey<E>,
+    domain: Radix2EvaluationDomain<E::Fr>,
+    columns: HashMap<usize, E::G2Prepared>,
+}
+
+impl<E: PairingEngine> GridVerifyContext<E> {
+    pub fn new(s: &Setup<E>) -> Self {
+        GridVerifyContext {
+            vk: s.vk.clone(),
+            domain: s.domain_n,
+            columns: HashMap::new(),
+        }
+    }
+
+    /// Number of columns with a cached G2 element
+    pub fn num_cached(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Checks that `values[i]` is the evaluation of the row committed in `commits[i]`
+    /// at the point of column `col`, for every row.
+    pub fn verify_column(
+        &mut self,
+        commits: &[E::G1Projective],
+        opens: &[E::G1Projective],
+        values: &[E::Fr],
+        col: usize,
+    ) -> bool {
+        if commits.len() != values.len()
+            || opens.len() != values.len()
+            || col >= self.domain.size()
+        {
+            return false;
+        }
+        let (vk, domain) = (&self.vk, &self.domain);
+        let shifted_beta_h = self.columns.entry(col).or_insert_with(|| {
+            let pt = domain.element(col);
+            (vk.beta_h.into_projective() - vk.h.mul(pt))
+                .into_affine()
+                .into()
+        });
+
+        let points = E::G1Projective::batch_normalization_into_affine(&[commits, opens].concat());
+        let (commits, opens) = points.split_at(values.len());
+        let mut bytes = Vec::new();
+        commits
+            .serialize(&mut bytes)
+            .and_then(|_| opens.serialize(&mut bytes))
+            .and_then(|_| values.serialize(&mut bytes))
+            .and_then(|_| (col as u64).serialize(&mut bytes))
+            .expect("Serializing to a Vec can't fail");
+        let r: E::Fr = Sha256ChallengeGen::hash_to_field(GRID_COLUMN_DST, &bytes);
+        let powers: Vec<E::Fr> = std::iter::successors(Some(E::Fr::one()), |x| Some(*x * r))
+            .take(values.len())
+            .collect();
+        let value: E::Fr = powers.iter().zip(values).map(|(p, v)| *p * v).sum();
+        let scalars: Vec<_> = powers.iter().map(|p| p.into_repr()).collect();
+
+        let c = VariableBaseMSM::multi_scalar_mul(commits, &scalars)
+            - self.vk.g.mul(value.into_repr());
+        let w = VariableBaseMSM::multi_scalar_mul(opens, &scalars);
+        let pair = E::G1Projective::batch_normalization_into_affine(&[c, -w]);
+        E::product_of_pairings(&[
+            (pair[0].into(), self.vk.prepared_h.clone()),
+            (pair[1].into(), shifted_beta_h.clone()),
+        ])
+        .is_one()
+    }
+}
+
+fn extension_challenges<E: PairingEngine>(
+    original_commits: &[Commitment<E>],
+    extended_commits: &[Commitment<E>],
+) -> (E::Fr, E::Fr) {
+    let mut bytes = Vec::new();
+    original_commits
+        .serialize(&mut bytes)
+        .and_then(|_| extended_commits.serialize(&mut bytes))
+        .expect("Serializing to a Vec can't fail");
+    let row_challenge: E::Fr = Sha256ChallengeGen::hash_to_field(EXTENSION_ROW_DST, &bytes);
+    let mut bytes = Vec::new();
+    row_challenge
+        .serialize(&mut bytes)
+        .expect("Serializing to a Vec can't fail");
+    let point = Sha256ChallengeGen::hash_to_field(EXTENSION_POINT_DST, &bytes);
+    (row_challenge, point)
+}
+
+/// KZG commitments to a bivariate polynomial `f(x, y)` given by its evaluations
+/// `grid[i][j] = f(\omega_x^j, \omega_y^i)` over `domain_x` and `domain_y`.
+///
+/// Each row `f(x, \omega_y^i)` is committed on its own. A commitment to `f(x, y)` for
+/// any fixed `y` is then the combination of the row commitments with the Lagrange
+/// coefficients `L_i(y)` over `domain_y`, so a point `(x, y)` is opened with a single
+/// KZG proof of that univariate polynomial at `x`.
+pub struct BivariateKzg<E: PairingEngine> {
+    powers: Powers<'static, E>,
+    vk: VerifierKey<E>,
+}
+
+impl<E: PairingEngine> BivariateKzg<E> {
+    /// Supports rows of up to `max_degree + 1` evaluations
+    pub fn new<R: RngCore>(max_degree: usize, rng: &mut R) -> Self {
+        let up = <KZGFor<E>>::setup(max_degree, rng).expect("Failed setup");
+        let (powers, vk) = <KZGFor<E>>::trim(&up, max_degree).expect("Failed trim");
+        BivariateKzg { powers, vk }
+    }
+
+    pub fn commit_bivariate<D: EvaluationDomain<E::Fr>>(
+        &self,
+        grid: &[Vec<E::Fr>],
+        domain_x: &D,
+        domain_y: &D,
+    ) -> Vec<Commitment<E>> {
+        assert_eq!(grid.len(), domain_y.size(), "One row per element of domain_y");
+        grid.iter()
+            .map(|row| {
+                <KZGFor<E>>::commit_lagrange(&self.powers, domain_x, row).expect("Failed commit")
+            })
+            .collect()
+    }
+
+    /// Returns `f(x, y)` and a proof of it
+    pub fn open_point<D: EvaluationDomain<E::Fr>>(
+        &self,
+        grid: &[Vec<E::Fr>],
+        domain_x: &D,
+        domain_y: &D,
+        x: E::Fr,
+        y: E::Fr,
+    ) -> (E::Fr, Proof<E>) {
+        // Evaluations of f(., y) over domain_x
+        let lagrange_y = domain_y.evaluate_all_lagrange_coefficients(y);
+        let mut row_at_y = vec![E::Fr::zero(); domain_x.size()];
+        for (row, l) in grid.iter().zip(lagrange_y.iter()) {
+            for (r, e) in row_at_y.iter_mut().zip(row.iter()) {
+                *r += *l * e;
+            }
+        }
+        let value = domain_x
+            .evaluate_all_lagrange_coefficients(x)
+            .iter()
+            .zip(row_at_y.iter())
+            .map(|(l, e)| *l * e)
+            .sum();
+        let proof = <KZGFor<E>>::open_from_evals(&self.powers, &row_at_y, domain_x, x)
+            .expect("Failed open");
+        (value, proof)
+    }
+
+    pub fn check_point<D: EvaluationDomain<E::Fr>>(
+        &self,
+        commits: &[Commitment<E>],
+        domain_y: &D,
+        x: E::Fr,
+        y: E::Fr,
+        value: E::Fr,
+        proof: &Proof<E>,
+    ) -> bool {
+        let lagrange_y: Vec<_> = domain_y
+            .evaluate_all_lagrange_coefficients(y)
+            .into_iter()
+            .map(|l| l.into_repr())
+            .collect();
+        let bases: Vec<_> = commits.iter().map(|c| c.0).collect();
+        let comm_at_y = VariableBaseMSM::multi_scalar_mul(&bases, &lagrange_y);
+        <KZGFor<E>>::check(&self.vk, &Commitment(comm_at_y.into()), x, value, proof)
+            .expect("Failed check")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ark::kzg::bit_reverse_permutation;
+    use crate::ark::representation::evals_to_coeffs;
+    use crate::{test_grid_reconstruct_works, test_matrix_works, test_rng};
+    use ark_bls12_381::Fr;
+    use ark_poly::UVPolynomial;
+    use rand::seq::index::sample;
+
+    #[test]
+    fn test_matrix_commit() {
+        test_matrix_works::<KzgGridBenchBls12_381>();
+    }
+
+    #[test]
+    fn test_grid_reconstruct() {
+        test_grid_reconstruct_works::<KzgGridBenchBls12_381>();
+    }
+
+    #[test]
+    fn test_open_columns_verify() {
+        type B = KzgGridBenchBls12_381;
+        let size = 16;
+        let s = B::do_setup(size);
+        let eg = B::extend_grid(&s, &B::rand_grid(size));
+        let commits = B::make_commits(&s, &eg);
+        let cols = [0, 3, 7, size - 1];
+        let opens = B::open_columns(&s, &eg, &cols);
+        assert_eq!(opens.len(), cols.len());
+        for (o, &j) in opens.iter().zip(cols.iter()) {
+            assert_eq!(o.len(), 2 * size);
+            assert!(B::verify_column(&s, &commits, o, &eg, j));
+            assert!(!B::verify_column(&s, &commits, o, &eg, (j + 1) % size));
+        }
+        assert!(!B::verify_column(&s, &commits, &opens[0], &eg, size));
+        assert!(!B::verify_column(&s, &commits, &opens[0], &eg, 2 * size));
+        assert!(!B::verify_column_by_index(&s, &commits, &opens[0], &[], size));
+    }
+
+    #[test]
+    fn test_verify_columns_batched() {
+        type B = KzgGridBenchBls12_381;
+        let rng = &mut test_rng();
+        let size = 16;
+        let s = B::do_setup(size);
+        let mut eg = B::extend_grid(&s, &B::rand_grid(size));
+        let commits = B::make_commits(&s, &eg);
+        let cols = sample(rng, size, 8).into_vec();
+        let opens = B::open_columns(&s, &eg, &cols);
+        assert!(B::verify_columns_batched(&s, &commits, &opens, &eg, &cols, rng));
+
+        let mut shifted = cols.clone();
+        shifted[0] = (shifted[0] + 1) % size;
+        assert!(!B::verify_columns_batched(&s, &commits, &opens, &eg, &shifted, rng));
+        assert!(!B::verify_columns_batched(&s, &commits, &opens[1..], &eg, &cols, rng));
+        shifted[0] = 2 * size;
+        assert!(!B::verify_columns_batched(&s, &commits, &opens, &eg, &shifted, rng));
+
+        eg[5][3] += Fr::one();
+        assert!(!B::verify_columns_batched(&s, &commits, &opens, &eg, &cols, rng));
+    }
+
+    #[test]
+    fn test_export_import_sample() {
+        type B = KzgGridBenchBls12_381;
+        let size = 16;
+        let s = B::do_setup(size);
+        let mut eg = B::extend_grid(&s, &B::rand_grid(size));
+        let commits = B::make_commits(&s, &eg);
+        let cols = [1, 6, size - 1];
+        let opens = B::open_columns(&s, &eg, &cols);
+
+        let blob = B::export_sample(&s, &commits, &opens, &cols);
+        let sample = B::import_sample(&blob).unwrap();
+        assert_eq!(sample.size, size);
+        assert_eq!(sample.commits, commits);
+        assert_eq!(sample.cols, cols);
+        assert_eq!(sample.opens, opens);
+        assert!(B::verify_imported_sample(&s, &sample, &eg));
+
+        for len in [0, 1, blob.len() / 2, blob.len() - 1] {
+            assert!(B::import_sample(&blob[..len]).is_err());
+        }
+        let mut padded = blob.clone();
+        padded.push(0);
+        assert!(B::import_sample(&padded).is_err());
+
+        let smaller = B::do_setup(size / 2);
+        assert!(!B::verify_imported_sample(&smaller, &sample, &eg));
+        eg[2][cols[0]] += Fr::one();
+        assert!(!B::verify_imported_sample(&s, &sample, &eg));
+    }
+
+    // What a light client samples: random cells, each checked against the commitment
+    // to its extended row alone
+    #[test]
+    fn test_random_cells_verify() {
+        type B = KzgGridBenchBls12_381;
+        let rng = &mut test_rng();
+        let size = 16;
+        let s = B::do_setup(size);
+        let eg = B::extend_grid(&s, &B::rand_grid(size));
+        let commits = B::make_commits(&s, &eg);
+        for _ in 0..8 {
+            let row = (0..2 * size).sample_single(rng);
+            let col = (0..size).sample_single(rng);
+            let value = B::cell(&s, &eg, row, col);
+            let proof = B::open_cell(&s, &eg, row, col);
+            assert!(B::verify_cell(&s, &commits, row, col, &value, &proof));
+            assert!(!B::verify_cell(&s, &commits, row, col, &(value + Fr::one()), &proof));
+            let other_row = (row + 1) % (2 * size);
+            assert!(!B::verify_cell(&s, &commits, other_row, col, &value, &proof));
+        }
+    }
+
+    #[test]
+    fn test_commit_storage_variants_match() {
+        type B = KzgGridBenchBls12_381;
+        let size = 16;
+        let s = B::do_setup(size);
+        let eg = B::extend_grid(&s, &B::rand_grid(size));
+        let affine = B::make_commits_affine(&s, &eg);
+        assert_eq!(affine, B::make_commits_projective(&s, &eg));
+        let commits: Vec<_> = B::make_commits(&s, &eg).iter().map(|c| c.into_affine()).collect();
+        assert_eq!(affine, commits);
+    }
+
+    #[test]
+    fn test_extend_grid_2d() {
+        type B = KzgGridBenchBls12_381;
+        let size = 16;
+        let s = B::do_setup(size);
+        let grid = B::rand_grid(size);
+        let eg = B::extend_grid_2d(&s, &grid);
+        assert_eq!(eg.len(), 2 * size);
+        assert!(eg.iter().all(|row| row.len() == 2 * size));
+        let columns_only = B::extend_grid(&s, &grid);
+        // Original cell `(i, j)` is row `2i` of the column extension at `\omega_n^j`
+        for i in 0..size {
+            for j in 0..size {
+                assert_eq!(eg[2 * i][2 * j], B::column_values(&s, &columns_only, j)[2 * i]);
+            }
+        }
+        let mut columns_first = columns_only.clone();
+        B::extend_rows(&s, &mut columns_first);
+        assert_eq!(eg, columns_first);
+        for row in 0..2 * size {
+            for col in 0..2 * size {
+                assert_eq!(B::cell(&s, &eg, row, col), B::cell(&s, &columns_only, row, col));
+            }
+        }
+
+        let commits = B::make_commits(&s, &eg);
+        assert_eq!(commits.len(), 2 * size);
+        let rows: Vec<_> = eg.iter().map(|row| B::commit_row(&s, row).0).collect();
+        let commits: Vec<_> = commits.iter().map(|c| c.into_affine()).collect();
+        assert_eq!(commits, rows);
+        assert_eq!(commits, B::make_commits_affine(&s, &columns_only));
+
+        // Cells of the row extension open and verify like the original ones
+        for (row, col) in [(0, 1), (3, 2 * size - 1)] {
+            let value = B::cell(&s, &eg, row, col);
+            let proof = B::open_cell(&s, &eg, row, col);
+            let c: Vec<_> = commits.iter().map(|c| c.into_projective()).collect();
+            assert!(B::verify_cell(&s, &c, row, col, &value, &proof));
+        }
+    }
+
+    #[test]
+    fn test_grid_verify_context() {
+        type B = KzgGridBenchBls12_381;
+        let size = 16;
+        let s = B::do_setup(size);
+        let eg = B::extend_grid(&s, &B::rand_grid(size));
+        let commits = B::make_commits(&s, &eg);
+        let cols = [0, 3, 7, size - 1];
+        let opens = B::open_columns(&s, &eg, &cols);
+        let mut ctx = GridVerifyContext::new(&s);
+
+        // Sample the same columns repeatedly, as for several grids
+        for _ in 0..3 {
+            for (o, &j) in opens.iter().zip(cols.iter()) {
+                let values = B::column_values(&s, &eg, j);
+                assert!(B::verify_column_by_index(&s, &commits, o, &values, j));
+                assert!(ctx.verify_column(&commits, o, &values, j));
+
+                let mut bad = values.clone();
+                bad[1] += Fr::one();
+                assert!(!B::verify_column_by_index(&s, &commits, o, &bad, j));
+                assert!(!ctx.verify_column(&commits, o, &bad, j));
+                assert!(!ctx.verify_column(&commits, o, &values, (j + 1) % size));
+                assert!(!ctx.verify_column(&commits, o, &values[1..], j));
+                assert!(!ctx.verify_column(&commits, o, &values, size));
+            }
+        }
+        assert_eq!(ctx.num_cached(), cols.len() + 3);
+    }
+
+    #[test]
+    fn test_suggest_dimensions() {
+        fn check<E>(data_bytes: usize, target_columns: usize)
+        where
+            E: PairingEngine,
+            E::G1Projective: DomainCoeff<E::Fr>,
+        {
+            let (rows, cols) = KzgGridBench::<E>::suggest_dimensions(data_bytes, target_columns);
+            assert!(rows.is_power_of_two() && cols.is_power_of_two());
+            assert!(cols >= target_columns);
+            assert!(rows * cols * KzgGridBench::<E>::bytes_per_elem() >= data_bytes);
+        }
+        for (data_bytes, target_columns) in [(1, 1), (1 << 20, 16), (1000, 64), (123457, 3)] {
+            check::<Bls12_381>(data_bytes, target_columns);
+            check::<ark_bn254::Bn254>(data_bytes, target_columns);
+        }
+        // 2^20 elements of 31 bytes fit a square grid
+        assert_eq!(
+            KzgGridBenchBls12_381::suggest_dimensions(31 << 20, 16),
+            (1024, 1024)
+        );
+    }
+
+    #[test]
+    fn test_extension_proof() {
+        type B = KzgGridBenchBls12_381;
+        let size = 16;
+        let s = B::do_setup(size);
+        let grid = B::rand_grid(size);
+        let original_commits: Vec<_> = grid
+            .to_rows()
+            .iter()
+            .map(|row| B::commit_row(&s, row))
+            .collect();
+
+        let eg = B::extend_grid(&s, &grid);
+        let proof = B::prove_extension(&s, &grid, &eg);
+        assert!(B::verify_extension(&s, &original_commits, &proof));
+
+        // Column 5 extended from the wrong data
+        let mut bad = eg.clone();
+        for row in bad.iter_mut().skip(1).step_by(2) {
+            row[5] += Fr::from(1u64);
+        }
+        let bad_proof = B::prove_extension(&s, &grid, &bad);
+        assert!(!B::verify_extension(&s, &original_commits, &bad_proof));
+    }
+
+    #[test]
+    fn test_required_srs_degree_commits_full_grid() {
+        type B = KzgGridBenchBls12_381;
+        for size in [4, 16, 64] {
+            let s = B::do_setup(size);
+            assert_eq!(s.powers.size(), B::required_srs_degree(size) + 1);
+            let eg = B::extend_grid(&s, &B::rand_grid(size));
+            let commits = B::make_commits(&s, &eg);
+            assert_eq!(commits.len(), 2 * size);
+            let opens = B::open_columns(&s, &eg, &[size - 1]);
+            assert!(B::verify_column(&s, &commits, &opens[0], &eg, size - 1));
+        }
+        assert_eq!(B::required_srs_degree(0), 0);
+    }
+
+    #[test]
+    fn test_bivariate_open_point() {
+        let rng = &mut test_rng();
+        let (n_x, n_y) = (16, 8);
+        let domain_x = Radix2EvaluationDomain::<Fr>::new(n_x).unwrap();
+        let domain_y = Radix2EvaluationDomain::<Fr>::new(n_y).unwrap();
+        let kzg = BivariateKzg::<Bls12_381>::new(n_x - 1, rng);
+        let grid: Vec<Vec<Fr>> = (0..n_y)
+            .map(|_| (0..n_x).map(|_| Fr::rand(rng)).collect())
+            .collect();
+        let commits = kzg.commit_bivariate(&grid, &domain_x, &domain_y);
+        assert_eq!(commits.len(), n_y);
+
+        let (x, y) = (Fr::rand(rng), Fr::rand(rng));
+        let (value, proof) = kzg.open_point(&grid, &domain_x, &domain_y, x, y);
+        assert!(kzg.check_point(&commits, &domain_y, x, y, value, &proof));
+        assert!(!kzg.check_point(&commits, &domain_y, x, y, value + Fr::from(1u64), &proof));
+        assert!(!kzg.check_point(&commits, &domain_y, y, x, value, &proof));
+
+        // On the grid itself, f(\omega_x^j, \omega_y^i) is the entry
+        let (x, y) = (domain_x.element(5), domain_y.element(3));
+        let (value, proof) = kzg.open_point(&grid, &domain_x, &domain_y, x, y);
+        assert_eq!(value, grid[3][5]);
+        assert!(kzg.check_point(&commits, &domain_y, x, y, value, &proof));
+    }
+
+    #[test]
+    fn test_flat_grid_extension_matches_nested() {
+        type B = KzgGridBenchBls12_381;
+        let size = 16;
+        let s = B::do_setup(size);
+        let grid = B::rand_grid(size);
+        let rows = grid.to_rows();
+        assert_eq!(FlatGrid::from_rows(&rows), grid);
+        for j in 0..size {
+            assert_eq!(grid.column(j), rows.iter().map(|r| r[j]).collect::<Vec<_>>());
+        }
+        assert_eq!(B::extend_grid(&s, &grid), B::extend_nested_grid(&s, &rows));
+    }
+
+    #[test]
+    fn test_bitreversed_commit_index_mapping() {
+        type B = KzgGridBenchBls12_381;
+        let size = 16;
+        let s = B::do_setup(size);
+        let evals: Vec<Fr> = (0..size).map(|_| Fr::rand(&mut test_rng())).collect();
+        let perm = bit_reverse_permutation(size);
+        let bitreversed: Vec<_> = perm.iter().map(|&i| evals[i]).collect();
+
+        let c = <KZGFor<Bls12_381>>::commit_lagrange(&s.powers, &s.domain_n, &evals).unwrap();
+        let c_rev =
+            <KZGFor<Bls12_381>>::commit_lagrange_bitreversed(&s.powers, &s.domain_n, &bitreversed)
+                .unwrap();
+        assert_eq!(c, c_rev);
+
+        let poly = DensePolynomial::from_coefficients_vec(evals_to_coeffs(&evals, &s.domain_n));
+        let commits = [c.0.into_projective()];
+        for j in 0..size {
+            let open = <KZGFor<Bls12_381>>::open(&s.powers, &poly, s.domain_n.element(j)).unwrap();
+            let opens = [open.w.into_projective()];
+            // Column `j` holds `evals[j]`, which sits at `perm[j]` in bit-reversed order
+            assert!(B::verify_column_by_index(&s, &commits, &opens, &[evals[j]], j));
+            assert!(B::verify_column_by_index(&s, &commits, &opens, &[bitreversed[perm[j]]], j));
+            if perm[j] != j {
+                assert!(!B::verify_column_by_index(&s, &commits, &opens, &[bitreversed[j]], j));
+            }
+        }
     }
 }
@@ -0,0 +1,39 @@
+//! The inner product argument from `ark_poly_commit::ipa_pc`, a transparent setup
+//! scheme to compare against the KZG variants. It runs over Pallas, whose scalar field
+//! is as large as those of the pairing curves.
+use ark_pallas::Affine;
+use ark_poly_commit::ipa_pc::InnerProductArgPC;
+use blake2::Blake2s;
+
+use super::pc_impl::{ArkPcBench, ArkScheme, Poly};
+
+type Fr = ark_pallas::Fr;
+
+// The digest only hashes the setup seed to curve points and derives the
+// Fiat-Shamir challenges, so the one the ark_poly_commit tests use will do
+pub type IpaPallas = InnerProductArgPC<Affine, Blake2s, Poly<Fr>>;
+
+impl ArkScheme for IpaPallas {
+    // Folding the committer key and the coefficients in each of the log n rounds
+    const OPEN_COMPLEXITY: &'static str = "O(n)";
+    // Two group elements per round
+    const PROOF_SIZE_CLASS: &'static str = "O(log n)";
+}
+
+pub type IpaPallasBench = ArkPcBench<Fr, IpaPallas>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_works, PcBench};
+
+    #[test]
+    fn test_pallas_ipa() {
+        test_works::<IpaPallasBench>();
+    }
+
+    #[test]
+    fn test_pallas_ser_size() {
+        assert_eq!(IpaPallasBench::bytes_per_elem(), 31);
+    }
+}
@@ -1,13 +1,24 @@
-use std::collections::BTreeMap;
+use std::{borrow::Cow, collections::BTreeMap};
 
-use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
-use ark_ff::{PrimeField, ToBytes, ToConstraintField, Zero, Field};
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{
+    fields::{Fp2, Fp2Parameters},
+    Field, One, PrimeField, ToBytes, ToConstraintField, UniformRand, Zero,
+};
+use ark_poly::{univariate::DensePolynomial, Polynomial};
 use ark_poly_commit::{PCUniversalParams, PCCommitment, PCProof};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 use ark_std::{
     io::{Read, Write},
     ops::AddAssign,
+    rand::RngCore,
+};
+
+use super::Error;
+use crate::ark::challenge::{
+    ChallengeGen, Sha256ChallengeGen, CONSTANT_TIME_MASK_DST, SRS_CHECK_DST,
 };
+use crate::ark::merkle::Hash;
 
 #[derive(Clone, Debug)]
 pub struct UniversalParams<E: PairingEngine> {
@@ -19,12 +30,182 @@ pub struct UniversalParams<E: PairingEngine> {
     pub h: E::G2Affine,
     /// \beta times the above generator of G2.
     pub beta_h: E::G2Affine,
+    /// Group elements of the form `{ \beta^i H }`, where `i` ranges from 0 to the number of G2
+    /// powers asked for at setup, at least `H` and `\beta H`.
+    pub powers_of_h: Vec<E::G2Affine>,
+    /// Group elements `\beta^{D - b} H` for each degree bound `b` asked for at setup,
+    /// where `D` is the maximum degree. Used to check degree-bound proofs.
+    pub degree_bound_powers_of_h: BTreeMap<usize, E::G2Affine>,
     /// The generator of G2, prepared for use in pairings.
     pub prepared_h: E::G2Prepared,
     /// \beta times the above generator of G2, prepared for use in pairings.
     pub prepared_beta_h: E::G2Prepared,
 }
 
+impl<E: PairingEngine> UniversalParams<E> {
+    /// Assembles parameters from the powers `\beta^i G` and the G2 points `h` and
+    /// `\beta h` of an external powers-of-tau ceremony, e.g. the Ethereum KZG ceremony.
+    /// `G` must be the standard generator. Ceremonies have no `\gamma G` powers, so
+    /// these are made from a fresh `\gamma` drawn from `rng`, as `\gamma` times each
+    /// power of `G`.
+    ///
+    /// Whoever assembles the parameters knows `\gamma`, so hiding commitments made
+    /// with them only give their guarantees against third parties: knowing `\gamma`
+    /// lets the assembler move a constant between a polynomial and its blinding
+    /// polynomial, and open the commitment to another value. Plain commitments only
+    /// rest on the ceremony.
+    /// [`import_eth_kzg`](Self::import_eth_kzg) goes through this constructor.
+    ///
+    /// Only `h` and `\beta h` are known in G2, so `powers_of_h` holds just those two
+    /// and no degree bounds are supported.
+    pub fn from_ptau_g1_g2<R: RngCore>(
+        g1_powers: &[E::G1Affine],
+        h: E::G2Affine,
+        beta_h: E::G2Affine,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        let g = g1_powers
+            .first()
+            .ok_or_else(|| Error::InvalidTrustedSetup("No G1 powers".to_string()))?;
+        if *g != E::G1Affine::prime_subgroup_generator() {
+            return Err(Error::InvalidTrustedSetup(
+                "The first G1 power is not the generator".to_string(),
+            ));
+        }
+
+        let gamma = E::Fr::rand(rng).into_repr();
+        let powers_of_gamma_g: Vec<_> = g1_powers.iter().map(|p| p.mul(gamma)).collect();
+        let powers_of_gamma_g = E::G1Projective::batch_normalization_into_affine(&powers_of_gamma_g)
+            .into_iter()
+            .enumerate()
+            .collect();
+
+        Ok(Self {
+            powers_of_g: g1_powers.to_vec(),
+            powers_of_gamma_g,
+            h,
+            beta_h,
+            powers_of_h: vec![h, beta_h],
+            degree_bound_powers_of_h: BTreeMap::new(),
+            prepared_h: h.into(),
+            prepared_beta_h: beta_h.into(),
+        })
+    }
+
+    /// Drops the powers beyond those `KZG10::setup` makes for `max_degree`.
+    pub fn truncate(&mut self, max_degree: usize) {
+        self.powers_of_g.truncate(max_degree + 1);
+        // `setup` makes one more power of gamma g than of g
+        self.powers_of_gamma_g.retain(|&i, _| i <= max_degree + 1);
+        self.powers_of_h.truncate(max_degree + 1);
+        // `\beta^{D - b} h` is for the old `D`, so it can't check bounds for the new one
+        self.degree_bound_powers_of_h.clear();
+    }
+
+    /// Checks that the parameters are made of powers of the `\beta` in `\beta h`:
+    /// - no point is the identity, and the prepared points match `h` and `\beta h`;
+    /// - the powers of `g`, and each two consecutive powers of `\gamma g`, satisfy
+    ///   `e(\beta^{i+1} g, h) == e(\beta^i g, \beta h)`;
+    /// - `powers_of_h` starts with `h` and `\beta h`, and its consecutive powers satisfy
+    ///   `e(g, \beta^{i+1} h) == e(\beta g, \beta^i h)`;
+    /// - each degree bound power satisfies `e(g, \beta^{D - b} h) == e(\beta^{D - b} g, h)`.
+    ///
+    /// All of these equations are combined with the powers of a challenge `r` drawn
+    /// from the parameters, so this costs a few MSMs and one pairing product.
+    pub fn verify_well_formed(&self) -> Result<(), Error> {
+        let invalid = |msg: &str| Err(Error::InvalidTrustedSetup(msg.to_string()));
+        if self.powers_of_g.len() < 2 {
+            return invalid("Fewer than two G1 powers");
+        }
+        let g1_zero = self
+            .powers_of_g
+            .iter()
+            .chain(self.powers_of_gamma_g.values())
+            .any(|p| p.is_zero());
+        let g2_zero = [self.h, self.beta_h]
+            .iter()
+            .chain(&self.powers_of_h)
+            .chain(self.degree_bound_powers_of_h.values())
+            .any(|p| p.is_zero());
+        if g1_zero || g2_zero {
+            return invalid("A power is the identity");
+        }
+        if !prepared_matches::<E>(&self.prepared_h, self.h)
+            || !prepared_matches::<E>(&self.prepared_beta_h, self.beta_h)
+        {
+            return invalid("The prepared G2 points don't match h and beta h");
+        }
+        if self.powers_of_h.iter().zip([self.h, self.beta_h]).any(|(p, q)| *p != q) {
+            return invalid("The G2 powers don't start with h and beta h");
+        }
+
+        // Pairs `(\beta^{i+1} P, \beta^i P)` of consecutive G1 powers
+        let g1_pairs: Vec<_> = self
+            .powers_of_g
+            .windows(2)
+            .map(|w| (w[1], w[0]))
+            .chain(self.powers_of_gamma_g.iter().filter_map(|(i, p)| {
+                self.powers_of_gamma_g.get(&(i + 1)).map(|q| (*q, *p))
+            }))
+            .collect();
+        let g2_pairs: Vec<_> = self.powers_of_h.windows(2).map(|w| (w[1], w[0])).collect();
+        // Pairs `(\beta^{D - b} h, \beta^{D - b} g)`
+        let max_degree = self.powers_of_g.len() - 1;
+        let mut bound_pairs = Vec::with_capacity(self.degree_bound_powers_of_h.len());
+        for (&bound, p) in &self.degree_bound_powers_of_h {
+            match max_degree.checked_sub(bound) {
+                Some(shift) => bound_pairs.push((*p, self.powers_of_g[shift])),
+                None => return invalid("A degree bound is above the max degree"),
+            }
+        }
+
+        let mut bytes = Vec::new();
+        self.serialize(&mut bytes).expect("Serializing to a Vec can't fail");
+        let r: E::Fr = Sha256ChallengeGen::hash_to_field(SRS_CHECK_DST, &bytes);
+        let scalars: Vec<_> = std::iter::successors(Some(E::Fr::one()), |x| Some(*x * r))
+            .take(g1_pairs.len() + g2_pairs.len() + bound_pairs.len())
+            .map(|x| x.into_repr())
+            .collect();
+        let (g1_scalars, rest) = scalars.split_at(g1_pairs.len());
+        let (g2_scalars, bound_scalars) = rest.split_at(g2_pairs.len());
+        let msm_g1 = |points: Vec<E::G1Affine>, scalars| {
+            VariableBaseMSM::multi_scalar_mul(&points, scalars)
+        };
+        let msm_g2 = |points: Vec<E::G2Affine>, scalars| {
+            VariableBaseMSM::multi_scalar_mul(&points, scalars)
+        };
+
+        let (shifted, unshifted): (Vec<_>, Vec<_>) = g1_pairs.into_iter().unzip();
+        let (g2_shifted, g2_unshifted): (Vec<_>, Vec<_>) = g2_pairs.into_iter().unzip();
+        let (bound_h, bound_g): (Vec<_>, Vec<_>) = bound_pairs.into_iter().unzip();
+        let g1_affine = E::G1Projective::batch_normalization_into_affine(&[
+            msm_g1(shifted, g1_scalars) - msm_g1(bound_g, bound_scalars),
+            -msm_g1(unshifted, g1_scalars),
+        ]);
+        let g2_affine = E::G2Projective::batch_normalization_into_affine(&[
+            msm_g2(g2_shifted, g2_scalars) + msm_g2(bound_h, bound_scalars),
+            msm_g2(g2_unshifted, g2_scalars),
+        ]);
+        let well_formed = E::product_of_pairings(&[
+            (g1_affine[0].into(), self.prepared_h.clone()),
+            (g1_affine[1].into(), self.prepared_beta_h.clone()),
+            (self.powers_of_g[0].into(), g2_affine[0].into()),
+            ((-self.powers_of_g[1]).into(), g2_affine[1].into()),
+        ])
+        .is_one();
+        if well_formed {
+            Ok(())
+        } else {
+            invalid("The powers aren't consecutive powers of beta")
+        }
+    }
+}
+
+/// Whether `prepared` is `point` prepared for pairings
+fn prepared_matches<E: PairingEngine>(prepared: &E::G2Prepared, point: E::G2Affine) -> bool {
+    ark_ff::to_bytes![prepared].ok() == ark_ff::to_bytes![E::G2Prepared::from(point)].ok()
+}
+
 impl<E: PairingEngine> PCUniversalParams for UniversalParams<E> {
     fn max_degree(&self) -> usize {
         self.powers_of_g.len() - 1
@@ -36,7 +217,9 @@ impl<E: PairingEngine> CanonicalSerialize for UniversalParams<E> {
         self.powers_of_g.serialize(&mut writer)?;
         self.powers_of_gamma_g.serialize(&mut writer)?;
         self.h.serialize(&mut writer)?;
-        self.beta_h.serialize(&mut writer)
+        self.beta_h.serialize(&mut writer)?;
+        self.powers_of_h.serialize(&mut writer)?;
+        self.degree_bound_powers_of_h.serialize(&mut writer)
     }
 
     fn serialized_size(&self) -> usize {
@@ -44,20 +227,26 @@ impl<E: PairingEngine> CanonicalSerialize for UniversalParams<E> {
             + self.powers_of_gamma_g.serialized_size()
             + self.h.serialized_size()
             + self.beta_h.serialized_size()
+            + self.powers_of_h.serialized_size()
+            + self.degree_bound_powers_of_h.serialized_size()
     }
 
     fn serialize_unchecked<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
         self.powers_of_g.serialize_unchecked(&mut writer)?;
         self.powers_of_gamma_g.serialize_unchecked(&mut writer)?;
         self.h.serialize_unchecked(&mut writer)?;
-        self.beta_h.serialize_unchecked(&mut writer)
+        self.beta_h.serialize_unchecked(&mut writer)?;
+        self.powers_of_h.serialize_unchecked(&mut writer)?;
+        self.degree_bound_powers_of_h.serialize_unchecked(&mut writer)
     }
 
     fn serialize_uncompressed<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
         self.powers_of_g.serialize_uncompressed(&mut writer)?;
         self.powers_of_gamma_g.serialize_uncompressed(&mut writer)?;
         self.h.serialize_uncompressed(&mut writer)?;
-        self.beta_h.serialize_uncompressed(&mut writer)
+        self.beta_h.serialize_uncompressed(&mut writer)?;
+        self.powers_of_h.serialize_uncompressed(&mut writer)?;
+        self.degree_bound_powers_of_h.serialize_uncompressed(&mut writer)
     }
 
     fn uncompressed_size(&self) -> usize {
@@ -65,6 +254,8 @@ impl<E: PairingEngine> CanonicalSerialize for UniversalParams<E> {
             + self.powers_of_gamma_g.uncompressed_size()
             + self.h.uncompressed_size()
             + self.beta_h.uncompressed_size()
+            + self.powers_of_h.uncompressed_size()
+            + self.degree_bound_powers_of_h.uncompressed_size()
     }
 }
 
@@ -74,6 +265,9 @@ impl<E: PairingEngine> CanonicalDeserialize for UniversalParams<E> {
         let powers_of_gamma_g = BTreeMap::<usize, E::G1Affine>::deserialize(&mut reader)?;
         let h = E::G2Affine::deserialize(&mut reader)?;
         let beta_h = E::G2Affine::deserialize(&mut reader)?;
+        let powers_of_h = Vec::<E::G2Affine>::deserialize(&mut reader)?;
+        let degree_bound_powers_of_h =
+            BTreeMap::<usize, E::G2Affine>::deserialize(&mut reader)?;
 
         let prepared_h = E::G2Prepared::from(h.clone());
         let prepared_beta_h = E::G2Prepared::from(beta_h.clone());
@@ -83,6 +277,8 @@ impl<E: PairingEngine> CanonicalDeserialize for UniversalParams<E> {
             powers_of_gamma_g,
             h,
             beta_h,
+            powers_of_h,
+            degree_bound_powers_of_h,
             prepared_h,
             prepared_beta_h,
         })
@@ -94,6 +290,9 @@ impl<E: PairingEngine> CanonicalDeserialize for UniversalParams<E> {
             BTreeMap::<usize, E::G1Affine>::deserialize_uncompressed(&mut reader)?;
         let h = E::G2Affine::deserialize_uncompressed(&mut reader)?;
         let beta_h = E::G2Affine::deserialize_uncompressed(&mut reader)?;
+        let powers_of_h = Vec::<E::G2Affine>::deserialize_uncompressed(&mut reader)?;
+        let degree_bound_powers_of_h =
+            BTreeMap::<usize, E::G2Affine>::deserialize_uncompressed(&mut reader)?;
 
         let prepared_h = E::G2Prepared::from(h.clone());
         let prepared_beta_h = E::G2Prepared::from(beta_h.clone());
@@ -103,6 +302,8 @@ impl<E: PairingEngine> CanonicalDeserialize for UniversalParams<E> {
             powers_of_gamma_g,
             h,
             beta_h,
+            powers_of_h,
+            degree_bound_powers_of_h,
             prepared_h,
             prepared_beta_h,
         })
@@ -113,6 +314,9 @@ impl<E: PairingEngine> CanonicalDeserialize for UniversalParams<E> {
         let powers_of_gamma_g = BTreeMap::<usize, E::G1Affine>::deserialize_unchecked(&mut reader)?;
         let h = E::G2Affine::deserialize_unchecked(&mut reader)?;
         let beta_h = E::G2Affine::deserialize_unchecked(&mut reader)?;
+        let powers_of_h = Vec::<E::G2Affine>::deserialize_unchecked(&mut reader)?;
+        let degree_bound_powers_of_h =
+            BTreeMap::<usize, E::G2Affine>::deserialize_unchecked(&mut reader)?;
 
         let prepared_h = E::G2Prepared::from(h.clone());
         let prepared_beta_h = E::G2Prepared::from(beta_h.clone());
@@ -122,6 +326,8 @@ impl<E: PairingEngine> CanonicalDeserialize for UniversalParams<E> {
             powers_of_gamma_g,
             h,
             beta_h,
+            powers_of_h,
+            degree_bound_powers_of_h,
             prepared_h,
             prepared_beta_h,
         })
@@ -131,20 +337,109 @@ impl<E: PairingEngine> CanonicalDeserialize for UniversalParams<E> {
 /// `Powers` is used to commit to and create evaluation proofs for a given
 /// polynomial.
 #[derive(Clone, Debug)]
-pub struct Powers<E: PairingEngine> {
-    /// Group elements of the form `β^i G`, for different values of `i`.
-    pub powers_of_g: Vec<E::G1Affine>,
+pub struct Powers<'a, E: PairingEngine> {
+    /// Group elements of the form `β^i G`, for different values of `i`. Borrowed from
+    /// the [`UniversalParams`] by `KZG10::trim_multi`.
+    pub powers_of_g: Cow<'a, [E::G1Affine]>,
     /// Group elements of the form `β^i γG`, for different values of `i`.
-    pub powers_of_gamma_g: Vec<E::G1Affine>,
+    pub powers_of_gamma_g: Cow<'a, [E::G1Affine]>,
 }
 
-impl<E: PairingEngine> Powers< E> {
+impl<'a, E: PairingEngine> Powers<'a, E> {
     /// The number of powers in `self`.
     pub fn size(&self) -> usize {
         self.powers_of_g.len()
     }
 }
 
+/// `ConstantTimePowers` is the version of [`Powers`] used by
+/// `KZG10::commit_constant_time`. It holds the fixed masks `m^{i+1}` added to the
+/// coefficients and their commitment, which don't depend on the polynomial.
+#[derive(Clone, Debug)]
+pub struct ConstantTimePowers<'a, E: PairingEngine> {
+    /// The powers commitments are made against.
+    pub powers: Powers<'a, E>,
+    /// The mask `m^{i+1}` of the `i`-th coefficient, one for each power.
+    pub masks: Vec<E::Fr>,
+    /// The commitment `\sum_i m^{i+1} β^i G` to the masks.
+    pub mask_commitment: E::G1Projective,
+}
+
+impl<'a, E: PairingEngine> ConstantTimePowers<'a, E> {
+    /// prepare `ConstantTimePowers` from `Powers`
+    pub fn prepare(powers: &Powers<'a, E>) -> Self {
+        let mask: E::Fr = Sha256ChallengeGen::hash_to_field(CONSTANT_TIME_MASK_DST, &[]);
+        let masks: Vec<E::Fr> = std::iter::successors(Some(mask), |m| Some(*m * mask))
+            .take(powers.size())
+            .collect();
+        let mask_scalars: Vec<_> = masks.iter().map(|m| m.into_repr()).collect();
+        let mask_commitment = VariableBaseMSM::multi_scalar_mul(&powers.powers_of_g, &mask_scalars);
+
+        Self {
+            powers: powers.clone(),
+            masks,
+            mask_commitment,
+        }
+    }
+}
+
+// Serialized as two `Vec`s, the powers are always deserialized as owned
+impl<'a, E: PairingEngine> CanonicalSerialize for Powers<'a, E> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.powers_of_g.as_ref().serialize(&mut writer)?;
+        self.powers_of_gamma_g.as_ref().serialize(&mut writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.powers_of_g.as_ref().serialized_size()
+            + self.powers_of_gamma_g.as_ref().serialized_size()
+    }
+
+    fn serialize_unchecked<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.powers_of_g.as_ref().serialize_unchecked(&mut writer)?;
+        self.powers_of_gamma_g.as_ref().serialize_unchecked(&mut writer)
+    }
+
+    fn serialize_uncompressed<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.powers_of_g.as_ref().serialize_uncompressed(&mut writer)?;
+        self.powers_of_gamma_g.as_ref().serialize_uncompressed(&mut writer)
+    }
+
+    fn uncompressed_size(&self) -> usize {
+        self.powers_of_g.as_ref().uncompressed_size()
+            + self.powers_of_gamma_g.as_ref().uncompressed_size()
+    }
+}
+
+impl<'a, E: PairingEngine> CanonicalDeserialize for Powers<'a, E> {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let powers_of_g = Vec::<E::G1Affine>::deserialize(&mut reader)?;
+        let powers_of_gamma_g = Vec::<E::G1Affine>::deserialize(&mut reader)?;
+        Ok(Self {
+            powers_of_g: Cow::Owned(powers_of_g),
+            powers_of_gamma_g: Cow::Owned(powers_of_gamma_g),
+        })
+    }
+
+    fn deserialize_uncompressed<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let powers_of_g = Vec::<E::G1Affine>::deserialize_uncompressed(&mut reader)?;
+        let powers_of_gamma_g = Vec::<E::G1Affine>::deserialize_uncompressed(&mut reader)?;
+        Ok(Self {
+            powers_of_g: Cow::Owned(powers_of_g),
+            powers_of_gamma_g: Cow::Owned(powers_of_gamma_g),
+        })
+    }
+
+    fn deserialize_unchecked<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let powers_of_g = Vec::<E::G1Affine>::deserialize_unchecked(&mut reader)?;
+        let powers_of_gamma_g = Vec::<E::G1Affine>::deserialize_unchecked(&mut reader)?;
+        Ok(Self {
+            powers_of_g: Cow::Owned(powers_of_g),
+            powers_of_gamma_g: Cow::Owned(powers_of_gamma_g),
+        })
+    }
+}
+
 /// `VerifierKey` is used to check evaluation proofs for a given commitment.
 #[derive(Clone, Debug)]
 pub struct VerifierKey<E: PairingEngine> {
@@ -156,6 +451,13 @@ pub struct VerifierKey<E: PairingEngine> {
     pub h: E::G2Affine,
     /// \beta times the above generator of G2.
     pub beta_h: E::G2Affine,
+    /// Group elements of the form `{ \beta^i H }`, where `i` ranges from 0 to the number of G2
+    /// powers asked for at setup, at least `H` and `\beta H`.
+    pub powers_of_h: Vec<E::G2Affine>,
+    /// The maximum degree `D` of the universal parameters this key was trimmed from.
+    pub max_degree: usize,
+    /// Group elements `\beta^{D - b} H` for each degree bound `b` asked for at setup.
+    pub degree_bound_powers_of_h: BTreeMap<usize, E::G2Affine>,
     /// The generator of G2, prepared for use in pairings.
     pub prepared_h: E::G2Prepared,
     /// \beta times the above generator of G2, prepared for use in pairings.
@@ -167,7 +469,10 @@ impl<E: PairingEngine> CanonicalSerialize for VerifierKey<E> {
         self.g.serialize(&mut writer)?;
         self.gamma_g.serialize(&mut writer)?;
         self.h.serialize(&mut writer)?;
-        self.beta_h.serialize(&mut writer)
+        self.beta_h.serialize(&mut writer)?;
+        self.powers_of_h.serialize(&mut writer)?;
+        self.max_degree.serialize(&mut writer)?;
+        self.degree_bound_powers_of_h.serialize(&mut writer)
     }
 
     fn serialized_size(&self) -> usize {
@@ -175,20 +480,29 @@ impl<E: PairingEngine> CanonicalSerialize for VerifierKey<E> {
             + self.gamma_g.serialized_size()
             + self.h.serialized_size()
             + self.beta_h.serialized_size()
+            + self.powers_of_h.serialized_size()
+            + self.max_degree.serialized_size()
+            + self.degree_bound_powers_of_h.serialized_size()
     }
 
     fn serialize_uncompressed<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
         self.g.serialize_uncompressed(&mut writer)?;
         self.gamma_g.serialize_uncompressed(&mut writer)?;
         self.h.serialize_uncompressed(&mut writer)?;
-        self.beta_h.serialize_uncompressed(&mut writer)
+        self.beta_h.serialize_uncompressed(&mut writer)?;
+        self.powers_of_h.serialize_uncompressed(&mut writer)?;
+        self.max_degree.serialize_uncompressed(&mut writer)?;
+        self.degree_bound_powers_of_h.serialize_uncompressed(&mut writer)
     }
 
     fn serialize_unchecked<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
         self.g.serialize_unchecked(&mut writer)?;
         self.gamma_g.serialize_unchecked(&mut writer)?;
         self.h.serialize_unchecked(&mut writer)?;
-        self.beta_h.serialize_unchecked(&mut writer)
+        self.beta_h.serialize_unchecked(&mut writer)?;
+        self.powers_of_h.serialize_unchecked(&mut writer)?;
+        self.max_degree.serialize_unchecked(&mut writer)?;
+        self.degree_bound_powers_of_h.serialize_unchecked(&mut writer)
     }
 
     fn uncompressed_size(&self) -> usize {
@@ -196,6 +510,9 @@ impl<E: PairingEngine> CanonicalSerialize for VerifierKey<E> {
             + self.gamma_g.uncompressed_size()
             + self.h.uncompressed_size()
             + self.beta_h.uncompressed_size()
+            + self.powers_of_h.uncompressed_size()
+            + self.max_degree.uncompressed_size()
+            + self.degree_bound_powers_of_h.uncompressed_size()
     }
 }
 
@@ -205,6 +522,10 @@ impl<E: PairingEngine> CanonicalDeserialize for VerifierKey<E> {
         let gamma_g = E::G1Affine::deserialize(&mut reader)?;
         let h = E::G2Affine::deserialize(&mut reader)?;
         let beta_h = E::G2Affine::deserialize(&mut reader)?;
+        let powers_of_h = Vec::<E::G2Affine>::deserialize(&mut reader)?;
+        let max_degree = usize::deserialize(&mut reader)?;
+        let degree_bound_powers_of_h =
+            BTreeMap::<usize, E::G2Affine>::deserialize(&mut reader)?;
 
         let prepared_h = E::G2Prepared::from(h.clone());
         let prepared_beta_h = E::G2Prepared::from(beta_h.clone());
@@ -214,6 +535,9 @@ impl<E: PairingEngine> CanonicalDeserialize for VerifierKey<E> {
             gamma_g,
             h,
             beta_h,
+            powers_of_h,
+            max_degree,
+            degree_bound_powers_of_h,
             prepared_h,
             prepared_beta_h,
         })
@@ -224,6 +548,10 @@ impl<E: PairingEngine> CanonicalDeserialize for VerifierKey<E> {
         let gamma_g = E::G1Affine::deserialize_uncompressed(&mut reader)?;
         let h = E::G2Affine::deserialize_uncompressed(&mut reader)?;
         let beta_h = E::G2Affine::deserialize_uncompressed(&mut reader)?;
+        let powers_of_h = Vec::<E::G2Affine>::deserialize_uncompressed(&mut reader)?;
+        let max_degree = usize::deserialize_uncompressed(&mut reader)?;
+        let degree_bound_powers_of_h =
+            BTreeMap::<usize, E::G2Affine>::deserialize_uncompressed(&mut reader)?;
 
         let prepared_h = E::G2Prepared::from(h.clone());
         let prepared_beta_h = E::G2Prepared::from(beta_h.clone());
@@ -233,6 +561,9 @@ impl<E: PairingEngine> CanonicalDeserialize for VerifierKey<E> {
             gamma_g,
             h,
             beta_h,
+            powers_of_h,
+            max_degree,
+            degree_bound_powers_of_h,
             prepared_h,
             prepared_beta_h,
         })
@@ -243,6 +574,10 @@ impl<E: PairingEngine> CanonicalDeserialize for VerifierKey<E> {
         let gamma_g = E::G1Affine::deserialize_unchecked(&mut reader)?;
         let h = E::G2Affine::deserialize_unchecked(&mut reader)?;
         let beta_h = E::G2Affine::deserialize_unchecked(&mut reader)?;
+        let powers_of_h = Vec::<E::G2Affine>::deserialize_unchecked(&mut reader)?;
+        let max_degree = usize::deserialize_unchecked(&mut reader)?;
+        let degree_bound_powers_of_h =
+            BTreeMap::<usize, E::G2Affine>::deserialize_unchecked(&mut reader)?;
 
         let prepared_h = E::G2Prepared::from(h.clone());
         let prepared_beta_h = E::G2Prepared::from(beta_h.clone());
@@ -252,6 +587,9 @@ impl<E: PairingEngine> CanonicalDeserialize for VerifierKey<E> {
             gamma_g,
             h,
             beta_h,
+            powers_of_h,
+            max_degree,
+            degree_bound_powers_of_h,
             prepared_h,
             prepared_beta_h,
         })
@@ -265,6 +603,12 @@ impl<E: PairingEngine> ToBytes for VerifierKey<E> {
         self.gamma_g.write(&mut writer)?;
         self.h.write(&mut writer)?;
         self.beta_h.write(&mut writer)?;
+        self.powers_of_h.write(&mut writer)?;
+        (self.max_degree as u64).write(&mut writer)?;
+        for (bound, power) in &self.degree_bound_powers_of_h {
+            (*bound as u64).write(&mut writer)?;
+            power.write(&mut writer)?;
+        }
         self.prepared_h.write(&mut writer)?;
         self.prepared_beta_h.write(&mut writer)
     }
@@ -282,6 +626,12 @@ where
         res.extend_from_slice(&self.gamma_g.to_field_elements().unwrap());
         res.extend_from_slice(&self.h.to_field_elements().unwrap());
         res.extend_from_slice(&self.beta_h.to_field_elements().unwrap());
+        for power_of_h in &self.powers_of_h {
+            res.extend_from_slice(&power_of_h.to_field_elements().unwrap());
+        }
+        for power_of_h in self.degree_bound_powers_of_h.values() {
+            res.extend_from_slice(&power_of_h.to_field_elements().unwrap());
+        }
 
         Some(res)
     }
@@ -366,7 +716,28 @@ impl<'a, E: PairingEngine> AddAssign<(E::Fr, &'a Commitment<E>)> for Commitment<
     }
 }
 
+/// Folds two commitments into `c1 + r * c2`, the step that folding/accumulation
+/// schemes repeat every round. Committing [`fold_polynomials`] of the underlying
+/// polynomials gives the same commitment.
+pub fn fold_commitments<E: PairingEngine>(
+    c1: &Commitment<E>,
+    c2: &Commitment<E>,
+    r: E::Fr,
+) -> Commitment<E> {
+    let mut folded = c1.clone();
+    folded += (r, c2);
+    folded
+}
+
+/// Folds two polynomials into `p1 + r * p2`. See [`fold_commitments`].
+pub fn fold_polynomials<F: Field, P: Polynomial<F>>(p1: &P, p2: &P, r: F) -> P {
+    let mut folded = p1.clone();
+    folded += (r, p2);
+    folded
+}
+
 /// `PreparedCommitment` commits to a polynomial and prepares for mul_bits.
+#[derive(Clone, Debug)]
 pub struct PreparedCommitment<E: PairingEngine>(
     /// The commitment is a group element.
     pub Vec<E::G1Affine>,
@@ -387,9 +758,38 @@ impl<E: PairingEngine> PreparedCommitment<E> {
 
         Self { 0: prepared_comm }
     }
+
+    /// Multiplies the commitment by `scalar`, adding up the precomputed doublings for
+    /// each set bit instead of doubling on the fly.
+    pub fn mul_u128(&self, scalar: u128) -> E::G1Projective {
+        let mut res = E::G1Projective::zero();
+        for (i, power) in self.0.iter().enumerate().take(128) {
+            if (scalar >> i) & 1 == 1 {
+                res.add_assign_mixed(power);
+            }
+        }
+        res
+    }
 }
 
 
+/// `Randomness` hides the polynomial inside a commitment; it is output by
+/// `KZG10::commit_hiding` and needed again by `KZG10::open_hiding`.
+#[derive(Clone, Debug)]
+pub struct Randomness<E: PairingEngine> {
+    /// The polynomial committed against the powers of `\gamma G` along with the
+    /// committed polynomial.
+    pub blinding_polynomial: DensePolynomial<E::Fr>,
+}
+
+/// `EqualityProof` reveals the blinding of a hiding commitment to show it commits to a
+/// public polynomial; it is output by `KZG10::prove_equals_public`.
+#[derive(Clone, Debug)]
+pub struct EqualityProof<E: PairingEngine> {
+    /// The blinding polynomial of the commitment.
+    pub blinding_polynomial: DensePolynomial<E::Fr>,
+}
+
 /// `Proof` is an evaluation proof that is output by `KZG10::open`.
 #[derive(Clone, Debug, CanonicalDeserialize, CanonicalSerialize)]
 pub struct Proof<E: PairingEngine> {
@@ -409,3 +809,89 @@ impl<E: PairingEngine> ToBytes for Proof<E> {
         self.w.write(&mut writer)
     }
 }
+
+/// `HidingProof` is an evaluation proof for a hiding commitment; it is output by
+/// `KZG10::open_hiding`.
+#[derive(Clone, Debug, CanonicalDeserialize, CanonicalSerialize)]
+pub struct HidingProof<E: PairingEngine> {
+    /// The opening of the committed polynomial plus the blinding polynomial.
+    pub proof: Proof<E>,
+    /// The evaluation of the blinding polynomial at the point.
+    pub random_v: E::Fr,
+}
+
+/// `ShiftProof` proves that one commitment is to the rotation `p(\omega x)` of the
+/// polynomial in another; it is output by `KZG10::commit_shift_relation`.
+#[derive(Clone, Debug, CanonicalDeserialize, CanonicalSerialize)]
+pub struct ShiftProof<E: PairingEngine> {
+    /// The shared value `p(\omega z)`, which is the rotation evaluated at `z`.
+    pub value: E::Fr,
+    /// Commitment to the witness for `p` at `\omega z`.
+    pub w: E::G1Affine,
+    /// Commitment to the witness for the rotation at `z`.
+    pub w_rotated: E::G1Affine,
+}
+
+/// `MerkleCoefficientProof` is a KZG opening along with one coefficient of the polynomial
+/// and its Merkle path; it is output by `KZG10::open_with_merkle_coefficient`.
+#[derive(Clone, Debug)]
+pub struct MerkleCoefficientProof<E: PairingEngine> {
+    /// The KZG opening at the point.
+    pub proof: Proof<E>,
+    /// The index of the revealed coefficient.
+    pub index: usize,
+    /// The revealed coefficient.
+    pub coeff: E::Fr,
+    /// The Merkle path of the coefficient.
+    pub path: Vec<Hash>,
+}
+
+/// `CosetProof` proves the evaluations of a polynomial over a whole multiplicative
+/// coset; it is output by `KZG10::open_over_coset`.
+#[derive(Clone, Debug, CanonicalDeserialize, CanonicalSerialize)]
+pub struct CosetProof<E: PairingEngine> {
+    /// Commitment to the quotient `q(x) = (p(x) - r(x)) / (x^n - s^n)`, where `r` is
+    /// the remainder interpolating `p` over the coset.
+    pub w_quotient: E::G1Affine,
+    /// Commitment to the witness for `p(x) - r(z) - q(x)(z^n - s^n)` vanishing at the
+    /// challenge `z`.
+    pub w_challenge: E::G1Affine,
+}
+
+/// `ExtensionFieldProof` is an opening of a polynomial over a quadratic extension at
+/// a point of the extension; it is output by `KZG10::open_extension_field`.
+#[derive(Clone, Debug, CanonicalDeserialize, CanonicalSerialize)]
+pub struct ExtensionFieldProof<E: PairingEngine, F: Fp2Parameters<Fp = E::Fr>> {
+    /// The value at the point of the component polynomial of `c0` coefficients.
+    pub c0_value: Fp2<F>,
+    /// The value at the point of the component polynomial of `c1` coefficients.
+    pub c1_value: Fp2<F>,
+    /// Commitment to the witness for the random combination of the two components,
+    /// divided by the minimal polynomial of the point.
+    pub w: E::G1Affine,
+}
+
+/// Version byte written in front of the structures serialized with
+/// [`serialize_versioned`]. Bump it whenever one of their formats changes.
+pub const SERIALIZATION_VERSION: u8 = 2;
+
+/// Serializes `value` behind a [`SERIALIZATION_VERSION`] byte. Meant for
+/// `UniversalParams`, `Powers`, `Commitment` and `Proof`.
+pub fn serialize_versioned<T: CanonicalSerialize, W: Write>(
+    value: &T,
+    mut writer: W,
+) -> Result<(), Error> {
+    SERIALIZATION_VERSION.serialize(&mut writer)?;
+    value.serialize(&mut writer)?;
+    Ok(())
+}
+
+/// Deserializes a value written by [`serialize_versioned`], rejecting any other
+/// version than [`SERIALIZATION_VERSION`] rather than guessing at its format.
+pub fn deserialize_versioned<T: CanonicalDeserialize, R: Read>(mut reader: R) -> Result<T, Error> {
+    let version = u8::deserialize(&mut reader)?;
+    if version != SERIALIZATION_VERSION {
+        return Err(Error::UnknownSerializationVersion(version));
+    }
+    Ok(T::deserialize(&mut reader)?)
+}
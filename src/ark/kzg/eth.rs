@@ -0,0 +1,255 @@
+//! Import and export of BLS12-381 parameters in the trusted setup JSON format of the
+//! Ethereum KZG ceremony (EIP-4844).
+//!
+//! The file holds hex strings of compressed points in the ZCash encoding: 48 byte G1
+//! and 96 byte G2 points, big-endian, with the compression, infinity and sign flags in
+//! the top three bits of the first byte. The ceremony output has 4096 G1 points,
+//! given both as monomial powers and in Lagrange form over the size 4096 domain in
+//! bit-reversed order, and the first 65 G2 powers.
+use std::{fs, path::Path};
+
+use ark_bls12_381::{Bls12_381, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use super::{bit_reverse_permutation, Error, UniversalParams};
+
+/// Number of G1 points in the ceremony output.
+pub const ETH_KZG_G1_POINTS: usize = 4096;
+/// Number of G2 points in the ceremony output.
+pub const ETH_KZG_G2_POINTS: usize = 65;
+
+const COMPRESSION_FLAG: u8 = 0b1000_0000;
+const INFINITY_FLAG: u8 = 0b0100_0000;
+const SIGN_FLAG: u8 = 0b0010_0000;
+
+#[derive(Serialize, Deserialize)]
+struct TrustedSetup {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    g1_monomial: Vec<String>,
+    g1_lagrange: Vec<String>,
+    g2_monomial: Vec<String>,
+}
+
+impl UniversalParams<Bls12_381> {
+    /// Writes the parameters to `path` in the ceremony's JSON format. The number of
+    /// G1 powers must be a power of two, and at most [`ETH_KZG_G2_POINTS`] G2 powers
+    /// are written.
+    pub fn export_eth_kzg(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let n = self.powers_of_g.len();
+        let domain = Radix2EvaluationDomain::<Fr>::new(n)
+            .filter(|d| d.size() == n)
+            .ok_or_else(|| invalid(format!("{} G1 powers is not a power of two", n)))?;
+
+        let mut lagrange: Vec<_> = self.powers_of_g.iter().map(|g| g.into_projective()).collect();
+        domain.ifft_in_place(&mut lagrange);
+        let lagrange = ark_bls12_381::G1Projective::batch_normalization_into_affine(&lagrange);
+
+        let setup = TrustedSetup {
+            g1_monomial: self.powers_of_g.iter().map(g1_to_hex).collect(),
+            g1_lagrange: bit_reverse_permutation(n)
+                .into_iter()
+                .map(|i| g1_to_hex(&lagrange[i]))
+                .collect(),
+            g2_monomial: self
+                .powers_of_h
+                .iter()
+                .take(ETH_KZG_G2_POINTS)
+                .map(g2_to_hex)
+                .collect(),
+        };
+        let json = serde_json::to_string_pretty(&setup).map_err(|e| invalid(e.to_string()))?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Reads parameters written in the ceremony's JSON format from `path`. Files
+    /// without `g1_monomial` are converted from `g1_lagrange`.
+    ///
+    /// The ceremony has no `\gamma G` powers, so these are made from a fresh `\gamma`
+    /// drawn from `rng`, as in [`UniversalParams::from_ptau_g1_g2`]. All the G2 powers
+    /// of the file are kept.
+    pub fn import_eth_kzg<R: RngCore>(path: impl AsRef<Path>, rng: &mut R) -> Result<Self, Error> {
+        let json = fs::read_to_string(path)?;
+        let setup: TrustedSetup =
+            serde_json::from_str(&json).map_err(|e| invalid(e.to_string()))?;
+
+        let powers_of_g = if setup.g1_monomial.is_empty() {
+            let n = setup.g1_lagrange.len();
+            let domain = Radix2EvaluationDomain::<Fr>::new(n)
+                .filter(|d| d.size() == n)
+                .ok_or_else(|| invalid(format!("{} G1 points is not a power of two", n)))?;
+            let bitreversed = setup
+                .g1_lagrange
+                .iter()
+                .map(|s| g1_from_hex(s))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut monomial = vec![ark_bls12_381::G1Projective::zero(); n];
+            for (i, rev_i) in bit_reverse_permutation(n).into_iter().enumerate() {
+                monomial[rev_i] = bitreversed[i].into_projective();
+            }
+            domain.fft_in_place(&mut monomial);
+            ark_bls12_381::G1Projective::batch_normalization_into_affine(&monomial)
+        } else {
+            setup
+                .g1_monomial
+                .iter()
+                .map(|s| g1_from_hex(s))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        let powers_of_h = setup
+            .g2_monomial
+            .iter()
+            .map(|s| g2_from_hex(s))
+            .collect::<Result<Vec<_>, _>>()?;
+        if powers_of_g.len() < 2 || powers_of_h.len() < 2 {
+            return Err(invalid("need at least two G1 and G2 powers".to_string()));
+        }
+
+        let mut pp = Self::from_ptau_g1_g2(&powers_of_g, powers_of_h[0], powers_of_h[1], rng)?;
+        pp.powers_of_h = powers_of_h;
+        Ok(pp)
+    }
+}
+
+fn invalid(msg: String) -> Error {
+    Error::InvalidTrustedSetup(msg)
+}
+
+fn fq_to_bytes(x: &Fq) -> Vec<u8> {
+    x.into_repr().to_bytes_be()
+}
+
+fn fq_from_bytes(bytes: &[u8]) -> Result<Fq, Error> {
+    let mut repr = <Fq as PrimeField>::BigInt::default();
+    for (limb, chunk) in repr.as_mut().iter_mut().rev().zip(bytes.chunks(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+    Fq::from_repr(repr).ok_or_else(|| invalid("coordinate is not a field element".to_string()))
+}
+
+fn to_hex(flags: u8, mut bytes: Vec<u8>) -> String {
+    bytes[0] |= flags;
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn from_hex(s: &str, len: usize) -> Result<(u8, Vec<u8>), Error> {
+    let mut bytes = hex::decode(s.trim_start_matches("0x")).map_err(|e| invalid(e.to_string()))?;
+    if bytes.len() != len {
+        return Err(invalid(format!("expected {} bytes, got {}", len, bytes.len())));
+    }
+    let flags = bytes[0] & (COMPRESSION_FLAG | INFINITY_FLAG | SIGN_FLAG);
+    if flags & COMPRESSION_FLAG == 0 {
+        return Err(invalid("point is not compressed".to_string()));
+    }
+    bytes[0] &= !flags;
+    Ok((flags, bytes))
+}
+
+fn g1_to_hex(p: &G1Affine) -> String {
+    if p.is_zero() {
+        return to_hex(COMPRESSION_FLAG | INFINITY_FLAG, vec![0; 48]);
+    }
+    let sign = if p.y > -p.y { SIGN_FLAG } else { 0 };
+    to_hex(COMPRESSION_FLAG | sign, fq_to_bytes(&p.x))
+}
+
+fn g1_from_hex(s: &str) -> Result<G1Affine, Error> {
+    let (flags, bytes) = from_hex(s, 48)?;
+    if flags & INFINITY_FLAG != 0 {
+        return Ok(G1Affine::zero());
+    }
+    let x = fq_from_bytes(&bytes)?;
+    G1Affine::get_point_from_x(x, flags & SIGN_FLAG != 0)
+        .filter(|p| p.is_in_correct_subgroup_assuming_on_curve())
+        .ok_or_else(|| invalid(format!("{} is not a valid G1 point", s)))
+}
+
+fn g2_to_hex(p: &G2Affine) -> String {
+    if p.is_zero() {
+        return to_hex(COMPRESSION_FLAG | INFINITY_FLAG, vec![0; 96]);
+    }
+    let sign = if p.y > -p.y { SIGN_FLAG } else { 0 };
+    let mut bytes = fq_to_bytes(&p.x.c1);
+    bytes.extend(fq_to_bytes(&p.x.c0));
+    to_hex(COMPRESSION_FLAG | sign, bytes)
+}
+
+fn g2_from_hex(s: &str) -> Result<G2Affine, Error> {
+    let (flags, bytes) = from_hex(s, 96)?;
+    if flags & INFINITY_FLAG != 0 {
+        return Ok(G2Affine::zero());
+    }
+    let x = Fq2::new(fq_from_bytes(&bytes[48..])?, fq_from_bytes(&bytes[..48])?);
+    G2Affine::get_point_from_x(x, flags & SIGN_FLAG != 0)
+        .filter(|p| p.is_in_correct_subgroup_assuming_on_curve())
+        .ok_or_else(|| invalid(format!("{} is not a valid G2 point", s)))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_camel_case_types)]
+    use super::*;
+    use crate::ark::kzg::KZG10;
+    use crate::test_rng;
+    use ark_ff::UniformRand;
+    use ark_poly::{univariate::DensePolynomial, Polynomial, UVPolynomial};
+
+    type KZG_Bls12_381 = KZG10<Bls12_381, DensePolynomial<Fr>>;
+
+    #[test]
+    fn test_generator_encoding() {
+        let g1 = G1Affine::prime_subgroup_generator();
+        let g1_hex = "0x97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb";
+        assert_eq!(g1_to_hex(&g1), g1_hex);
+        assert_eq!(g1_from_hex(g1_hex).unwrap(), g1);
+        assert_eq!(g1_from_hex(&g1_to_hex(&-g1)).unwrap(), -g1);
+
+        let g2 = G2Affine::prime_subgroup_generator();
+        let g2_hex = "0x93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8";
+        assert_eq!(g2_to_hex(&g2), g2_hex);
+        assert_eq!(g2_from_hex(g2_hex).unwrap(), g2);
+        assert_eq!(g2_from_hex(&g2_to_hex(&-g2)).unwrap(), -g2);
+    }
+
+    #[test]
+    fn test_eth_kzg_round_trip() {
+        let rng = &mut test_rng();
+        let max_degree = 127;
+        // Ceremony files start from the standard generators
+        let pp = KZG_Bls12_381::setup_test_vector(max_degree, Fr::rand(rng)).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trusted_setup.json");
+        pp.export_eth_kzg(&path).unwrap();
+
+        let imported = UniversalParams::<Bls12_381>::import_eth_kzg(&path, rng).unwrap();
+        assert_eq!(imported.powers_of_g, pp.powers_of_g);
+        assert_eq!(imported.powers_of_h, pp.powers_of_h);
+        // Hiding commitments need real powers of gamma g
+        assert!(imported.powers_of_gamma_g.values().all(|p| !p.is_zero()));
+
+        // Older files only carry the Lagrange form
+        let mut setup: TrustedSetup =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        setup.g1_monomial.clear();
+        fs::write(&path, serde_json::to_string(&setup).unwrap()).unwrap();
+        let from_lagrange = UniversalParams::<Bls12_381>::import_eth_kzg(&path, rng).unwrap();
+        assert_eq!(from_lagrange.powers_of_g, pp.powers_of_g);
+
+        let (powers, vk) = KZG_Bls12_381::trim(&imported, max_degree).unwrap();
+        let p = DensePolynomial::<Fr>::rand(max_degree, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        let point = Fr::rand(rng);
+        let proof = KZG_Bls12_381::open(&powers, &p, point).unwrap();
+        assert!(KZG_Bls12_381::check(&vk, &comm, point, p.evaluate(&point), &proof).unwrap());
+
+        let (hiding_comm, rand) = KZG_Bls12_381::commit_hiding(&powers, &p, 1, rng).unwrap();
+        assert_ne!(hiding_comm, comm);
+        let proof = KZG_Bls12_381::open_hiding(&powers, &p, point, &rand).unwrap();
+        let value = p.evaluate(&point);
+        assert!(KZG_Bls12_381::check_hiding(&vk, &hiding_comm, point, value, &proof).unwrap());
+    }
+}
@@ -6,15 +6,55 @@
 //! This construction achieves extractability in the algebraic group model (AGM).
 use ark_ec::msm::{FixedBaseMSM, VariableBaseMSM};
 use ark_ec::{group::Group, AffineCurve, PairingEngine, ProjectiveCurve};
-use ark_ff::{One, PrimeField, UniformRand, Zero};
-use ark_poly::UVPolynomial;
+use ark_ff::{
+    fields::{Fp2, Fp2Parameters},
+    Field, One, PrimeField, UniformRand, Zero,
+};
+use ark_poly::{univariate::DensePolynomial, EvaluationDomain, Polynomial, UVPolynomial};
 use ark_poly_commit::LabeledPolynomial;
+use ark_serialize::CanonicalSerialize;
 use ark_std::{marker::PhantomData, ops::Div, vec};
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
 
 use ark_std::rand::RngCore;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::challenge::{
+    ChallengeGen, Sha256ChallengeGen, COSET_OPENING_DST, EXTENSION_FIELD_DST,
+    MERKLE_COEFFICIENT_DST, SHARED_POINTS_DST, SHIFT_RELATION_DST,
+};
+use super::merkle::{hash_leaf, Hash, MerkleTree};
+use super::packing::pack_bytes_to_field;
+use super::representation::DomainPoint;
 
 mod data_structures;
 pub use data_structures::*;
+#[cfg(feature = "eth-kzg")]
+mod eth;
+#[cfg(feature = "eth-kzg")]
+pub use eth::*;
+
+/// Number of coefficients [`KZG10::commit_from_iter`] buffers for each MSM.
+pub const COMMIT_CHUNK_SIZE: usize = 1 << 12;
+
+/// Evaluations of several polynomials at a shared set of points, `values[k][i]` being
+/// the `k`-th polynomial at the `i`-th point.
+pub type EvaluationMatrix<F> = Vec<Vec<F>>;
+
+/// A committer and verifier key pair, as output by [`KZG10::trim`].
+pub type TrimmedKeys<'a, E> = (Powers<'a, E>, VerifierKey<E>);
+
+/// A value `p(z)`, derivative `p'(z)` and their proof, as output by
+/// [`KZG10::open_value_and_derivative`].
+pub type ValueAndDerivative<E> = (<E as PairingEngine>::Fr, <E as PairingEngine>::Fr, Proof<E>);
+
+/// A claim `p(z) = v` as a commitment, point `z`, value `v` and proof, as output by
+/// [`KZG10::aggregate_proofs`].
+pub type Claim<E> = (Commitment<E>, <E as PairingEngine>::Fr, <E as PairingEngine>::Fr, Proof<E>);
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -22,6 +62,8 @@ pub enum Error {
     DegreeIsZero,
     #[error("Degree is zero")]
     UnsupportedDegreeBound(usize),
+    #[error("No G2 power was made at setup to check the degree bound {0}")]
+    MissingDegreeBoundPower(usize),
     #[error("Degree is zero")]
     IncorrectDegreeBound {
         poly_degree: usize,
@@ -34,6 +76,59 @@ pub enum Error {
         num_coefficients: usize,
         num_powers: usize,
     },
+    #[error("Polynomial of degree {poly_degree} exceeds the trimmed degree {supported}")]
+    PolynomialExceedsTrimmedDegree { poly_degree: usize, supported: usize },
+    #[error("Expected {expected} evaluations, got {got}")]
+    WrongNumberOfEvaluations { expected: usize, got: usize },
+    #[error("Coset shift is zero")]
+    ZeroCosetShift,
+    #[error("Invalid trusted setup: {0}")]
+    InvalidTrustedSetup(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Can't open at {num_points} points with {max_points} G2 powers")]
+    TooManyPoints { num_points: usize, max_points: usize },
+    #[error("Opening points must be distinct")]
+    DuplicatePoints,
+    #[error("Window offset {offset} needs more G2 powers, at most {max_offset} is supported")]
+    WindowOffsetTooLarge { offset: usize, max_offset: usize },
+    #[error("Coefficients don't match the Merkle root")]
+    MerkleRootMismatch,
+    #[error("Hiding bound is zero")]
+    HidingBoundIsZero,
+    #[error("Hiding bound {hiding_bound} needs more than the {num_powers} powers of gamma g")]
+    HidingBoundTooLarge {
+        hiding_bound: usize,
+        num_powers: usize,
+    },
+    #[error("Unknown serialization version {0}")]
+    UnknownSerializationVersion(u8),
+    #[error(transparent)]
+    Serialization(#[from] ark_serialize::SerializationError),
+    #[error("Coefficient {index} of a degree {degree} polynomial differs from its mirror")]
+    NotPalindromic { degree: usize, index: usize },
+    #[error("Polynomial of degree {poly_degree} doesn't fit a domain of size {domain_size}")]
+    ExceedsDomainSize {
+        poly_degree: usize,
+        domain_size: usize,
+    },
+    #[error("Point is in the base field, which KZG10::open handles")]
+    PointInBaseField,
+    #[error("Polynomial doesn't vanish on the domain of size {domain_size}")]
+    DoesNotVanishOnDomain { domain_size: usize },
+    #[error("Domain of size {domain_size} must exceed the supported degree {supported_degree}")]
+    DomainNotLargerThanDegree {
+        domain_size: usize,
+        supported_degree: usize,
+    },
+    #[error("Verifier key has {num_powers} powers of h, missing beta^{power} h")]
+    MissingG2Power { power: usize, num_powers: usize },
+    #[error("Expected a point, value and proof for each of the {expected} commitments, got {got}")]
+    MismatchedClaimLengths { expected: usize, got: usize },
+    #[error("Committer key has no powers")]
+    NoPowers,
+    #[error("Iterator reported {reported} coefficients but yielded {yielded}")]
+    WrongCoefficientCount { reported: usize, yielded: usize },
 }
 
 /// `KZG10` is an implementation of the polynomial commitment scheme of
@@ -52,16 +147,63 @@ where
     for<'a, 'b> &'a P: Div<&'b P, Output = P>,
 {
     /// Constructs public parameters when given as input the maximum degree `degree`
-    /// for the polynomial commitment scheme.
+    /// for the polynomial commitment scheme. Only `h` and `\beta h` are made in G2.
     pub fn setup<R: RngCore>(max_degree: usize, rng: &mut R) -> Result<UniversalParams<E>, Error> {
+        Self::setup_with_g2_powers(max_degree, 2, &[], rng)
+    }
+
+    /// Same as [`Self::setup`], but also makes the G2 powers `\beta^i h` for
+    /// `i < num_g2_powers`, which opening at several points needs, and `\beta^{D - b} h`
+    /// for each bound `b` in `degree_bounds`, which [`Self::verify_degree_bound`] needs.
+    pub fn setup_with_g2_powers<R: RngCore>(
+        max_degree: usize,
+        num_g2_powers: usize,
+        degree_bounds: &[usize],
+        rng: &mut R,
+    ) -> Result<UniversalParams<E>, Error> {
         if max_degree < 1 {
             return Err(Error::DegreeIsZero);
         }
+        if let Some(&bound) = degree_bounds.iter().find(|&&b| b > max_degree) {
+            return Err(Error::UnsupportedDegreeBound(bound));
+        }
         let beta = E::Fr::rand(rng);
         let g = E::G1Projective::rand(rng);
         let gamma_g = E::G1Projective::rand(rng);
         let h = E::G2Projective::rand(rng);
+        Ok(Self::setup_from_trapdoor(
+            max_degree,
+            beta,
+            g,
+            gamma_g,
+            h,
+            num_g2_powers,
+            degree_bounds,
+        ))
+    }
+
+    /// Same as [`Self::setup`], but deterministic: `tau` is used as `\beta`, `g` and `h`
+    /// are the standard generators and `\gamma g = 2 g`. The trapdoor is public, so
+    /// these parameters are only good for reproducible test vectors.
+    pub fn setup_test_vector(max_degree: usize, tau: E::Fr) -> Result<UniversalParams<E>, Error> {
+        if max_degree < 1 {
+            return Err(Error::DegreeIsZero);
+        }
+        let g = E::G1Projective::prime_subgroup_generator();
+        let h = E::G2Projective::prime_subgroup_generator();
+        let gamma_g = ProjectiveCurve::double(&g);
+        Ok(Self::setup_from_trapdoor(max_degree, tau, g, gamma_g, h, 2, &[]))
+    }
 
+    fn setup_from_trapdoor(
+        max_degree: usize,
+        beta: E::Fr,
+        g: E::G1Projective,
+        gamma_g: E::G1Projective,
+        h: E::G2Projective,
+        num_g2_powers: usize,
+        degree_bounds: &[usize],
+    ) -> UniversalParams<E> {
         let mut powers_of_beta = vec![E::Fr::one()];
 
         let mut cur = beta;
@@ -91,39 +233,116 @@ where
         // up to D queries.
         powers_of_gamma_g.push(powers_of_gamma_g.last().unwrap().mul(&beta));
 
+        // Only the G2 powers that were asked for, followed by `\beta^{D - b}` for each bound
+        let num_g2_powers = num_g2_powers.clamp(2, max_degree + 1);
+        let g2_scalars: Vec<_> = powers_of_beta[..num_g2_powers]
+            .iter()
+            .chain(degree_bounds.iter().map(|&b| &powers_of_beta[max_degree - b]))
+            .cloned()
+            .collect();
+        let g2_window_size = FixedBaseMSM::get_mul_window_size(g2_scalars.len());
+        let h_table = FixedBaseMSM::get_window_table(scalar_bits, g2_window_size, h);
+        let g2_powers = FixedBaseMSM::multi_scalar_mul::<E::G2Projective>(
+            scalar_bits,
+            g2_window_size,
+            &h_table,
+            &g2_scalars,
+        );
+
         let powers_of_g = E::G1Projective::batch_normalization_into_affine(&powers_of_g);
         let powers_of_gamma_g =
             E::G1Projective::batch_normalization_into_affine(&powers_of_gamma_g)
                 .into_iter()
                 .enumerate()
                 .collect();
+        let mut powers_of_h = E::G2Projective::batch_normalization_into_affine(&g2_powers);
+        let degree_bound_powers_of_h = degree_bounds
+            .iter()
+            .cloned()
+            .zip(powers_of_h.split_off(num_g2_powers))
+            .collect();
 
-        let h = h.into_affine();
-        let beta_h = h.mul(beta).into_affine();
+        let h = powers_of_h[0];
+        let beta_h = powers_of_h[1];
         let prepared_h = h.into();
         let prepared_beta_h = beta_h.into();
 
-        let pp = UniversalParams {
+        UniversalParams {
             powers_of_g,
             powers_of_gamma_g,
             h,
             beta_h,
+            powers_of_h,
+            degree_bound_powers_of_h,
             prepared_h,
             prepared_beta_h,
-        };
+        }
+    }
+
+    /// Same as [`Self::setup`], but caches the parameters in the file at `path`. If the
+    /// file holds parameters for at least `max_degree`, they're loaded and truncated to
+    /// `max_degree`. Otherwise new ones are generated with `rng` and written there.
+    pub fn setup_cached<R: RngCore>(
+        max_degree: usize,
+        path: impl AsRef<Path>,
+        rng: &mut R,
+    ) -> Result<UniversalParams<E>, Error> {
+        let path = path.as_ref();
+        if path.exists() {
+            let reader = BufReader::new(File::open(path)?);
+            match deserialize_versioned::<UniversalParams<E>, _>(reader) {
+                Ok(mut pp) if pp.powers_of_g.len() > max_degree => {
+                    pp.truncate(max_degree);
+                    return Ok(pp);
+                }
+                // Too few powers, or written in an older format, so regenerate
+                Ok(_) | Err(Error::UnknownSerializationVersion(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let pp = Self::setup(max_degree, rng)?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        serialize_versioned(&pp, &mut writer)?;
+        writer.flush()?;
         Ok(pp)
     }
 
     /// Specializes the public parameters for a given maximum degree `d` for polynomials
     /// `d` should be less that `pp.max_degree()`.
     pub fn trim(
+        pp: &UniversalParams<E>,
+        supported_degree: usize,
+    ) -> Result<(Powers<'static, E>, VerifierKey<E>), Error> {
+        let (powers, vk) = Self::trim_borrowed(pp, supported_degree)?;
+        let powers = Powers {
+            powers_of_g: Cow::Owned(powers.powers_of_g.into_owned()),
+            powers_of_gamma_g: Cow::Owned(powers.powers_of_gamma_g.into_owned()),
+        };
+        Ok((powers, vk))
+    }
+
+    /// Same as [`Self::trim`] for each of `degrees`. The powers of `g` of every key are
+    /// a prefix of those in `pp`, so they are borrowed from it instead of copied. The
+    /// powers of `\gamma g` are kept in a map in `pp`, so each key still owns those.
+    pub fn trim_multi<'a>(
+        pp: &'a UniversalParams<E>,
+        degrees: &[usize],
+    ) -> Result<Vec<TrimmedKeys<'a, E>>, Error> {
+        degrees
+            .iter()
+            .map(|&d| Self::trim_borrowed(pp, d))
+            .collect()
+    }
+
+    fn trim_borrowed(
         pp: &UniversalParams<E>,
         mut supported_degree: usize,
-    ) -> Result<(Powers<E>, VerifierKey<E>), Error> {
+    ) -> Result<(Powers<'_, E>, VerifierKey<E>), Error> {
         if supported_degree == 1 {
             supported_degree += 1;
         }
-        let powers_of_g = pp.powers_of_g[..=supported_degree].to_vec();
+        let powers_of_g = Cow::Borrowed(&pp.powers_of_g[..=supported_degree]);
         let powers_of_gamma_g = (0..=supported_degree)
             .map(|i| pp.powers_of_gamma_g[&i])
             .collect();
@@ -137,15 +356,23 @@ where
             gamma_g: pp.powers_of_gamma_g[&0],
             h: pp.h,
             beta_h: pp.beta_h,
+            powers_of_h: pp
+                .powers_of_h
+                .iter()
+                .take(supported_degree + 1)
+                .cloned()
+                .collect(),
+            max_degree: pp.powers_of_g.len() - 1,
+            degree_bound_powers_of_h: pp.degree_bound_powers_of_h.clone(),
             prepared_h: pp.prepared_h.clone(),
             prepared_beta_h: pp.prepared_beta_h.clone(),
         };
         Ok((powers, vk))
     }
 
-    /// Outputs a commitment to `polynomial`.
+    /// Outputs a commitment to `polynomial`, given in coefficient form.
     pub fn commit(powers: &Powers<E>, polynomial: &P) -> Result<Commitment<E>, Error> {
-        Self::check_degree_is_too_large(polynomial.degree(), powers.size())?;
+        Self::check_trimmed_degree(polynomial.degree(), powers)?;
 
         let (num_leading_zeros, plain_coeffs) =
             skip_leading_zeros_and_convert_to_bigints(polynomial);
@@ -158,6 +385,468 @@ where
         Ok(Commitment(commitment.into()))
     }
 
+    /// Outputs the same commitment as [`Self::commit`], but always runs the MSM over
+    /// every power in `ct_powers`, so the work doesn't depend on how many leading (or
+    /// trailing) coefficients of `polynomial` are zero. The arkworks MSM also skips
+    /// zero scalars and is faster when many scalars share a bucket, so the fixed masks
+    /// `m^{i+1}` are added to the coefficients first and their commitment subtracted
+    /// after. That commitment doesn't depend on `polynomial`, so
+    /// [`ConstantTimePowers::prepare`] computes it once.
+    pub fn commit_constant_time(
+        ct_powers: &ConstantTimePowers<E>,
+        polynomial: &P,
+    ) -> Result<Commitment<E>, Error> {
+        let powers = &ct_powers.powers;
+        Self::check_trimmed_degree(polynomial.degree(), powers)?;
+
+        let mut coeffs = polynomial.coeffs().to_vec();
+        coeffs.resize(powers.size(), E::Fr::zero());
+        let masked_coeffs: Vec<_> = ark_std::cfg_iter!(coeffs)
+            .zip(ark_std::cfg_iter!(ct_powers.masks))
+            .map(|(c, m)| (*c + m).into_repr())
+            .collect();
+        let masked = VariableBaseMSM::multi_scalar_mul(&powers.powers_of_g, &masked_coeffs);
+
+        Ok(Commitment((masked - ct_powers.mask_commitment).into()))
+    }
+
+    /// Outputs a commitment to `bytes`, packed into the coefficients of a polynomial
+    /// with [`pack_bytes_to_field`].
+    pub fn commit_bytes(powers: &Powers<E>, bytes: &[u8]) -> Result<Commitment<E>, Error> {
+        Self::commit(powers, &P::from_coefficients_vec(pack_bytes_to_field(bytes)))
+    }
+
+    /// Approximate number of group operations [`Self::commit`] does for a polynomial
+    /// of degree `degree`, for balancing commits across workers. It follows the
+    /// Pippenger schedule arkworks runs: every coefficient is added into a bucket in
+    /// each window, the buckets of a window are summed with two additions each, and
+    /// the window sums are combined with one doubling per scalar bit. The window grows
+    /// with the degree, so the estimate dips where it does, e.g. at `2^18`.
+    pub fn commit_cost_estimate(degree: usize) -> usize {
+        let num_scalars = degree + 1;
+        let c = msm_window_size(num_scalars);
+        let num_bits = E::Fr::size_in_bits();
+        let num_windows = num_bits.div_ceil(c);
+        num_windows * (num_scalars + 2 * ((1 << c) - 1)) + num_bits + num_windows
+    }
+
+    /// Proves that the hiding commitment made with `rand` by [`Self::commit_hiding`] is
+    /// a commitment to `public_poly`, which the verifier knows, by revealing the
+    /// blinding polynomial. The proof is `hiding_bound + 1` field elements whatever the
+    /// degree. It gives away the blinding, so the commitment is no longer hiding.
+    pub fn prove_equals_public(
+        powers: &Powers<E>,
+        rand: &Randomness<E>,
+        public_poly: &P,
+    ) -> Result<EqualityProof<E>, Error> {
+        Self::check_degree_is_too_large(public_poly.degree(), powers.size())?;
+        Ok(EqualityProof {
+            blinding_polynomial: rand.blinding_polynomial.clone(),
+        })
+    }
+
+    /// Verifies a proof from [`Self::prove_equals_public`], i.e. that `comm` minus the
+    /// revealed blinding `r(\beta) \gamma g` is the commitment to `public_poly`.
+    pub fn verify_equals_public(
+        powers: &Powers<E>,
+        comm: &Commitment<E>,
+        public_poly: &P,
+        proof: &EqualityProof<E>,
+    ) -> Result<bool, Error> {
+        if proof.blinding_polynomial.degree() >= powers.powers_of_gamma_g.len() {
+            return Ok(false);
+        }
+        let Commitment(public_comm) = Self::commit(powers, public_poly)?;
+        let blinding = Self::commit_blinding(powers, &proof.blinding_polynomial);
+        Ok(comm.0.into_projective() - blinding == public_comm.into_projective())
+    }
+
+    /// `r(\beta) \gamma g` for the blinding polynomial `r`, which must fit the powers.
+    fn commit_blinding(
+        powers: &Powers<E>,
+        blinding_polynomial: &DensePolynomial<E::Fr>,
+    ) -> E::G1Projective {
+        let (num_leading_zeros, blinding_coeffs) =
+            skip_leading_zeros_and_convert_to_bigints(blinding_polynomial);
+        VariableBaseMSM::multi_scalar_mul(
+            &powers.powers_of_gamma_g[num_leading_zeros..],
+            &blinding_coeffs,
+        )
+    }
+
+    /// Commits to each of `polynomials`, the same as calling [`Self::commit`] on each.
+    /// With the `parallel` feature the commitments are computed concurrently.
+    pub fn batch_commit(powers: &Powers<E>, polynomials: &[P]) -> Result<Vec<Commitment<E>>, Error>
+    where
+        P: Sync,
+    {
+        ark_std::cfg_iter!(polynomials)
+            .map(|p| Self::commit(powers, p))
+            .collect()
+    }
+
+    /// Outputs a hiding commitment to `polynomial`, `p(\beta) g + r(\beta) \gamma g` for
+    /// a random blinding polynomial `r` of degree `hiding_bound`. Up to `hiding_bound`
+    /// openings reveal nothing about `p` beyond the opened values. The returned
+    /// [`Randomness`] is needed to open with [`Self::open_hiding`].
+    pub fn commit_hiding<R: RngCore>(
+        powers: &Powers<E>,
+        polynomial: &P,
+        hiding_bound: usize,
+        rng: &mut R,
+    ) -> Result<(Commitment<E>, Randomness<E>), Error> {
+        if hiding_bound == 0 {
+            return Err(Error::HidingBoundIsZero);
+        }
+        if hiding_bound >= powers.powers_of_gamma_g.len() {
+            return Err(Error::HidingBoundTooLarge {
+                hiding_bound,
+                num_powers: powers.powers_of_gamma_g.len(),
+            });
+        }
+        let Commitment(commitment) = Self::commit(powers, polynomial)?;
+
+        let blinding_polynomial = DensePolynomial::rand(hiding_bound, rng);
+        let blinding = Self::commit_blinding(powers, &blinding_polynomial);
+
+        Ok((
+            Commitment((blinding + commitment.into_projective()).into()),
+            Randomness {
+                blinding_polynomial,
+            },
+        ))
+    }
+
+    /// Outputs a commitment to the polynomial `\sum_k c_k x^{i_k}` given by its nonzero
+    /// terms `(i_k, c_k)`. The MSM only runs over the terms, so this costs as much as
+    /// a dense commitment to `terms.len()` coefficients, whatever the degree.
+    pub fn commit_sparse(
+        powers: &Powers<E>,
+        terms: &[(usize, E::Fr)],
+    ) -> Result<Commitment<E>, Error> {
+        if let Some(&(degree, _)) = terms.iter().max_by_key(|(i, _)| *i) {
+            Self::check_trimmed_degree(degree, powers)?;
+        }
+        let bases: Vec<_> = terms.iter().map(|(i, _)| powers.powers_of_g[*i]).collect();
+        let scalars: Vec<_> = terms.iter().map(|(_, c)| c.into_repr()).collect();
+        let commitment = VariableBaseMSM::multi_scalar_mul(&bases, &scalars);
+        Ok(Commitment(commitment.into()))
+    }
+
+    /// Outputs a commitment to `polynomial` in G2, `p(\beta) h`, for protocols that keep
+    /// commitments in G2 and proofs in G1. `powers_of_h` are the `\beta^i h`, e.g.
+    /// [`VerifierKey::powers_of_h`]. Openings are ordinary [`Self::open`] proofs,
+    /// checked with [`Self::check_g2_commit`].
+    pub fn commit_g2(powers_of_h: &[E::G2Affine], polynomial: &P) -> Result<E::G2Affine, Error> {
+        if polynomial.degree() >= powers_of_h.len() {
+            return Err(Error::PolynomialExceedsTrimmedDegree {
+                poly_degree: polynomial.degree(),
+                supported: powers_of_h.len().saturating_sub(1),
+            });
+        }
+
+        let (num_leading_zeros, plain_coeffs) =
+            skip_leading_zeros_and_convert_to_bigints(polynomial);
+
+        let commitment = VariableBaseMSM::multi_scalar_mul(
+            &powers_of_h[num_leading_zeros..],
+            &plain_coeffs,
+        );
+
+        Ok(commitment.into())
+    }
+
+    /// Outputs a commitment to `polynomial` against the window `powers_of_g[offset..]`
+    /// of the SRS, i.e. to `x^{offset} p(x)`, so the same polynomial can be committed at
+    /// different heights. Openings are ordinary [`Self::open`] proofs of `polynomial`,
+    /// checked with [`Self::check_windowed`].
+    pub fn commit_windowed(
+        powers: &Powers<E>,
+        polynomial: &P,
+        offset: usize,
+    ) -> Result<Commitment<E>, Error> {
+        Self::check_trimmed_degree(polynomial.degree() + offset, powers)?;
+
+        let (num_leading_zeros, plain_coeffs) =
+            skip_leading_zeros_and_convert_to_bigints(polynomial);
+
+        let commitment = VariableBaseMSM::multi_scalar_mul(
+            &powers.powers_of_g[offset + num_leading_zeros..],
+            &plain_coeffs,
+        );
+
+        Ok(Commitment(commitment.into()))
+    }
+
+    /// Same as [`Self::commit`], but runs a plain Pippenger MSM and also returns the
+    /// partial sum of each `c`-bit window: the buckets of the window summed with
+    /// their weights, then scaled by `2^{wc}` for window `w`. They add up to the
+    /// commitment, so a wrong window points at where a faster MSM goes wrong.
+    #[cfg(feature = "debug-msm")]
+    pub fn commit_debug(
+        powers: &Powers<E>,
+        polynomial: &P,
+    ) -> Result<(Commitment<E>, Vec<E::G1Projective>), Error> {
+        use ark_ff::BigInteger;
+
+        Self::check_degree_is_too_large(polynomial.degree(), powers.size())?;
+        let (num_leading_zeros, scalars) = skip_leading_zeros_and_convert_to_bigints(polynomial);
+        let bases = &powers.powers_of_g[num_leading_zeros..];
+
+        let c = msm_window_size(scalars.len());
+        let num_bits = E::Fr::size_in_bits();
+
+        let partial_sums: Vec<E::G1Projective> = (0..num_bits)
+            .step_by(c)
+            .map(|w_start| {
+                let mut buckets = vec![E::G1Projective::zero(); (1 << c) - 1];
+                for (scalar, base) in scalars.iter().zip(bases) {
+                    let mut scalar = *scalar;
+                    scalar.divn(w_start as u32);
+                    let index = (scalar.as_ref()[0] % (1 << c)) as usize;
+                    if index != 0 {
+                        buckets[index - 1].add_assign_mixed(base);
+                    }
+                }
+                // Bucket `i` is added `i + 1` times by the running sum
+                let mut running_sum = E::G1Projective::zero();
+                let mut window_sum = E::G1Projective::zero();
+                for bucket in buckets.into_iter().rev() {
+                    running_sum += &bucket;
+                    window_sum += &running_sum;
+                }
+                for _ in 0..w_start {
+                    ProjectiveCurve::double_in_place(&mut window_sum);
+                }
+                window_sum
+            })
+            .collect();
+
+        let commitment: E::G1Projective = partial_sums.iter().sum();
+        Ok((Commitment(commitment.into()), partial_sums))
+    }
+
+    /// Outputs a commitment to `p`, whose coefficients must be palindromic,
+    /// `a_i == a_{degree - i}`. Folding the bases into `G_i + G_{degree - i}` halves the
+    /// size of the MSM.
+    pub fn commit_palindromic(powers: &Powers<E>, p: &P) -> Result<Commitment<E>, Error> {
+        let degree = p.degree();
+        Self::check_degree_is_too_large(degree, powers.size())?;
+        let coeffs = p.coeffs();
+        if coeffs.is_empty() {
+            return Ok(Commitment(E::G1Affine::zero()));
+        }
+        if let Some(index) = (0..=degree / 2).find(|&i| coeffs[i] != coeffs[degree - i]) {
+            return Err(Error::NotPalindromic { degree, index });
+        }
+        let half_coeffs = &coeffs[..=degree / 2];
+
+        let folded_bases: Vec<_> = (0..half_coeffs.len())
+            .map(|i| {
+                let mut base = powers.powers_of_g[i].into_projective();
+                // The middle coefficient of an even degree polynomial has no pair
+                if i != degree - i {
+                    base.add_assign_mixed(&powers.powers_of_g[degree - i]);
+                }
+                base
+            })
+            .collect();
+        let folded_bases = E::G1Projective::batch_normalization_into_affine(&folded_bases);
+        let commitment =
+            VariableBaseMSM::multi_scalar_mul(&folded_bases, &convert_to_bigints(half_coeffs));
+
+        Ok(Commitment(commitment.into()))
+    }
+
+    /// Commits to `p` in segments of `chunk_degree + 1` coefficients, so `p` can have a
+    /// higher degree than `powers` supports. Writing `N = chunk_degree + 1`, this is
+    /// `p(x) = \sum_k x^{k N} p_k(x)`, and the `k`-th commitment is to `p_k`.
+    pub fn commit_segmented(
+        powers: &Powers<E>,
+        p: &P,
+        chunk_degree: usize,
+    ) -> Result<Vec<Commitment<E>>, Error> {
+        Self::check_degree_is_too_large(chunk_degree, powers.size())?;
+        p.coeffs()
+            .chunks(chunk_degree + 1)
+            .map(|segment| Self::commit(powers, &P::from_coefficients_slice(segment)))
+            .collect()
+    }
+
+    /// Returns false for the identity commitment, which is what the zero polynomial
+    /// commits to. This is only a cheap filter to run before full verification: it
+    /// doesn't prove the committer knows the committed polynomial, and a nonzero
+    /// polynomial can still commit to the identity if it vanishes at `\beta`.
+    pub fn is_nontrivial(comm: &Commitment<E>) -> bool {
+        !comm.0.is_zero()
+    }
+
+    /// Outputs a commitment to the polynomial with coefficients `coeffs`, lowest degree
+    /// first, without collecting them. Coefficients are consumed in chunks of
+    /// [`COMMIT_CHUNK_SIZE`], each of which is a separate MSM, so a polynomial backed by
+    /// e.g. a memory-mapped file is never fully loaded.
+    pub fn commit_from_iter(
+        powers: &Powers<E>,
+        coeffs: impl ExactSizeIterator<Item = E::Fr>,
+    ) -> Result<Commitment<E>, Error> {
+        Self::commit_from_iter_chunked(powers, coeffs, COMMIT_CHUNK_SIZE)
+    }
+
+    fn commit_from_iter_chunked(
+        powers: &Powers<E>,
+        coeffs: impl ExactSizeIterator<Item = E::Fr>,
+        chunk_size: usize,
+    ) -> Result<Commitment<E>, Error> {
+        let num_coefficients = coeffs.len();
+        if num_coefficients > powers.size() {
+            return Err(Error::TooManyCoefficients {
+                num_coefficients,
+                num_powers: powers.size(),
+            });
+        }
+
+        let mut coeffs = coeffs.enumerate().skip_while(|(_, c)| c.is_zero()).peekable();
+        let mut commitment = E::G1Projective::zero();
+        let mut chunk = Vec::with_capacity(chunk_size.min(num_coefficients));
+        while let Some(&(start, _)) = coeffs.peek() {
+            chunk.clear();
+            chunk.extend(coeffs.by_ref().take(chunk_size).map(|(_, c)| c.into_repr()));
+            // len() is only a promise, so an iterator can still run past the powers here
+            let bases = match powers.powers_of_g.get(start..start + chunk.len()) {
+                Some(bases) => bases,
+                None => {
+                    return Err(Error::TooManyCoefficients {
+                        num_coefficients: start + chunk.len(),
+                        num_powers: powers.size(),
+                    })
+                }
+            };
+            commitment += &VariableBaseMSM::multi_scalar_mul(bases, &chunk);
+        }
+
+        Ok(Commitment(commitment.into()))
+    }
+
+    /// Same as [`Self::open`] for the polynomial with coefficients `coeffs`, highest
+    /// degree first, also returning its value at `point`. Synthetic division produces the
+    /// witness coefficients in that order, and they are committed in chunks of
+    /// [`COMMIT_CHUNK_SIZE`] as they come, so neither polynomial is ever collected.
+    pub fn open_from_iter(
+        powers: &Powers<E>,
+        coeffs: impl ExactSizeIterator<Item = E::Fr>,
+        point: E::Fr,
+    ) -> Result<(E::Fr, Proof<E>), Error> {
+        Self::open_from_iter_chunked(powers, coeffs, point, COMMIT_CHUNK_SIZE)
+    }
+
+    fn open_from_iter_chunked(
+        powers: &Powers<E>,
+        mut coeffs: impl ExactSizeIterator<Item = E::Fr>,
+        point: E::Fr,
+        chunk_size: usize,
+    ) -> Result<(E::Fr, Proof<E>), Error> {
+        let num_coefficients = coeffs.len();
+        if num_coefficients > powers.size() {
+            return Err(Error::TooManyCoefficients {
+                num_coefficients,
+                num_powers: powers.size(),
+            });
+        }
+
+        // After folding in the coefficient of x^i, the running remainder is the witness
+        // coefficient of x^{i-1}, and after the constant term it is the value at `point`
+        let mut remainder = E::Fr::zero();
+        let mut w = E::G1Projective::zero();
+        let mut chunk = Vec::with_capacity(chunk_size.min(num_coefficients));
+        for i in (0..num_coefficients).rev() {
+            let coeff = match coeffs.next() {
+                Some(coeff) => coeff,
+                None => {
+                    return Err(Error::WrongCoefficientCount {
+                        reported: num_coefficients,
+                        yielded: num_coefficients - 1 - i,
+                    })
+                }
+            };
+            remainder = remainder * point + coeff;
+            if i == 0 {
+                break;
+            }
+            chunk.push(remainder.into_repr());
+            if chunk.len() == chunk_size || i == 1 {
+                // The chunk holds the witness coefficients of x^{i-1} and up, highest first
+                chunk.reverse();
+                w += &VariableBaseMSM::multi_scalar_mul(
+                    &powers.powers_of_g[i - 1..i - 1 + chunk.len()],
+                    &chunk,
+                );
+                chunk.clear();
+            }
+        }
+        let extra = coeffs.count();
+        if extra > 0 {
+            return Err(Error::WrongCoefficientCount {
+                reported: num_coefficients,
+                yielded: num_coefficients + extra,
+            });
+        }
+
+        let proof = Proof { w: w.into_affine() };
+        Ok((remainder, proof))
+    }
+
+    /// Outputs a commitment to the polynomial `p` with `evals[i] = p(\omega^i)`, where
+    /// `\omega` generates `domain`. This is the natural order, the same order arkworks'
+    /// `fft` produces and `ifft` consumes.
+    pub fn commit_lagrange<D: EvaluationDomain<E::Fr>>(
+        powers: &Powers<E>,
+        domain: &D,
+        evals: &[E::Fr],
+    ) -> Result<Commitment<E>, Error> {
+        Self::check_degree_is_too_large(evals.len().saturating_sub(1), domain.size())?;
+        let coeffs = domain.ifft(evals);
+        Self::commit(powers, &P::from_coefficients_vec(coeffs))
+    }
+
+    /// Outputs a commitment to the polynomial `p` with `evals[i] = p(\omega^{rev(i)})`,
+    /// where `rev` reverses the `log2(domain.size())` low bits of `i`. This is the
+    /// order used by implementations whose FFTs skip the final bit-reversal pass.
+    /// See [`bit_reverse_permutation`].
+    pub fn commit_lagrange_bitreversed<D: EvaluationDomain<E::Fr>>(
+        powers: &Powers<E>,
+        domain: &D,
+        evals: &[E::Fr],
+    ) -> Result<Commitment<E>, Error> {
+        Self::check_degree_is_too_large(evals.len().saturating_sub(1), domain.size())?;
+        let mut natural = vec![E::Fr::zero(); domain.size()];
+        for (i, rev_i) in bit_reverse_permutation(domain.size()).into_iter().enumerate() {
+            if let Some(e) = evals.get(i) {
+                natural[rev_i] = *e;
+            }
+        }
+        Self::commit_lagrange(powers, domain, &natural)
+    }
+
+    /// Outputs a commitment to the polynomial `p` with `evals[i] = p(s \omega^i)` over the
+    /// coset `s * domain`, for a nonzero `coset_shift` `s`. The inverse FFT gives the
+    /// coefficients of `p(s x)`, so coefficient `i` is scaled by `s^{-i}` before committing.
+    pub fn commit_coset<D: EvaluationDomain<E::Fr>>(
+        powers: &Powers<E>,
+        domain: &D,
+        evals: &[E::Fr],
+        coset_shift: E::Fr,
+    ) -> Result<Commitment<E>, Error> {
+        Self::check_degree_is_too_large(evals.len().saturating_sub(1), domain.size())?;
+        let shift_inv = coset_shift.inverse().ok_or(Error::ZeroCosetShift)?;
+        let mut coeffs = domain.ifft(evals);
+        let mut scale = E::Fr::one();
+        for c in coeffs.iter_mut() {
+            *c *= scale;
+            scale *= shift_inv;
+        }
+        Self::commit(powers, &P::from_coefficients_vec(coeffs))
+    }
+
     /// Compute witness polynomial.
     ///
     /// The witness polynomial w(x) the quotient of the division (p(x) - p(z)) / (x - z)
@@ -187,15 +876,153 @@ where
         Ok(Proof { w: w.into_affine() })
     }
 
-    /// On input a polynomial `p` and a point `point`, outputs a proof for the same.
-    pub fn open<'a>(powers: &Powers<E>, p: &P, point: P::Point) -> Result<Proof<E>, Error> {
+    /// Outputs the commitment to the witness polynomial of `p` at `point`, before
+    /// conversion to affine. Witnesses from many proofs can then be combined in
+    /// projective form and normalized together.
+    pub fn witness_commitment(
+        powers: &Powers<E>,
+        p: &P,
+        point: P::Point,
+    ) -> Result<E::G1Projective, Error> {
         Self::check_degree_is_too_large(p.degree(), powers.size())?;
 
         let witness_poly = Self::compute_witness_polynomial(p, point)?;
+        let (num_leading_zeros, witness_coeffs) =
+            skip_leading_zeros_and_convert_to_bigints(&witness_poly);
+
+        Ok(VariableBaseMSM::multi_scalar_mul(
+            &powers.powers_of_g[num_leading_zeros..],
+            &witness_coeffs,
+        ))
+    }
+
+    /// On input a polynomial `p` and a point `point`, outputs a proof for the same.
+    pub fn open<'a>(powers: &Powers<E>, p: &P, point: P::Point) -> Result<Proof<E>, Error> {
+        let w = Self::witness_commitment(powers, p, point)?;
+
+        Ok(Proof { w: w.into_affine() })
+    }
+
+    /// Same as [`Self::open`], at an element of an evaluation domain. See
+    /// [`Self::check_at_domain_point`].
+    pub fn open_at_domain_point(
+        powers: &Powers<E>,
+        p: &P,
+        point: DomainPoint<E::Fr>,
+    ) -> Result<Proof<E>, Error> {
+        Self::open(powers, p, point.element())
+    }
+
+    /// The sum of the evaluations of `p` over `domain`, with a proof of it. Over a
+    /// multiplicative subgroup of size `n`, the powers `\omega^{ij}` sum to zero unless
+    /// `n` divides `j`, so for `p` of degree below `n` the sum is `n p(0)` and the proof
+    /// is an opening at zero. See [`Self::verify_domain_sum`].
+    pub fn prove_domain_sum<D: EvaluationDomain<E::Fr>>(
+        powers: &Powers<E>,
+        p: &P,
+        domain: &D,
+    ) -> Result<(E::Fr, Proof<E>), Error> {
+        if p.degree() >= domain.size() {
+            return Err(Error::ExceedsDomainSize {
+                poly_degree: p.degree(),
+                domain_size: domain.size(),
+            });
+        }
+        let zero = E::Fr::zero();
+        let sum = p.evaluate(&zero) * domain.size_as_field_element();
+        Ok((sum, Self::open(powers, p, zero)?))
+    }
+
+    /// Same as [`Self::open`], but for a hiding commitment from [`Self::commit_hiding`].
+    /// The witness of the blinding polynomial is committed against the powers of
+    /// `\gamma g` and added to `w`, and its value at `point` is sent along in
+    /// `random_v`. See [`Self::check_hiding`].
+    pub fn open_hiding(
+        powers: &Powers<E>,
+        p: &P,
+        point: P::Point,
+        rand: &Randomness<E>,
+    ) -> Result<HidingProof<E>, Error> {
+        let mut w = Self::witness_commitment(powers, p, point)?;
+
+        let blinding = &rand.blinding_polynomial;
+        let random_v = blinding.evaluate(&point);
+        let divisor = DensePolynomial::from_coefficients_vec(vec![-point, E::Fr::one()]);
+        let blinding_witness = blinding / &divisor;
+        let (num_leading_zeros, blinding_coeffs) =
+            skip_leading_zeros_and_convert_to_bigints(&blinding_witness);
+        w += &VariableBaseMSM::multi_scalar_mul(
+            &powers.powers_of_gamma_g[num_leading_zeros..],
+            &blinding_coeffs,
+        );
+
+        Ok(HidingProof {
+            proof: Proof { w: w.into_affine() },
+            random_v,
+        })
+    }
+
+    /// Same as [`Self::open`], but for the polynomial `p` with `evals[i] = p(\omega^i)`
+    /// over `domain`, given only its evaluations. The quotient
+    /// `q(x) = (p(x) - p(z)) / (x - z)` is computed in evaluation form, as
+    /// `q(\omega^i) = (e_i - p(z)) / (\omega^i - z)`, and committed with
+    /// [`Self::commit_lagrange`].
+    ///
+    /// When `z = \omega^m` is in the domain this is undefined at `i = m`, and
+    /// `q(\omega^m) = \sum_{i \ne m} (e_i - e_m) \omega^i / (\omega^m (\omega^m - \omega^i))`
+    /// instead. Outside the domain, `p(z)` is found with the barycentric formula.
+    pub fn open_from_evals<D: EvaluationDomain<E::Fr>>(
+        powers: &Powers<E>,
+        evals: &[E::Fr],
+        domain: &D,
+        point: E::Fr,
+    ) -> Result<Proof<E>, Error> {
+        let n = domain.size();
+        if evals.len() != n {
+            return Err(Error::WrongNumberOfEvaluations {
+                expected: n,
+                got: evals.len(),
+            });
+        }
+        let roots: Vec<E::Fr> = domain.elements().collect();
+        let in_domain = roots.iter().position(|r| *r == point);
+
+        let mut denoms: Vec<E::Fr> = roots.iter().map(|r| *r - point).collect();
+        if let Some(m) = in_domain {
+            // Keeps the batch inversion from failing, the entry is overwritten below
+            denoms[m] = E::Fr::one();
+        }
+        ark_ff::batch_inversion(&mut denoms);
+
+        let value = match in_domain {
+            Some(m) => evals[m],
+            None => {
+                // p(z) = (z^n - 1) / n * \sum_i e_i \omega^i / (z - \omega^i)
+                let sum = evals
+                    .iter()
+                    .zip(roots.iter())
+                    .zip(denoms.iter())
+                    .fold(E::Fr::zero(), |acc, ((e, r), d)| acc - *e * r * d);
+                let n_inv = domain.size_as_field_element().inverse().unwrap();
+                sum * (point.pow([n as u64]) - E::Fr::one()) * n_inv
+            }
+        };
 
-        let proof = Self::open_with_witness_polynomial(powers, &witness_poly);
+        let mut quotient: Vec<E::Fr> = evals
+            .iter()
+            .zip(denoms.iter())
+            .map(|(e, d)| (*e - value) * d)
+            .collect();
+        if let Some(m) = in_domain {
+            let point_inv = point.inverse().unwrap();
+            quotient[m] = (0..n).filter(|&i| i != m).fold(E::Fr::zero(), |acc, i| {
+                // (e_i - e_m) / (\omega^i - z) is already in `quotient[i]`
+                acc - quotient[i] * roots[i] * point_inv
+            });
+        }
 
-        proof
+        let w = Self::commit_lagrange(powers, domain, &quotient)?;
+        Ok(Proof { w: w.0 })
     }
 
     /// Verifies that `value` is the evaluation at `point` of the polynomial
@@ -216,277 +1043,3060 @@ where
         Ok(lhs == rhs)
     }
 
-    /// Check that each `proof_i` in `proofs` is a valid proof of evaluation for
-    /// `commitment_i` at `point_i`.
-    pub fn batch_check<R: RngCore>(
+    /// Removes the blinding value of `proof` from the hiding commitment `comm`, leaving
+    /// `comm - random_v \gamma g`, which `proof.proof` opens like an ordinary
+    /// commitment. The result can go to [`Self::batch_check`] or
+    /// [`Self::aggregate_proofs`] with other claims.
+    pub fn unblind(
         vk: &VerifierKey<E>,
-        commitments: &[Commitment<E>],
-        points: &[E::Fr],
-        values: &[E::Fr],
-        proofs: &[Proof<E>],
-        rng: &mut R,
+        comm: &Commitment<E>,
+        proof: &HidingProof<E>,
+    ) -> Commitment<E> {
+        Commitment((comm.0.into_projective() - vk.gamma_g.mul(proof.random_v)).into())
+    }
+
+    /// Verifies a proof from [`Self::open_hiding`] that `value` is the evaluation at
+    /// `point` of the polynomial inside the hiding commitment `comm`.
+    pub fn check_hiding(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &HidingProof<E>,
     ) -> Result<bool, Error> {
-        let mut total_c = <E::G1Projective>::zero();
-        let mut total_w = <E::G1Projective>::zero();
+        Self::check(vk, &Self::unblind(vk, comm, proof), point, value, &proof.proof)
+    }
 
-        let mut randomizer = E::Fr::one();
-        // Instead of multiplying g and gamma_g in each turn, we simply accumulate
-        // their coefficients and perform a final multiplication at the end.
-        let mut g_multiplier = E::Fr::zero();
-        let gamma_g_multiplier = E::Fr::zero();
-        for (((c, z), v), proof) in commitments.iter().zip(points).zip(values).zip(proofs) {
-            let w = proof.w;
-            let mut temp = w.mul(*z);
-            temp.add_assign_mixed(&c.0);
-            let c = temp;
-            g_multiplier += &(randomizer * v);
-            total_c += &c.mul(randomizer.into_repr());
-            total_w += &w.mul(randomizer.into_repr());
-            // We don't need to sample randomizers from the full field,
-            // only from 128-bit strings.
-            randomizer = u128::rand(rng).into();
-        }
-        total_c -= &vk.g.mul(g_multiplier);
-        total_c -= &vk.gamma_g.mul(gamma_g_multiplier);
+    /// Verifies a proof from [`Self::prove_domain_sum`] that the evaluations of the
+    /// committed polynomial over `domain` add up to `sum`. The commitment doesn't bound
+    /// the degree, so this is only sound if every polynomial the universal parameters
+    /// can commit to fits in `domain`, and smaller domains are rejected.
+    pub fn verify_domain_sum<D: EvaluationDomain<E::Fr>>(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        domain: &D,
+        sum: E::Fr,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        if domain.size() <= vk.max_degree {
+            return Err(Error::DomainNotLargerThanDegree {
+                domain_size: domain.size(),
+                supported_degree: vk.max_degree,
+            });
+        }
+        let size_inv = domain.size_as_field_element().inverse().unwrap();
+        Self::check(vk, comm, E::Fr::zero(), sum * size_inv, proof)
+    }
 
-        let affine_points = E::G1Projective::batch_normalization_into_affine(&[-total_w, total_c]);
-        let (total_w, total_c) = (affine_points[0], affine_points[1]);
+    /// Proves that `p` vanishes on `domain`, i.e. that `Z_H(x) = x^n - 1` divides `p`,
+    /// with a commitment to the quotient `p / Z_H` in `w`.
+    /// See [`Self::verify_vanishes_on_domain`].
+    pub fn prove_vanishes_on_domain<D: EvaluationDomain<E::Fr>>(
+        powers: &Powers<E>,
+        p: &P,
+        domain: &D,
+    ) -> Result<Proof<E>, Error> {
+        let p = DensePolynomial::from_coefficients_slice(p.coeffs());
+        let (quotient, remainder) = p.divide_by_vanishing_poly(*domain).unwrap();
+        if !remainder.is_zero() {
+            return Err(Error::DoesNotVanishOnDomain {
+                domain_size: domain.size(),
+            });
+        }
+        let Commitment(w) = Self::commit(powers, &P::from_coefficients_vec(quotient.coeffs))?;
+        Ok(Proof { w })
+    }
 
-        let result = E::product_of_pairings(&[
-            (total_w.into(), vk.prepared_beta_h.clone()),
-            (total_c.into(), vk.prepared_h.clone()),
+    /// Verifies a proof from [`Self::prove_vanishes_on_domain`] by checking
+    /// `e(comm, h) == e(w, Z_H(\beta) h)`, where `Z_H(\beta) h = \beta^n h - h` needs
+    /// the `n`-th power of `h` in `vk`.
+    pub fn verify_vanishes_on_domain<D: EvaluationDomain<E::Fr>>(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        domain: &D,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let n = domain.size();
+        if n >= vk.powers_of_h.len() {
+            return Err(Error::MissingG2Power {
+                power: n,
+                num_powers: vk.powers_of_h.len(),
+            });
+        }
+        let vanishing_h = vk.powers_of_h[n].into_projective() - vk.h.into_projective();
+        Ok(E::product_of_pairings(&[
+            (comm.0.into(), vk.prepared_h.clone()),
+            ((-proof.w).into(), vanishing_h.into_affine().into()),
         ])
-        .is_one();
-        Ok(result)
+        .is_one())
+    }
+
+    /// Verifies a proof from [`Self::open_at_domain_point`].
+    pub fn check_at_domain_point(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        point: DomainPoint<E::Fr>,
+        value: E::Fr,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        Self::check(vk, comm, point.element(), value, proof)
+    }
+
+    /// Same as [`Self::check`], but rearranged as
+    /// `e(comm - value g + point w, h) * e(-w, \beta h) == 1` so it can use the
+    /// prepared `h` and `\beta h` in `vk` and a single final exponentiation.
+    pub fn check_prepared(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let inner = comm.0.into_projective() - vk.g.mul(value) + proof.w.mul(point);
+        let affine_points = E::G1Projective::batch_normalization_into_affine(&[
+            inner,
+            -proof.w.into_projective(),
+        ]);
+
+        Ok(E::product_of_pairings(&[
+            (affine_points[0].into(), vk.prepared_h.clone()),
+            (affine_points[1].into(), vk.prepared_beta_h.clone()),
+        ])
+        .is_one())
+    }
+
+    /// Same as [`Self::check_prepared`], but with as few projective temporaries as
+    /// possible for verifiers short on memory: `comm` and `-w` are added in affine
+    /// form with mixed addition, and only the one resulting point is normalized, so
+    /// there is no batch normalization buffer. The pairing product takes its prepared
+    /// points by value, so the two prepared G2 points of `vk` are still cloned, and
+    /// their line coefficients are the bulk of what this allocates.
+    pub fn check_low_mem(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let mut inner = proof.w.mul(point);
+        inner.add_assign_mixed(&comm.0);
+        inner -= &vk.g.mul(value);
+
+        Ok(E::product_of_pairings(&[
+            (inner.into_affine().into(), vk.prepared_h.clone()),
+            ((-proof.w).into(), vk.prepared_beta_h.clone()),
+        ])
+        .is_one())
+    }
+
+    /// Verifies that `value` is the evaluation at `point` of the polynomial committed in
+    /// G2 inside `comm_g2`, see [`Self::commit_g2`]. The pairing is swapped with respect
+    /// to [`Self::check`] and rearranged as
+    /// `e(g, comm) * e(point w - value g, h) * e(-w, \beta h) == 1`, so only the
+    /// commitment has to be prepared and all the scalar multiplications are in G1.
+    pub fn check_g2_commit(
+        vk: &VerifierKey<E>,
+        comm_g2: &E::G2Affine,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let inner = proof.w.mul(point) - vk.g.mul(value);
+        let affine_points = E::G1Projective::batch_normalization_into_affine(&[
+            inner,
+            -proof.w.into_projective(),
+        ]);
+
+        Ok(E::product_of_pairings(&[
+            (vk.g.into(), (*comm_g2).into()),
+            (affine_points[0].into(), vk.prepared_h.clone()),
+            (affine_points[1].into(), vk.prepared_beta_h.clone()),
+        ])
+        .is_one())
+    }
+
+    /// Verifies an ordinary [`Self::open`] proof that `p(point) = value` against a
+    /// commitment from [`Self::commit_windowed`] at height `offset`. With
+    /// `comm = \beta^{offset} p(\beta) g` and `p(\beta) g = value g + (\beta - point) w`,
+    /// this checks `e(comm, h) == e(value g - point w, \beta^{offset} h) *
+    /// e(w, \beta^{offset + 1} h)`, so `vk` needs the powers of `h` up to `offset + 1`.
+    pub fn check_windowed(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        offset: usize,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        if offset + 1 >= vk.powers_of_h.len() {
+            return Err(Error::WindowOffsetTooLarge {
+                offset,
+                max_offset: vk.powers_of_h.len().saturating_sub(2),
+            });
+        }
+        let inner = vk.g.mul(value) - proof.w.mul(point);
+        let affine_points = E::G1Projective::batch_normalization_into_affine(&[
+            inner,
+            proof.w.into_projective(),
+            -comm.0.into_projective(),
+        ]);
+
+        Ok(E::product_of_pairings(&[
+            (affine_points[0].into(), vk.powers_of_h[offset].into()),
+            (affine_points[1].into(), vk.powers_of_h[offset + 1].into()),
+            (affine_points[2].into(), vk.prepared_h.clone()),
+        ])
+        .is_one())
+    }
+
+    /// Opens `p` at all of `points` with a single proof, returning the evaluations and
+    /// a commitment to `q(x) = p(x) / Z(x)`, where `Z` vanishes on `points`.
+    pub fn open_multi_points(
+        powers: &Powers<E>,
+        p: &P,
+        points: &[E::Fr],
+    ) -> Result<(Vec<E::Fr>, Proof<E>), Error> {
+        Self::check_degree_is_too_large(p.degree(), powers.size())?;
+
+        let values = points.iter().map(|z| p.evaluate(z)).collect();
+        // The remainder of the division is the interpolation of `values`
+        let quotient = p / &P::from_coefficients_vec(vanishing_polynomial(points));
+        let proof = Self::open_with_witness_polynomial(powers, &quotient)?;
+
+        Ok((values, proof))
+    }
+
+    /// Verifies a proof from [`Self::open_multi_points`] that `values[i]` is the
+    /// evaluation at `points[i]` of the polynomial committed inside `comm`. With `I` the
+    /// interpolation of `values` over `points`, this checks
+    /// `e(comm, h) == e(proof, Z(\beta) h) * e(g, I(\beta) h)`, so both `Z` and `I` are
+    /// evaluated in G2 and need `points.len() + 1` powers of `h`.
+    pub fn check_multi_points(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        points: &[E::Fr],
+        values: &[E::Fr],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        if points.len() != values.len() {
+            return Err(Error::WrongNumberOfEvaluations {
+                expected: points.len(),
+                got: values.len(),
+            });
+        }
+        // Opening at k points needs \beta^k h for the vanishing polynomial
+        let max_points = match vk.powers_of_h.len().checked_sub(1) {
+            Some(max_points) => max_points,
+            None => {
+                return Err(Error::MissingG2Power {
+                    power: points.len(),
+                    num_powers: 0,
+                })
+            }
+        };
+        if points.len() > max_points {
+            return Err(Error::TooManyPoints {
+                num_points: points.len(),
+                max_points,
+            });
+        }
+
+        let vanishing = convert_to_bigints(&vanishing_polynomial(points));
+        let interpolation = lagrange_interpolate(points, values).ok_or(Error::DuplicatePoints)?;
+        let interpolation = convert_to_bigints(&interpolation);
+        let vanishing_h = VariableBaseMSM::multi_scalar_mul(&vk.powers_of_h, &vanishing);
+        let interpolation_h = VariableBaseMSM::multi_scalar_mul(&vk.powers_of_h, &interpolation);
+
+        let lhs = E::pairing(comm.0, vk.h);
+        let rhs = E::pairing(proof.w, vanishing_h) * E::pairing(vk.g, interpolation_h);
+
+        Ok(lhs == rhs)
+    }
+
+    /// Discloses the evaluations of `p` at `points` with a single proof. This is
+    /// [`Self::open_multi_points`] under a name that says what the caller gets out of
+    /// it. It doesn't hide the rest of `p`: the commitment and the proof are
+    /// deterministic in `p`, so a verifier can check any guess about it against them.
+    pub fn open_subset(
+        powers: &Powers<E>,
+        p: &P,
+        points: &[E::Fr],
+    ) -> Result<(Vec<E::Fr>, Proof<E>), Error> {
+        Self::open_multi_points(powers, p, points)
+    }
+
+    /// Verifies a disclosure from [`Self::open_subset`]. The proof is bound to the
+    /// disclosed set: it does not verify against any other set of points.
+    pub fn check_subset(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        points: &[E::Fr],
+        values: &[E::Fr],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        Self::check_multi_points(vk, comm, points, values, proof)
+    }
+
+    /// Opens every polynomial in `polys`, committed in `comms`, at all of `points` with
+    /// one proof. The polynomials are combined as `\sum_k \gamma^k p_k` and the
+    /// combination opened with [`Self::open_multi_points`], with `\gamma` hashed from
+    /// the commitments, points and values. Returns `values[k][i] = p_k(points[i])`.
+    pub fn open_shared_points(
+        powers: &Powers<E>,
+        polys: &[P],
+        comms: &[Commitment<E>],
+        points: &[E::Fr],
+    ) -> Result<(EvaluationMatrix<E::Fr>, Proof<E>), Error> {
+        let values: EvaluationMatrix<E::Fr> = polys
+            .iter()
+            .map(|p| points.iter().map(|z| p.evaluate(z)).collect())
+            .collect();
+        let gamma = shared_points_challenge(comms, points, &values);
+        let combined = Self::combine_polys(powers, polys, gamma)?;
+        let (_, proof) = Self::open_multi_points(powers, &combined, points)?;
+        Ok((values, proof))
+    }
+
+    /// Verifies a proof from [`Self::open_shared_points`] that `value_matrix[k][i]` is
+    /// the evaluation at `points[i]` of the polynomial committed in `comms[k]`. As all
+    /// the commitments share `points`, the vanishing polynomial is evaluated in G2 once
+    /// for the whole batch rather than once per commitment.
+    pub fn batch_check_shared_points(
+        vk: &VerifierKey<E>,
+        comms: &[Commitment<E>],
+        points: &[E::Fr],
+        value_matrix: &[Vec<E::Fr>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        if value_matrix.len() != comms.len() {
+            return Err(Error::WrongNumberOfEvaluations {
+                expected: comms.len(),
+                got: value_matrix.len(),
+            });
+        }
+        if let Some(values) = value_matrix.iter().find(|v| v.len() != points.len()) {
+            return Err(Error::WrongNumberOfEvaluations {
+                expected: points.len(),
+                got: values.len(),
+            });
+        }
+        let gamma = shared_points_challenge(comms, points, value_matrix);
+        let rows = value_matrix.iter().map(Vec::as_slice);
+        let (combined, combined_values) =
+            Self::combine_evaluations(comms, rows, points.len(), gamma);
+        Self::check_multi_points(vk, &combined, points, &combined_values, proof)
+    }
+
+    /// Opens every polynomial in `polys` at the same `point` with one proof, an opening
+    /// of `\sum_k c^k p_k` for the `challenge` `c`, so only one witness is committed.
+    /// `challenge` must be chosen after the commitments and values are fixed, e.g. by
+    /// the verifier or hashed from them. See [`Self::batch_check_same_point`].
+    pub fn batch_open_same_point(
+        powers: &Powers<E>,
+        polys: &[P],
+        point: E::Fr,
+        challenge: E::Fr,
+    ) -> Result<Proof<E>, Error> {
+        let combined = Self::combine_polys(powers, polys, challenge)?;
+        Self::open(powers, &combined, point)
+    }
+
+    /// Verifies a proof from [`Self::batch_open_same_point`] that `values[k]` is the
+    /// evaluation at `point` of the polynomial committed in `comms[k]`. The commitments
+    /// and values are combined with the powers of `challenge` and checked with one
+    /// [`Self::check`].
+    pub fn batch_check_same_point(
+        vk: &VerifierKey<E>,
+        comms: &[Commitment<E>],
+        point: E::Fr,
+        values: &[E::Fr],
+        proof: &Proof<E>,
+        challenge: E::Fr,
+    ) -> Result<bool, Error> {
+        if values.len() != comms.len() {
+            return Err(Error::WrongNumberOfEvaluations {
+                expected: comms.len(),
+                got: values.len(),
+            });
+        }
+        let (combined, combined_value) =
+            Self::combine_evaluations(comms, values.chunks(1), 1, challenge);
+        Self::check(vk, &combined, point, combined_value[0], proof)
+    }
+
+    /// `\sum_k c^k p_k`, the polynomials of a batch opened with a single proof
+    fn combine_polys(powers: &Powers<E>, polys: &[P], challenge: E::Fr) -> Result<P, Error> {
+        let mut combined = P::zero();
+        let mut scale = E::Fr::one();
+        for p in polys {
+            Self::check_degree_is_too_large(p.degree(), powers.size())?;
+            combined += (scale, p);
+            scale *= challenge;
+        }
+        Ok(combined)
+    }
+
+    /// The commitment and `width` evaluations that match [`Self::combine_polys`], where
+    /// `comms[k]` is opened to the `k`-th of `value_rows`
+    fn combine_evaluations<'a>(
+        comms: &[Commitment<E>],
+        value_rows: impl IntoIterator<Item = &'a [E::Fr]>,
+        width: usize,
+        challenge: E::Fr,
+    ) -> (Commitment<E>, Vec<E::Fr>) {
+        let mut combined_values = vec![E::Fr::zero(); width];
+        let mut scalars = Vec::with_capacity(comms.len());
+        let mut scale = E::Fr::one();
+        for values in value_rows.into_iter().take(comms.len()) {
+            for (c, v) in combined_values.iter_mut().zip(values) {
+                *c += scale * v;
+            }
+            scalars.push(scale.into_repr());
+            scale *= challenge;
+        }
+        let bases: Vec<_> = comms.iter().map(|c| c.0).collect();
+        let combined = VariableBaseMSM::multi_scalar_mul(&bases, &scalars);
+        (Commitment(combined.into()), combined_values)
+    }
+
+    /// Opens a polynomial committed with [`Self::commit_segmented`] at `point`. The
+    /// segments are folded into `q(x) = \sum_k z^{k N} p_k(x)`, which shifts each one
+    /// by its power of `z` and has `q(z) = p(z)`, and `q` is opened with a single proof.
+    /// Returns `p(point)` and the proof.
+    pub fn open_segmented(
+        powers: &Powers<E>,
+        p: &P,
+        chunk_degree: usize,
+        point: E::Fr,
+    ) -> Result<(E::Fr, Proof<E>), Error> {
+        Self::check_degree_is_too_large(chunk_degree, powers.size())?;
+        let shift = point.pow([(chunk_degree + 1) as u64]);
+        let mut folded = vec![E::Fr::zero(); chunk_degree + 1];
+        let mut scale = E::Fr::one();
+        for segment in p.coeffs().chunks(chunk_degree + 1) {
+            for (f, c) in folded.iter_mut().zip(segment) {
+                *f += scale * c;
+            }
+            scale *= shift;
+        }
+        let folded = P::from_coefficients_vec(folded);
+        let proof = Self::open(powers, &folded, point)?;
+        Ok((folded.evaluate(&point), proof))
+    }
+
+    /// Verifies a proof from [`Self::open_segmented`] that `value` is the evaluation
+    /// at `point` of the polynomial whose segments are committed in `comms`.
+    pub fn check_segmented(
+        vk: &VerifierKey<E>,
+        comms: &[Commitment<E>],
+        chunk_degree: usize,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let shift = point.pow([(chunk_degree + 1) as u64]);
+        let scalars: Vec<_> = comms
+            .iter()
+            .scan(E::Fr::one(), |scale, _| {
+                let s = *scale;
+                *scale *= shift;
+                Some(s.into_repr())
+            })
+            .collect();
+        let bases: Vec<_> = comms.iter().map(|c| c.0).collect();
+        let folded = VariableBaseMSM::multi_scalar_mul(&bases, &scalars);
+        Self::check(vk, &Commitment(folded.into()), point, value, proof)
+    }
+
+    /// Proves that commitments to `p` under two different SRSs (e.g. before and after a
+    /// migration) are to the same polynomial, by opening both at `challenge`. Returns
+    /// the shared value `p(challenge)` and the proofs under `old_powers` and
+    /// `new_powers`, in that order.
+    ///
+    /// `challenge` must be chosen after both commitments are fixed.
+    pub fn prove_same_poly(
+        old_powers: &Powers<E>,
+        new_powers: &Powers<E>,
+        p: &P,
+        challenge: E::Fr,
+    ) -> Result<(E::Fr, [Proof<E>; 2]), Error> {
+        let value = p.evaluate(&challenge);
+        let old_proof = Self::open(old_powers, p, challenge)?;
+        let new_proof = Self::open(new_powers, p, challenge)?;
+        Ok((value, [old_proof, new_proof]))
+    }
+
+    /// Verifies a proof from [`Self::prove_same_poly`] that `old_comm` under `old_vk`
+    /// and `new_comm` under `new_vk` commit to the same polynomial.
+    pub fn verify_same_poly(
+        old_vk: &VerifierKey<E>,
+        new_vk: &VerifierKey<E>,
+        old_comm: &Commitment<E>,
+        new_comm: &Commitment<E>,
+        challenge: E::Fr,
+        value: E::Fr,
+        proofs: &[Proof<E>; 2],
+    ) -> Result<bool, Error> {
+        Ok(Self::check(old_vk, old_comm, challenge, value, &proofs[0])?
+            && Self::check(new_vk, new_comm, challenge, value, &proofs[1])?)
+    }
+
+    /// Commits to the rotation `p(\omega x)` by scaling coefficient `i` of `p` by
+    /// `\omega^i`, and proves it is a rotation of the commitment to `p` by opening both
+    /// at a Fiat-Shamir challenge `z`: `p` at `\omega z` and the rotation at `z`.
+    pub fn commit_shift_relation(
+        powers: &Powers<E>,
+        p: &P,
+        omega: E::Fr,
+    ) -> Result<(Commitment<E>, ShiftProof<E>), Error> {
+        let mut scale = E::Fr::one();
+        let rotated = P::from_coefficients_vec(
+            p.coeffs()
+                .iter()
+                .map(|c| {
+                    let scaled = *c * scale;
+                    scale *= omega;
+                    scaled
+                })
+                .collect(),
+        );
+        let comm = Self::commit(powers, p)?;
+        let rotated_comm = Self::commit(powers, &rotated)?;
+
+        let challenge = shift_relation_challenge(&comm, &rotated_comm, omega);
+        let proof = ShiftProof {
+            value: rotated.evaluate(&challenge),
+            w: Self::open(powers, p, omega * challenge)?.w,
+            w_rotated: Self::open(powers, &rotated, challenge)?.w,
+        };
+        Ok((rotated_comm, proof))
+    }
+
+    /// Verifies a proof from [`Self::commit_shift_relation`] that `rotated_comm` commits
+    /// to `p(\omega x)` for the `p` committed to in `comm`.
+    pub fn check_shift_relation(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        rotated_comm: &Commitment<E>,
+        omega: E::Fr,
+        proof: &ShiftProof<E>,
+    ) -> Result<bool, Error> {
+        let challenge = shift_relation_challenge(comm, rotated_comm, omega);
+        let w = Proof { w: proof.w };
+        let w_rotated = Proof { w: proof.w_rotated };
+        Ok(Self::check(vk, comm, omega * challenge, proof.value, &w)?
+            && Self::check(vk, rotated_comm, challenge, proof.value, &w_rotated)?)
+    }
+
+    /// The Merkle tree over the coefficients of `p`, lowest degree first, which are
+    /// padded with zeros to a power of two. Each leaf is the serialized coefficient.
+    pub fn coefficients_merkle_tree(p: &P) -> MerkleTree {
+        let num_leaves = p.coeffs().len().max(1).next_power_of_two();
+        let leaves = (0..num_leaves)
+            .map(|i| coefficient_leaf(p.coeffs().get(i).copied().unwrap_or_else(E::Fr::zero)))
+            .collect();
+        MerkleTree::new(leaves)
+    }
+
+    /// Opens `p` at `point` like [`Self::open`], and also reveals one coefficient of `p`
+    /// with its path in the Merkle tree [`Self::coefficients_merkle_tree`] whose root is
+    /// `merkle_root`. The coefficient is picked by hashing the commitment, the root and
+    /// the point, so the prover can't choose it.
+    ///
+    /// This doesn't bind the commitment to the Merkle root: the revealed coefficient is
+    /// only shown to be in the tree, not to be a coefficient of the committed
+    /// polynomial, so a prover can pair a commitment with the root of an unrelated
+    /// polynomial. The two halves are only checked side by side.
+    pub fn open_with_merkle_coefficient(
+        powers: &Powers<E>,
+        p: &P,
+        point: E::Fr,
+        merkle_root: &Hash,
+    ) -> Result<MerkleCoefficientProof<E>, Error> {
+        let tree = Self::coefficients_merkle_tree(p);
+        if tree.root() != *merkle_root {
+            return Err(Error::MerkleRootMismatch);
+        }
+        let comm = Self::commit(powers, p)?;
+        let index = merkle_coefficient_index(&comm, merkle_root, point, tree.num_leaves());
+        Ok(MerkleCoefficientProof {
+            proof: Self::open(powers, p, point)?,
+            index,
+            coeff: p.coeffs().get(index).copied().unwrap_or_else(E::Fr::zero),
+            path: tree.open(index),
+        })
+    }
+
+    /// Verifies a proof from [`Self::open_with_merkle_coefficient`]: the KZG opening of
+    /// `comm` to `value` at `point`, and that the revealed coefficient is the leaf of
+    /// the Merkle tree with root `merkle_root` at the expected index.
+    pub fn check_with_merkle_coefficient(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        point: E::Fr,
+        value: E::Fr,
+        merkle_root: &Hash,
+        proof: &MerkleCoefficientProof<E>,
+    ) -> Result<bool, Error> {
+        // A path this long can't come from a tree whose leaves fit in memory
+        let num_leaves = match u32::try_from(proof.path.len())
+            .ok()
+            .and_then(|depth| 1usize.checked_shl(depth))
+        {
+            Some(num_leaves) => num_leaves,
+            None => return Ok(false),
+        };
+        let index = merkle_coefficient_index(comm, merkle_root, point, num_leaves);
+        Ok(index == proof.index
+            && MerkleTree::verify(merkle_root, &coefficient_leaf(proof.coeff), index, &proof.path)
+            && Self::check(vk, comm, point, value, &proof.proof)?)
     }
 
-    pub(crate) fn check_degree_is_too_large(degree: usize, num_powers: usize) -> Result<(), Error> {
-        let num_coefficients = degree + 1;
-        if num_coefficients > num_powers {
+    /// Opens `p` and its derivative at `point`, returning `(p(z), p'(z), proof)`.
+    ///
+    /// The witness `w'(x) = (p(x) - p(z) - p'(z)(x - z)) / (x - z)^2` is the quotient of
+    /// the usual witness `w(x)` by `x - z` (since `w(z) = p'(z)`), so a single group
+    /// element proves both evaluations.
+    pub fn open_value_and_derivative(
+        powers: &Powers<E>,
+        p: &P,
+        point: P::Point,
+    ) -> Result<ValueAndDerivative<E>, Error> {
+        Self::check_degree_is_too_large(p.degree(), powers.size())?;
+
+        let witness_poly = Self::compute_witness_polynomial(p, point)?;
+        let value = p.evaluate(&point);
+        let derivative = witness_poly.evaluate(&point);
+        let witness_poly = Self::compute_witness_polynomial(&witness_poly, point)?;
+        let proof = Self::open_with_witness_polynomial(powers, &witness_poly)?;
+
+        Ok((value, derivative, proof))
+    }
+
+    /// Verifies a proof from [`Self::open_value_and_derivative`] by checking
+    /// `e(comm - (v - d z) g, h) == e(d g, \beta h) * e(proof, (\beta - z)^2 h)`.
+    pub fn check_value_and_derivative(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        point: E::Fr,
+        value: E::Fr,
+        derivative: E::Fr,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        if vk.powers_of_h.len() < 3 {
+            return Err(Error::MissingG2Power {
+                power: 2,
+                num_powers: vk.powers_of_h.len(),
+            });
+        }
+        let inner = comm.0.into_projective() - vk.g.mul(value - derivative * point);
+        let lhs = E::pairing(inner, vk.h);
+
+        // (\beta - z)^2 h = \beta^2 h - 2z \beta h + z^2 h
+        let divisor_h = vk.powers_of_h[2].into_projective() - vk.powers_of_h[1].mul(point.double())
+            + vk.h.mul(point.square());
+        let rhs = E::pairing(vk.g.mul(derivative), vk.beta_h) * E::pairing(proof.w, divisor_h);
+
+        Ok(lhs == rhs)
+    }
+
+    /// Opens `p` over the coset `coset_shift * domain`, returning the evaluations of `p`
+    /// at `coset_shift * \omega^i` in natural order, and a proof for all of them.
+    ///
+    /// Writing `p(x) = q(x) Z(x) + r(x)` with `Z(x) = x^n - s^n` the vanishing polynomial
+    /// of the coset, `r` interpolates the evaluations. Dividing by `Z` only needs one
+    /// pass over the coefficients. The proof commits to `q` and to the witness for
+    /// `p(x) - r(z) - q(x) Z(z)` vanishing at a Fiat-Shamir challenge `z`. `z` is
+    /// drawn after `q` is committed: a prover who knew it in advance could commit to
+    /// the constant `(p(z) - r(z)) / Z(z)` instead and pass any evaluations.
+    pub fn open_over_coset<D: EvaluationDomain<E::Fr>>(
+        powers: &Powers<E>,
+        p: &P,
+        comm: &Commitment<E>,
+        domain: &D,
+        coset_shift: E::Fr,
+    ) -> Result<(Vec<E::Fr>, CosetProof<E>), Error> {
+        Self::check_degree_is_too_large(p.degree(), powers.size())?;
+
+        let n = domain.size();
+        let shift_n = coset_shift.pow([n as u64]);
+        let coeffs = p.coeffs();
+
+        // p_i = q_{i - n} - s^n q_i, so walk down from the leading coefficient
+        let mut quotient = vec![E::Fr::zero(); coeffs.len().saturating_sub(n)];
+        for i in (n..coeffs.len()).rev() {
+            let carry = quotient.get(i).map_or(E::Fr::zero(), |q| shift_n * q);
+            quotient[i - n] = coeffs[i] + carry;
+        }
+        let remainder: Vec<_> = (0..n.min(coeffs.len()))
+            .map(|i| coeffs[i] + quotient.get(i).map_or(E::Fr::zero(), |q| shift_n * q))
+            .collect();
+
+        // r(s \omega^i) is the fft of r(s x)
+        let mut evals: Vec<_> = remainder
+            .iter()
+            .zip(gen_powers(coset_shift, remainder.len()))
+            .map(|(r, s)| *r * s)
+            .collect();
+        domain.fft_in_place(&mut evals);
+
+        let quotient = P::from_coefficients_vec(quotient);
+        let Commitment(w_quotient) = Self::commit(powers, &quotient)?;
+        let challenge = coset_opening_challenge(comm, coset_shift, &evals, &w_quotient);
+
+        let r_z = P::from_coefficients_vec(remainder).evaluate(&challenge);
+        let z_z = challenge.pow([n as u64]) - shift_n;
+        let mut l = p.clone();
+        l -= &P::from_coefficients_vec(vec![r_z]);
+        l += (-z_z, &quotient);
+        let witness = Self::compute_witness_polynomial(&l, challenge)?;
+        let Proof { w: w_challenge, .. } = Self::open_with_witness_polynomial(powers, &witness)?;
+
+        Ok((
+            evals,
+            CosetProof {
+                w_quotient,
+                w_challenge,
+            },
+        ))
+    }
+
+    /// Verifies that `evals` are the evaluations over `coset_shift * domain` of the
+    /// polynomial committed inside `comm`, by checking
+    /// `e(comm - r(z) g - Z(z) w_quotient, h) == e(w_challenge, (\beta - z) h)` at the
+    /// challenge `z` of [`Self::open_over_coset`].
+    pub fn check_over_coset<D: EvaluationDomain<E::Fr>>(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        domain: &D,
+        coset_shift: E::Fr,
+        evals: &[E::Fr],
+        proof: &CosetProof<E>,
+    ) -> Result<bool, Error> {
+        let n = domain.size();
+        if evals.len() != n {
+            return Err(Error::WrongNumberOfEvaluations {
+                expected: n,
+                got: evals.len(),
+            });
+        }
+
+        // Undo the coset shift to recover r
+        let shift_inv = coset_shift.inverse().ok_or(Error::ZeroCosetShift)?;
+        let remainder: Vec<_> = domain
+            .ifft(evals)
+            .into_iter()
+            .zip(gen_powers(shift_inv, n))
+            .map(|(r, s)| r * s)
+            .collect();
+        let challenge = coset_opening_challenge(comm, coset_shift, evals, &proof.w_quotient);
+        let r_z = P::from_coefficients_vec(remainder).evaluate(&challenge);
+        let z_z = challenge.pow([n as u64]) - coset_shift.pow([n as u64]);
+
+        let inner = comm.0.into_projective()
+            - vk.g.mul(r_z)
+            - proof.w_quotient.mul(z_z);
+        let lhs = E::pairing(inner, vk.h);
+
+        let inner = vk.beta_h.into_projective() - vk.h.mul(challenge);
+        let rhs = E::pairing(proof.w_challenge, inner);
+
+        Ok(lhs == rhs)
+    }
+
+    /// Commits to `p`, whose coefficients are in a quadratic extension `F[u]/(u^2 - \beta)`
+    /// of `E::Fr`, as the commitments to its two component polynomials, made of the
+    /// `c0` and of the `c1` coefficients, so that `p = p_0 + u p_1`.
+    ///
+    /// A point `z = a + u b` of the extension isn't in `E::Fr`, so the components
+    /// can't be opened there directly. Instead, `p_i(z)` is the value at `z` of the
+    /// remainder of `p_i` divided by the minimal polynomial
+    /// `m(x) = (x - a)^2 - \beta b^2` of `z`, which is linear, and the verifier
+    /// recovers that remainder from `p_i(z)`. [`Self::open_extension_field`] proves
+    /// that both remainders are right at once, by dividing a random combination of
+    /// the components by `m`.
+    pub fn commit_extension_field<F: Fp2Parameters<Fp = E::Fr>>(
+        powers: &Powers<E>,
+        p: &[Fp2<F>],
+    ) -> Result<[Commitment<E>; 2], Error> {
+        let (p_0, p_1) = extension_components::<E, P, F>(p);
+        Ok([Self::commit(powers, &p_0)?, Self::commit(powers, &p_1)?])
+    }
+
+    /// Opens the polynomial committed to in `comms` by [`Self::commit_extension_field`]
+    /// at `point`, which must not be in the base field. The combination of the
+    /// components is derived from `comms`, `point` and the component values.
+    pub fn open_extension_field<F: Fp2Parameters<Fp = E::Fr>>(
+        powers: &Powers<E>,
+        comms: &[Commitment<E>; 2],
+        p: &[Fp2<F>],
+        point: Fp2<F>,
+    ) -> Result<ExtensionFieldProof<E, F>, Error> {
+        let m = minimal_polynomial::<E, P, F>(point)?;
+        let (p_0, p_1) = extension_components::<E, P, F>(p);
+        let c0_value = evaluate_in_extension(p_0.coeffs(), point);
+        let c1_value = evaluate_in_extension(p_1.coeffs(), point);
+        let r = extension_field_challenge::<E, F>(comms, point, c0_value, c1_value);
+
+        let mut combined = p_0.coeffs().to_vec();
+        combined.resize(combined.len().max(p_1.coeffs().len()), E::Fr::zero());
+        for (c, c_1) in combined.iter_mut().zip(p_1.coeffs()) {
+            *c += r * c_1;
+        }
+        // The remainder is left over, only the quotient is committed to
+        let quotient = &P::from_coefficients_vec(combined) / &m;
+        let Commitment(w) = Self::commit(powers, &quotient)?;
+        Ok(ExtensionFieldProof {
+            c0_value,
+            c1_value,
+            w,
+        })
+    }
+
+    /// Verifies that the polynomial committed to in `comms` takes the value `value` at
+    /// `point`. With `C = C_0 + r C_1` and the combined remainder `\alpha + \gamma x`,
+    /// this checks `e(C - \alpha g, h) e(-\gamma g, \beta h) e(-w, m(\beta) h) == 1`,
+    /// so `vk` needs the powers of `h` up to `\beta^2`.
+    pub fn check_extension_field<F: Fp2Parameters<Fp = E::Fr>>(
+        vk: &VerifierKey<E>,
+        comms: &[Commitment<E>; 2],
+        point: Fp2<F>,
+        value: Fp2<F>,
+        proof: &ExtensionFieldProof<E, F>,
+    ) -> Result<bool, Error> {
+        if vk.powers_of_h.len() < 3 {
+            return Err(Error::MissingG2Power {
+                power: 2,
+                num_powers: vk.powers_of_h.len(),
+            });
+        }
+        let m = minimal_polynomial::<E, P, F>(point)?;
+        let u = Fp2::new(E::Fr::zero(), E::Fr::one());
+        if value != proof.c0_value + u * proof.c1_value {
+            return Ok(false);
+        }
+
+        let r = extension_field_challenge::<E, F>(
+            comms,
+            point,
+            proof.c0_value,
+            proof.c1_value,
+        );
+        // `\alpha_i + \gamma_i z` has `c1` part `\gamma_i b` and `c0` part `\alpha_i + \gamma_i a`
+        let b_inv = point.c1.inverse().unwrap();
+        let remainder = |v: Fp2<F>| {
+            let gamma = v.c1 * b_inv;
+            (v.c0 - gamma * point.c0, gamma)
+        };
+        let (alpha_0, gamma_0) = remainder(proof.c0_value);
+        let (alpha_1, gamma_1) = remainder(proof.c1_value);
+        let (alpha, gamma) = (alpha_0 + r * alpha_1, gamma_0 + r * gamma_1);
+
+        let comm = comms[0].0.into_projective() + comms[1].0.mul(r);
+        let m_h: E::G2Projective = vk
+            .powers_of_h
+            .iter()
+            .zip(m.coeffs())
+            .map(|(h, c)| h.mul(*c))
+            .sum();
+        let affine_points = E::G1Projective::batch_normalization_into_affine(&[
+            comm - vk.g.mul(alpha),
+            -vk.g.mul(gamma),
+            -proof.w.into_projective(),
+        ]);
+        Ok(E::product_of_pairings(&[
+            (affine_points[0].into(), vk.prepared_h.clone()),
+            (affine_points[1].into(), vk.prepared_beta_h.clone()),
+            (affine_points[2].into(), m_h.into_affine().into()),
+        ])
+        .is_one())
+    }
+
+    /// Proves that `p` has degree at most `bound` by committing to the shifted
+    /// polynomial `x^{D - bound} p(x)`, where `D` is the maximum degree of `pp`. The
+    /// shift must be by the universal `D` rather than that of a trimmed key, or a
+    /// prover holding the larger powers could shift by less. If `p` has a larger
+    /// degree, the shifted polynomial does not fit in `pp` and no proof can be produced.
+    pub fn prove_degree_bound(
+        pp: &UniversalParams<E>,
+        p: &P,
+        bound: usize,
+    ) -> Result<Proof<E>, Error> {
+        let max_degree = pp.powers_of_g.len().saturating_sub(1);
+        let shift = max_degree
+            .checked_sub(bound)
+            .ok_or(Error::UnsupportedDegreeBound(bound))?;
+        Self::check_degree_is_too_large(p.degree() + shift, pp.powers_of_g.len())?;
+
+        let (num_leading_zeros, plain_coeffs) = skip_leading_zeros_and_convert_to_bigints(p);
+        let w = VariableBaseMSM::multi_scalar_mul(
+            &pp.powers_of_g[shift + num_leading_zeros..],
+            &plain_coeffs,
+        );
+
+        Ok(Proof { w: w.into_affine() })
+    }
+
+    /// Verifies a proof from [`Self::prove_degree_bound`] that the polynomial
+    /// committed inside `comm` has degree at most `bound`, by checking
+    /// `e(comm_shifted, h) == e(comm, \beta^{D - bound} h)`, where `D` is the maximum
+    /// degree of the parameters `vk` was trimmed from. `\beta^{D - bound} h` must have
+    /// been made at setup, either as one of the powers of `h` or for this `bound`.
+    pub fn verify_degree_bound(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        bound: usize,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let shift = vk
+            .max_degree
+            .checked_sub(bound)
+            .ok_or(Error::UnsupportedDegreeBound(bound))?;
+        let shift_h = vk
+            .degree_bound_powers_of_h
+            .get(&bound)
+            .or_else(|| vk.powers_of_h.get(shift))
+            .ok_or(Error::MissingDegreeBoundPower(bound))?;
+
+        Ok(E::product_of_pairings(&[
+            (proof.w.into(), vk.prepared_h.clone()),
+            ((-comm.0).into(), (*shift_h).into()),
+        ])
+        .is_one())
+    }
+
+    /// Check that each `proof_i` in `proofs` is a valid proof of evaluation for
+    /// `commitment_i` at `point_i`.
+    pub fn batch_check<R: RngCore>(
+        vk: &VerifierKey<E>,
+        commitments: &[Commitment<E>],
+        points: &[E::Fr],
+        values: &[E::Fr],
+        proofs: &[Proof<E>],
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        let mut total_c = <E::G1Projective>::zero();
+        let mut total_w = <E::G1Projective>::zero();
+
+        let mut randomizer = E::Fr::one();
+        // Instead of multiplying g and gamma_g in each turn, we simply accumulate
+        // their coefficients and perform a final multiplication at the end.
+        let mut g_multiplier = E::Fr::zero();
+        let gamma_g_multiplier = E::Fr::zero();
+        for (((c, z), v), proof) in commitments.iter().zip(points).zip(values).zip(proofs) {
+            let w = proof.w;
+            let mut temp = w.mul(*z);
+            temp.add_assign_mixed(&c.0);
+            let c = temp;
+            g_multiplier += &(randomizer * v);
+            total_c += &c.mul(randomizer.into_repr());
+            total_w += &w.mul(randomizer.into_repr());
+            // We don't need to sample randomizers from the full field,
+            // only from 128-bit strings.
+            randomizer = u128::rand(rng).into();
+        }
+        total_c -= &vk.g.mul(g_multiplier);
+        total_c -= &vk.gamma_g.mul(gamma_g_multiplier);
+
+        let affine_points = E::G1Projective::batch_normalization_into_affine(&[-total_w, total_c]);
+        let (total_w, total_c) = (affine_points[0], affine_points[1]);
+
+        let result = E::product_of_pairings(&[
+            (total_w.into(), vk.prepared_beta_h.clone()),
+            (total_c.into(), vk.prepared_h.clone()),
+        ])
+        .is_one();
+        Ok(result)
+    }
+
+    /// Same as [`Self::batch_check`], but scales each commitment by its randomizer
+    /// using the precomputed doublings in `prepared_commitments`. Preparing costs
+    /// as much as one scalar multiplication, so this pays off when the same
+    /// commitments are batch-checked repeatedly.
+    pub fn batch_check_prepared<R: RngCore>(
+        vk: &VerifierKey<E>,
+        prepared_commitments: &[PreparedCommitment<E>],
+        points: &[E::Fr],
+        values: &[E::Fr],
+        proofs: &[Proof<E>],
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        let mut total_c = <E::G1Projective>::zero();
+        let mut total_w = <E::G1Projective>::zero();
+
+        let mut randomizer = 1u128;
+        let mut g_multiplier = E::Fr::zero();
+        for (((c, z), v), proof) in prepared_commitments
+            .iter()
+            .zip(points)
+            .zip(values)
+            .zip(proofs)
+        {
+            let w = proof.w;
+            let r = E::Fr::from(randomizer);
+            g_multiplier += &(r * v);
+            total_c += &c.mul_u128(randomizer);
+            total_c += &w.mul((r * z).into_repr());
+            total_w += &w.mul(r.into_repr());
+            randomizer = u128::rand(rng);
+        }
+        total_c -= &vk.g.mul(g_multiplier);
+
+        let affine_points = E::G1Projective::batch_normalization_into_affine(&[-total_w, total_c]);
+        let (total_w, total_c) = (affine_points[0], affine_points[1]);
+
+        let result = E::product_of_pairings(&[
+            (total_w.into(), vk.prepared_beta_h.clone()),
+            (total_c.into(), vk.prepared_h.clone()),
+        ])
+        .is_one();
+        Ok(result)
+    }
+
+    /// Folds the claims `p_i(z_i) = v_i` into a single claim that [`Self::check`]
+    /// accepts at the point zero, so the aggregate can be handed on and verified
+    /// later with one pairing check. With `r_i = challenge^i` this is
+    /// `(\sum_i r_i (c_i + z_i w_i), 0, \sum_i r_i v_i, \sum_i r_i w_i)`, since each
+    /// proof satisfies `e(c_i - v_i g + z_i w_i, h) == e(w_i, \beta h)`.
+    ///
+    /// `challenge` must be chosen after the claims are fixed, e.g. by hashing them.
+    pub fn aggregate_proofs(
+        commitments: &[Commitment<E>],
+        points: &[E::Fr],
+        values: &[E::Fr],
+        proofs: &[Proof<E>],
+        challenge: E::Fr,
+    ) -> Result<Claim<E>, Error> {
+        let n = commitments.len();
+        if let Some(got) = [points.len(), values.len(), proofs.len()]
+            .into_iter()
+            .find(|&len| len != n)
+        {
+            return Err(Error::MismatchedClaimLengths { expected: n, got });
+        }
+
+        let mut comm_bases = Vec::with_capacity(2 * n);
+        let mut comm_scalars = Vec::with_capacity(2 * n);
+        let mut w_scalars = Vec::with_capacity(n);
+        let mut value = E::Fr::zero();
+        let mut r = E::Fr::one();
+        for (((c, z), v), proof) in commitments.iter().zip(points).zip(values).zip(proofs) {
+            comm_bases.push(c.0);
+            comm_scalars.push(r.into_repr());
+            comm_bases.push(proof.w);
+            comm_scalars.push((r * z).into_repr());
+            w_scalars.push(r.into_repr());
+            value += r * v;
+            r *= challenge;
+        }
+        let comm = VariableBaseMSM::multi_scalar_mul(&comm_bases, &comm_scalars);
+        let w_bases: Vec<_> = proofs.iter().map(|p| p.w).collect();
+        let w = VariableBaseMSM::multi_scalar_mul(&w_bases, &w_scalars);
+
+        Ok((
+            Commitment(comm.into()),
+            E::Fr::zero(),
+            value,
+            Proof { w: w.into() },
+        ))
+    }
+
+    /// Errors unless `powers` can commit to a polynomial of degree `degree`
+    fn check_trimmed_degree(degree: usize, powers: &Powers<E>) -> Result<(), Error> {
+        let supported = powers.size().checked_sub(1).ok_or(Error::NoPowers)?;
+        if degree > supported {
+            return Err(Error::PolynomialExceedsTrimmedDegree {
+                poly_degree: degree,
+                supported,
+            });
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_degree_is_too_large(degree: usize, num_powers: usize) -> Result<(), Error> {
+        let num_coefficients = degree + 1;
+        if num_coefficients > num_powers {
+            Err(Error::TooManyCoefficients {
+                num_coefficients,
+                num_powers,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(crate) fn check_degrees_and_bounds<'a>(
+        supported_degree: usize,
+        max_degree: usize,
+        enforced_degree_bounds: Option<&[usize]>,
+        p: &'a LabeledPolynomial<E::Fr, P>,
+    ) -> Result<(), Error> {
+        if let Some(bound) = p.degree_bound() {
+            let enforced_degree_bounds =
+                enforced_degree_bounds.ok_or(Error::UnsupportedDegreeBound(bound))?;
+
+            if enforced_degree_bounds.binary_search(&bound).is_err() {
+                Err(Error::UnsupportedDegreeBound(bound))
+            } else if bound < p.degree() || bound > max_degree {
+                return Err(Error::IncorrectDegreeBound {
+                    poly_degree: p.degree(),
+                    degree_bound: p.degree_bound().unwrap(),
+                    supported_degree,
+                    label: p.label().to_string(),
+                });
+            } else {
+                Ok(())
+            }
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// Fiat-Shamir challenge for [`KZG10::open_shared_points`], binding everything the
+// verifier sees before the proof
+fn shared_points_challenge<E: PairingEngine>(
+    comms: &[Commitment<E>],
+    points: &[E::Fr],
+    values: &[Vec<E::Fr>],
+) -> E::Fr {
+    let mut bytes = Vec::new();
+    comms
+        .serialize(&mut bytes)
+        .and_then(|_| points.serialize(&mut bytes))
+        .and_then(|_| values.serialize(&mut bytes))
+        .expect("Serializing to a Vec can't fail");
+    Sha256ChallengeGen::hash_to_field(SHARED_POINTS_DST, &bytes)
+}
+
+fn extension_field_challenge<E: PairingEngine, F: Fp2Parameters<Fp = E::Fr>>(
+    comms: &[Commitment<E>; 2],
+    point: Fp2<F>,
+    c0_value: Fp2<F>,
+    c1_value: Fp2<F>,
+) -> E::Fr {
+    let mut bytes = Vec::new();
+    comms[0]
+        .serialize(&mut bytes)
+        .and_then(|_| comms[1].serialize(&mut bytes))
+        .and_then(|_| point.serialize(&mut bytes))
+        .and_then(|_| c0_value.serialize(&mut bytes))
+        .and_then(|_| c1_value.serialize(&mut bytes))
+        .expect("Serializing to a Vec can't fail");
+    Sha256ChallengeGen::hash_to_field(EXTENSION_FIELD_DST, &bytes)
+}
+
+fn shift_relation_challenge<E: PairingEngine>(
+    comm: &Commitment<E>,
+    rotated_comm: &Commitment<E>,
+    omega: E::Fr,
+) -> E::Fr {
+    let mut bytes = Vec::new();
+    comm.serialize(&mut bytes)
+        .and_then(|_| rotated_comm.serialize(&mut bytes))
+        .and_then(|_| omega.serialize(&mut bytes))
+        .expect("Serializing to a Vec can't fail");
+    Sha256ChallengeGen::hash_to_field(SHIFT_RELATION_DST, &bytes)
+}
+
+fn coset_opening_challenge<E: PairingEngine>(
+    comm: &Commitment<E>,
+    coset_shift: E::Fr,
+    evals: &[E::Fr],
+    w_quotient: &E::G1Affine,
+) -> E::Fr {
+    let mut bytes = Vec::new();
+    comm.serialize(&mut bytes)
+        .and_then(|_| coset_shift.serialize(&mut bytes))
+        .and_then(|_| evals.serialize(&mut bytes))
+        .and_then(|_| w_quotient.serialize(&mut bytes))
+        .expect("Serializing to a Vec can't fail");
+    Sha256ChallengeGen::hash_to_field(COSET_OPENING_DST, &bytes)
+}
+
+fn coefficient_leaf<F: PrimeField>(coeff: F) -> Hash {
+    let mut bytes = Vec::new();
+    coeff
+        .serialize(&mut bytes)
+        .expect("Serializing to a Vec can't fail");
+    hash_leaf(&bytes)
+}
+
+fn merkle_coefficient_index<E: PairingEngine>(
+    comm: &Commitment<E>,
+    merkle_root: &Hash,
+    point: E::Fr,
+    num_leaves: usize,
+) -> usize {
+    let mut bytes = Vec::new();
+    comm.serialize(&mut bytes)
+        .and_then(|_| point.serialize(&mut bytes))
+        .expect("Serializing to a Vec can't fail");
+    bytes.extend_from_slice(merkle_root);
+    let challenge: E::Fr = Sha256ChallengeGen::hash_to_field(MERKLE_COEFFICIENT_DST, &bytes);
+    // `num_leaves` is a power of two, so this keeps the low bits
+    (challenge.into_repr().as_ref()[0] as usize) & (num_leaves - 1)
+}
+
+/// Returns `perm` with `perm[i]` equal to `i` with its `log2(n)` low bits reversed.
+/// Permuting by it twice is the identity. `n` must be a power of two.
+pub fn bit_reverse_permutation(n: usize) -> Vec<usize> {
+    assert!(n.is_power_of_two(), "n must be a power of two");
+    let log_n = n.trailing_zeros();
+    (0..n)
+        .map(|i| {
+            if log_n == 0 {
+                i
+            } else {
+                i.reverse_bits() >> (usize::BITS - log_n)
+            }
+        })
+        .collect()
+}
+
+/// Coefficients of `\prod_i (x - points[i])`, lowest degree first.
+fn vanishing_polynomial<F: Field>(points: &[F]) -> Vec<F> {
+    let mut coeffs = vec![F::one()];
+    for point in points {
+        // multiply by (x - point)
+        let mut next = vec![F::zero(); coeffs.len() + 1];
+        for (i, c) in coeffs.iter().enumerate() {
+            next[i + 1] += c;
+            next[i] -= *c * point;
+        }
+        coeffs = next;
+    }
+    coeffs
+}
+
+/// Coefficients of the polynomial of degree less than `points.len()` taking `values[i]`
+/// at `points[i]`, or `None` if the points aren't distinct.
+fn lagrange_interpolate<F: Field>(points: &[F], values: &[F]) -> Option<Vec<F>> {
+    let mut coeffs = vec![F::zero(); points.len()];
+    for (i, (x_i, y_i)) in points.iter().zip(values).enumerate() {
+        // l_i(x) = \prod_{j != i} (x - x_j) / (x_i - x_j)
+        let others: Vec<_> = points
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, x_j)| *x_j)
+            .collect();
+        let denom: F = others.iter().map(|x_j| *x_i - x_j).product();
+        let scale = *y_i * denom.inverse()?;
+        for (c, l) in coeffs.iter_mut().zip(vanishing_polynomial(&others)) {
+            *c += scale * l;
+        }
+    }
+    Some(coeffs)
+}
+
+// The window size `VariableBaseMSM::multi_scalar_mul` picks for `num_scalars` scalars
+fn msm_window_size(num_scalars: usize) -> usize {
+    if num_scalars < 32 {
+        3
+    } else {
+        // `ln(num_scalars)`, rounded the same way as arkworks
+        ark_std::log2(num_scalars) as usize * 69 / 100 + 2
+    }
+}
+
+// The polynomials of the `c0` and of the `c1` coefficients of `p`
+fn extension_components<E, P, F>(p: &[Fp2<F>]) -> (P, P)
+where
+    E: PairingEngine,
+    P: UVPolynomial<E::Fr>,
+    F: Fp2Parameters<Fp = E::Fr>,
+{
+    (
+        P::from_coefficients_vec(p.iter().map(|c| c.c0).collect()),
+        P::from_coefficients_vec(p.iter().map(|c| c.c1).collect()),
+    )
+}
+
+// `(x - a)^2 - \beta b^2` for `point = a + u b`, the monic polynomial over the base
+// field with roots `point` and its conjugate
+fn minimal_polynomial<E, P, F>(point: Fp2<F>) -> Result<P, Error>
+where
+    E: PairingEngine,
+    P: UVPolynomial<E::Fr>,
+    F: Fp2Parameters<Fp = E::Fr>,
+{
+    if point.c1.is_zero() {
+        return Err(Error::PointInBaseField);
+    }
+    let (a, b) = (point.c0, point.c1);
+    Ok(P::from_coefficients_vec(vec![
+        a.square() - F::NONRESIDUE * b.square(),
+        -a.double(),
+        E::Fr::one(),
+    ]))
+}
+
+fn evaluate_in_extension<F: Fp2Parameters>(coeffs: &[F::Fp], point: Fp2<F>) -> Fp2<F> {
+    coeffs
+        .iter()
+        .rev()
+        .fold(Fp2::zero(), |acc, c| acc * point + Fp2::new(*c, F::Fp::zero()))
+}
+
+fn gen_powers<F: Field>(element: F, len: usize) -> Vec<F> {
+    let mut powers = Vec::with_capacity(len);
+    let mut cur = F::one();
+    for _ in 0..len {
+        powers.push(cur);
+        cur *= element;
+    }
+    powers
+}
+
+fn skip_leading_zeros_and_convert_to_bigints<F: PrimeField, P: UVPolynomial<F>>(
+    p: &P,
+) -> (usize, Vec<F::BigInt>) {
+    let mut num_leading_zeros = 0;
+    while num_leading_zeros < p.coeffs().len() && p.coeffs()[num_leading_zeros].is_zero() {
+        num_leading_zeros += 1;
+    }
+    let coeffs = convert_to_bigints(&p.coeffs()[num_leading_zeros..]);
+    (num_leading_zeros, coeffs)
+}
+
+fn convert_to_bigints<F: PrimeField>(p: &[F]) -> Vec<F::BigInt> {
+    let coeffs = ark_std::cfg_iter!(p)
+        .map(|s| s.into_repr())
+        .collect::<Vec<_>>();
+    coeffs
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_camel_case_types)]
+    use super::*;
+
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+    use ark_bls12_381::Fr;
+    use ark_bn254::Bn254;
+    use ark_ec::PairingEngine;
+    use ark_ff::FftField;
+    use ark_poly::univariate::DensePolynomial as DensePoly;
+    use ark_poly::EvaluationDomain;
+    use ark_poly::Polynomial;
+    use ark_poly::Radix2EvaluationDomain;
+    use ark_poly_commit::PCCommitment;
+    use ark_serialize::CanonicalDeserialize;
+    use crate::ark::representation::{coeffs_to_evals, evals_to_coeffs, extend_evals_in_place};
+    use crate::test_rng;
+
+    type UniPoly_381 = DensePoly<<Bls12_381 as PairingEngine>::Fr>;
+    type UniPoly_377 = DensePoly<<Bls12_377 as PairingEngine>::Fr>;
+    type KZG_Bls12_381 = KZG10<Bls12_381, UniPoly_381>;
+
+    // Reports a fixed len() whatever it yields, which safe code is free to do
+    struct MisreportedLen(std::vec::IntoIter<Fr>, usize);
+
+    impl Iterator for MisreportedLen {
+        type Item = Fr;
+        fn next(&mut self) -> Option<Fr> {
+            self.0.next()
+        }
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.1, Some(self.1))
+        }
+    }
+
+    impl ExactSizeIterator for MisreportedLen {}
+
+    #[test]
+    fn add_commitments_test() {
+        let rng = &mut test_rng();
+        let p = DensePoly::from_coefficients_slice(&[
+            Fr::rand(rng),
+            Fr::rand(rng),
+            Fr::rand(rng),
+            Fr::rand(rng),
+            Fr::rand(rng),
+        ]);
+        let f = Fr::rand(rng);
+        let mut f_p = DensePoly::zero();
+        f_p += (f, &p);
+
+        let degree = 4;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let comm = KZG10::commit(&powers, &p).unwrap();
+        let f_comm = KZG10::commit(&powers, &f_p).unwrap();
+        let mut f_comm_2 = Commitment::empty();
+        f_comm_2 += (f, &comm);
+
+        assert_eq!(f_comm, f_comm_2);
+    }
+
+    #[test]
+    fn fold_commitments_test() {
+        let rng = &mut test_rng();
+        let degree = 32;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        for _ in 0..10 {
+            let p1 = UniPoly_381::rand(usize::rand(rng) % degree, rng);
+            let p2 = UniPoly_381::rand(usize::rand(rng) % degree, rng);
+            let r = Fr::rand(rng);
+
+            let c1 = KZG_Bls12_381::commit(&powers, &p1).unwrap();
+            let c2 = KZG_Bls12_381::commit(&powers, &p2).unwrap();
+            let folded = fold_polynomials(&p1, &p2, r);
+
+            assert_eq!(
+                fold_commitments(&c1, &c2, r),
+                KZG_Bls12_381::commit(&powers, &folded).unwrap()
+            );
+        }
+    }
+
+    fn end_to_end_test_template<E, P>() -> Result<(), Error>
+    where
+        E: PairingEngine,
+        P: UVPolynomial<E::Fr, Point = E::Fr>,
+        for<'a, 'b> &'a P: Div<&'b P, Output = P>,
+    {
+        let rng = &mut test_rng();
+        for _ in 0..100 {
+            let mut degree = 0;
+            while degree <= 1 {
+                degree = usize::rand(rng) % 20;
+            }
+            let pp = KZG10::<E, P>::setup(degree, rng)?;
+            let (ck, vk) = KZG10::<E, P>::trim(&pp, degree)?;
+            let p = P::rand(degree, rng);
+            let comm = KZG10::<E, P>::commit(&ck, &p)?;
+            let point = E::Fr::rand(rng);
+            let value = p.evaluate(&point);
+            let proof = KZG10::<E, P>::open(&ck, &p, point)?;
+            assert!(
+                KZG10::<E, P>::check(&vk, &comm, point, value, &proof)?,
+                "proof was incorrect for max_degree = {}, polynomial_degree = {}",
+                degree,
+                p.degree(),
+            );
+        }
+        Ok(())
+    }
+
+    fn compressed_round_trip_test_template<E, P>() -> Result<(), Error>
+    where
+        E: PairingEngine,
+        P: UVPolynomial<E::Fr, Point = E::Fr>,
+        for<'a, 'b> &'a P: Div<&'b P, Output = P>,
+    {
+        let rng = &mut test_rng();
+        let degree = 32;
+        let pp = KZG10::<E, P>::setup(degree, rng)?;
+        let (ck, vk) = KZG10::<E, P>::trim(&pp, degree)?;
+        let p = P::rand(degree, rng);
+        let comm = KZG10::<E, P>::commit(&ck, &p)?;
+        let point = E::Fr::rand(rng);
+        let value = p.evaluate(&point);
+        let proof = KZG10::<E, P>::open(&ck, &p, point)?;
+
+        let mut comm_bytes = Vec::new();
+        comm.serialize(&mut comm_bytes)?;
+        let mut proof_bytes = Vec::new();
+        proof.serialize(&mut proof_bytes)?;
+        assert_eq!(comm_bytes.len(), comm.serialized_size());
+        assert!(comm.serialized_size() < comm.uncompressed_size());
+
+        let comm = Commitment::<E>::deserialize(&comm_bytes[..])?;
+        let proof = Proof::<E>::deserialize(&proof_bytes[..])?;
+        assert!(KZG10::<E, P>::check(&vk, &comm, point, value, &proof)?);
+        assert!(!KZG10::<E, P>::check(&vk, &comm, point, value + E::Fr::one(), &proof)?);
+        Ok(())
+    }
+
+    fn linear_polynomial_test_template<E, P>() -> Result<(), Error>
+    where
+        E: PairingEngine,
+        P: UVPolynomial<E::Fr, Point = E::Fr>,
+        for<'a, 'b> &'a P: Div<&'b P, Output = P>,
+    {
+        let rng = &mut test_rng();
+        for _ in 0..100 {
+            let degree = 50;
+            let pp = KZG10::<E, P>::setup(degree, rng)?;
+            let (ck, vk) = KZG10::<E, P>::trim(&pp, 2)?;
+            let p = P::rand(1, rng);
+            let comm = KZG10::<E, P>::commit(&ck, &p)?;
+            let point = E::Fr::rand(rng);
+            let value = p.evaluate(&point);
+            let proof = KZG10::<E, P>::open(&ck, &p, point)?;
+            assert!(
+                KZG10::<E, P>::check(&vk, &comm, point, value, &proof)?,
+                "proof was incorrect for max_degree = {}, polynomial_degree = {}",
+                degree,
+                p.degree(),
+            );
+        }
+        Ok(())
+    }
+
+    fn batch_check_test_template<E, P>() -> Result<(), Error>
+    where
+        E: PairingEngine,
+        P: UVPolynomial<E::Fr, Point = E::Fr>,
+        for<'a, 'b> &'a P: Div<&'b P, Output = P>,
+    {
+        let rng = &mut test_rng();
+        for _ in 0..10 {
+            let mut degree = 0;
+            while degree <= 1 {
+                degree = usize::rand(rng) % 20;
+            }
+            let pp = KZG10::<E, P>::setup(degree, rng)?;
+            let (ck, vk) = KZG10::<E, P>::trim(&pp, degree)?;
+            let mut comms = Vec::new();
+            let mut values = Vec::new();
+            let mut points = Vec::new();
+            let mut proofs = Vec::new();
+            for _ in 0..10 {
+                let p = P::rand(degree, rng);
+                let comm = KZG10::<E, P>::commit(&ck, &p)?;
+                let point = E::Fr::rand(rng);
+                let value = p.evaluate(&point);
+                let proof = KZG10::<E, P>::open(&ck, &p, point)?;
+
+                assert!(KZG10::<E, P>::check(&vk, &comm, point, value, &proof)?);
+                comms.push(comm);
+                values.push(value);
+                points.push(point);
+                proofs.push(proof);
+            }
+            assert!(KZG10::<E, P>::batch_check(
+                &vk, &comms, &points, &values, &proofs, rng
+            )?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_proofs_test() {
+        let rng = &mut test_rng();
+        let degree = 16;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (ck, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let mut comms = Vec::new();
+        let mut values = Vec::new();
+        let mut points = Vec::new();
+        let mut proofs = Vec::new();
+        for _ in 0..8 {
+            let p = UniPoly_381::rand(degree, rng);
+            let point = Fr::rand(rng);
+            comms.push(KZG_Bls12_381::commit(&ck, &p).unwrap());
+            values.push(p.evaluate(&point));
+            points.push(point);
+            proofs.push(KZG_Bls12_381::open(&ck, &p, point).unwrap());
+        }
+        let challenge = Fr::rand(rng);
+
+        let (comm, point, value, proof) =
+            KZG_Bls12_381::aggregate_proofs(&comms, &points, &values, &proofs, challenge)
+                .unwrap();
+        assert!(KZG_Bls12_381::check(&vk, &comm, point, value, &proof).unwrap());
+        assert!(KZG_Bls12_381::check_prepared(&vk, &comm, point, value, &proof).unwrap());
+
+        values[3] += Fr::one();
+        let (comm, point, value, proof) =
+            KZG_Bls12_381::aggregate_proofs(&comms, &points, &values, &proofs, challenge)
+                .unwrap();
+        assert!(!KZG_Bls12_381::check(&vk, &comm, point, value, &proof).unwrap());
+
+        assert!(matches!(
+            KZG_Bls12_381::aggregate_proofs(&comms, &points[1..], &values, &proofs, challenge),
+            Err(Error::MismatchedClaimLengths {
+                expected: 8,
+                got: 7
+            })
+        ));
+    }
+
+    #[test]
+    fn batch_check_prepared_test() {
+        let rng = &mut test_rng();
+        let degree = 16;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (ck, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let mut comms = Vec::new();
+        let mut values = Vec::new();
+        let mut points = Vec::new();
+        let mut proofs = Vec::new();
+        for _ in 0..10 {
+            let p = UniPoly_381::rand(degree, rng);
+            let point = Fr::rand(rng);
+            comms.push(KZG_Bls12_381::commit(&ck, &p).unwrap());
+            values.push(p.evaluate(&point));
+            points.push(point);
+            proofs.push(KZG_Bls12_381::open(&ck, &p, point).unwrap());
+        }
+        let prepared: Vec<_> = comms.iter().map(PreparedCommitment::prepare).collect();
+
+        // Reuse the same prepared commitments across batches
+        for _ in 0..3 {
+            assert!(KZG_Bls12_381::batch_check(&vk, &comms, &points, &values, &proofs, rng).unwrap());
+            assert!(KZG_Bls12_381::batch_check_prepared(
+                &vk, &prepared, &points, &values, &proofs, rng
+            )
+            .unwrap());
+        }
+
+        values[4] += Fr::one();
+        assert!(!KZG_Bls12_381::batch_check(&vk, &comms, &points, &values, &proofs, rng).unwrap());
+        assert!(!KZG_Bls12_381::batch_check_prepared(
+            &vk, &prepared, &points, &values, &proofs, rng
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn end_to_end_test() {
+        end_to_end_test_template::<Bls12_377, UniPoly_377>().expect("test failed for bls12-377");
+        end_to_end_test_template::<Bls12_381, UniPoly_381>().expect("test failed for bls12-381");
+    }
+
+    #[test]
+    fn compressed_round_trip_test() {
+        type UniPoly_254 = DensePoly<<Bn254 as PairingEngine>::Fr>;
+        compressed_round_trip_test_template::<Bls12_381, UniPoly_381>()
+            .expect("test failed for bls12-381");
+        compressed_round_trip_test_template::<Bn254, UniPoly_254>()
+            .expect("test failed for bn254");
+        compressed_round_trip_test_template::<Bls12_377, UniPoly_377>()
+            .expect("test failed for bls12-377");
+    }
+
+    #[test]
+    fn linear_polynomial_test() {
+        linear_polynomial_test_template::<Bls12_377, UniPoly_377>()
+            .expect("test failed for bls12-377");
+        linear_polynomial_test_template::<Bls12_381, UniPoly_381>()
+            .expect("test failed for bls12-381");
+    }
+    #[test]
+    fn batch_check_test() {
+        batch_check_test_template::<Bls12_377, UniPoly_377>().expect("test failed for bls12-377");
+        batch_check_test_template::<Bls12_381, UniPoly_381>().expect("test failed for bls12-381");
+    }
+
+    #[test]
+    fn test_degree_is_too_large() {
+        let rng = &mut test_rng();
+
+        let max_degree = 123;
+        let pp = KZG_Bls12_381::setup(max_degree, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, max_degree).unwrap();
+
+        let p = DensePoly::<Fr>::rand(max_degree + 1, rng);
+        assert!(p.degree() > max_degree);
+        assert!(KZG_Bls12_381::check_degree_is_too_large(p.degree(), powers.size()).is_err());
+    }
+
+    #[test]
+    fn commit_sparse_test() {
+        let rng = &mut test_rng();
+        let degree = 64;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let terms = [(0, Fr::rand(rng)), (17, Fr::rand(rng)), (degree, Fr::rand(rng))];
+        let mut coeffs = vec![Fr::zero(); degree + 1];
+        for (i, c) in terms {
+            coeffs[i] = c;
+        }
+        let dense = KZG_Bls12_381::commit(&powers, &UniPoly_381::from_coefficients_vec(coeffs));
+        assert_eq!(KZG_Bls12_381::commit_sparse(&powers, &terms).unwrap(), dense.unwrap());
+        assert!(KZG_Bls12_381::commit_sparse(&powers, &[]).unwrap().0.is_zero());
+        assert!(matches!(
+            KZG_Bls12_381::commit_sparse(&powers, &[(degree + 1, Fr::one())]),
+            Err(Error::PolynomialExceedsTrimmedDegree { .. })
+        ));
+    }
+
+    #[test]
+    fn commit_constant_time_test() {
+        let rng = &mut test_rng();
+        let degree = 64;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let ct_powers = ConstantTimePowers::prepare(&powers);
+        for num_leading_zeros in [0, 1, 32, degree] {
+            let mut coeffs = UniPoly_381::rand(degree, rng).coeffs;
+            coeffs[..num_leading_zeros].fill(Fr::zero());
+            let p = UniPoly_381::from_coefficients_vec(coeffs);
+            assert_eq!(
+                KZG_Bls12_381::commit_constant_time(&ct_powers, &p).unwrap(),
+                KZG_Bls12_381::commit(&powers, &p).unwrap()
+            );
+        }
+        // Shorter than the powers, and zero
+        let p = UniPoly_381::rand(degree / 2, rng);
+        assert_eq!(
+            KZG_Bls12_381::commit_constant_time(&ct_powers, &p).unwrap(),
+            KZG_Bls12_381::commit(&powers, &p).unwrap()
+        );
+        let zero = KZG_Bls12_381::commit_constant_time(&ct_powers, &UniPoly_381::zero()).unwrap();
+        assert!(zero.0.is_zero());
+        assert!(matches!(
+            KZG_Bls12_381::commit_constant_time(&ct_powers, &UniPoly_381::rand(degree + 1, rng)),
+            Err(Error::PolynomialExceedsTrimmedDegree { .. })
+        ));
+    }
+
+    #[test]
+    fn batch_open_same_point_test() {
+        let rng = &mut test_rng();
+        let degree = 32;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let polys: Vec<_> = (0..5).map(|i| UniPoly_381::rand(degree - i, rng)).collect();
+        let comms = KZG_Bls12_381::batch_commit(&powers, &polys).unwrap();
+        let point = Fr::rand(rng);
+        let challenge = Fr::rand(rng);
+        let mut values: Vec<_> = polys.iter().map(|p| p.evaluate(&point)).collect();
+
+        let proof =
+            KZG_Bls12_381::batch_open_same_point(&powers, &polys, point, challenge).unwrap();
+        let check = |values: &[Fr], challenge| {
+            KZG_Bls12_381::batch_check_same_point(&vk, &comms, point, values, &proof, challenge)
+        };
+        assert!(check(&values, challenge).unwrap());
+        assert!(!check(&values, challenge + Fr::one()).unwrap());
+        assert!(matches!(
+            check(&values[1..], challenge),
+            Err(Error::WrongNumberOfEvaluations { .. })
+        ));
+        values[3] += Fr::one();
+        assert!(!check(&values, challenge).unwrap());
+    }
+
+    #[test]
+    fn merkle_coefficient_test() {
+        let rng = &mut test_rng();
+        let degree = 20;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let p = UniPoly_381::rand(degree, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        let root = KZG_Bls12_381::coefficients_merkle_tree(&p).root();
+        let point = Fr::rand(rng);
+        let value = p.evaluate(&point);
+
+        let proof = KZG_Bls12_381::open_with_merkle_coefficient(&powers, &p, point, &root).unwrap();
+        // 21 coefficients are padded to 32 leaves
+        assert_eq!(proof.path.len(), 5);
+        assert_eq!(proof.coeff, p.coeffs.get(proof.index).copied().unwrap_or_default());
+        let check = |value, root: &Hash, proof: &MerkleCoefficientProof<Bls12_381>| {
+            KZG_Bls12_381::check_with_merkle_coefficient(&vk, &comm, point, value, root, proof)
+                .unwrap()
+        };
+        assert!(check(value, &root, &proof));
+        assert!(!check(value + Fr::one(), &root, &proof));
+
+        let mut bad_coeff = proof.clone();
+        bad_coeff.coeff += Fr::one();
+        assert!(!check(value, &root, &bad_coeff));
+        let mut bad_index = proof.clone();
+        bad_index.index ^= 1;
+        assert!(!check(value, &root, &bad_index));
+        let mut long_path = proof.clone();
+        long_path.path = vec![root; usize::BITS as usize];
+        assert!(!check(value, &root, &long_path));
+
+        // The root has to be the one of `p`
+        let other_root = KZG_Bls12_381::coefficients_merkle_tree(&UniPoly_381::rand(degree, rng));
+        assert!(!check(value, &other_root.root(), &proof));
+        assert!(matches!(
+            KZG_Bls12_381::open_with_merkle_coefficient(&powers, &p, point, &other_root.root()),
+            Err(Error::MerkleRootMismatch)
+        ));
+    }
+
+    #[test]
+    fn g2_commit_test() {
+        let rng = &mut test_rng();
+        let degree = 16;
+        let pp = KZG_Bls12_381::setup_with_g2_powers(degree, degree + 1, &[], rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let p = UniPoly_381::rand(degree, rng);
+        let comm_g2 = KZG_Bls12_381::commit_g2(&vk.powers_of_h, &p).unwrap();
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        // Both commit to p(beta)
+        assert_eq!(
+            Bls12_381::pairing(comm.0, vk.h),
+            Bls12_381::pairing(vk.g, comm_g2)
+        );
+
+        let point = Fr::rand(rng);
+        let value = p.evaluate(&point);
+        let proof = KZG_Bls12_381::open(&powers, &p, point).unwrap();
+        assert!(KZG_Bls12_381::check_g2_commit(&vk, &comm_g2, point, value, &proof).unwrap());
+        let wrong = value + Fr::one();
+        assert!(!KZG_Bls12_381::check_g2_commit(&vk, &comm_g2, point, wrong, &proof).unwrap());
+
+        let too_long = UniPoly_381::rand(degree + 1, rng);
+        assert!(KZG_Bls12_381::commit_g2(&vk.powers_of_h, &too_long).is_err());
+    }
+
+    #[test]
+    fn windowed_test() {
+        let rng = &mut test_rng();
+        let degree = 16;
+        let pp = KZG_Bls12_381::setup_with_g2_powers(degree, degree + 1, &[], rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let p = UniPoly_381::rand(degree - 4, rng);
+        let point = Fr::rand(rng);
+        let value = p.evaluate(&point);
+        let proof = KZG_Bls12_381::open(&powers, &p, point).unwrap();
+
+        let comm_0 = KZG_Bls12_381::commit_windowed(&powers, &p, 0).unwrap();
+        let comm_4 = KZG_Bls12_381::commit_windowed(&powers, &p, 4).unwrap();
+        assert_eq!(comm_0, KZG_Bls12_381::commit(&powers, &p).unwrap());
+        // Committing at height 4 is committing to x^4 p(x)
+        let mut shifted = vec![Fr::zero(); 4];
+        shifted.extend_from_slice(&p.coeffs);
+        let shifted = UniPoly_381::from_coefficients_vec(shifted);
+        assert_eq!(comm_4, KZG_Bls12_381::commit(&powers, &shifted).unwrap());
+
+        assert!(KZG_Bls12_381::check_windowed(&vk, &comm_0, 0, point, value, &proof).unwrap());
+        assert!(KZG_Bls12_381::check_windowed(&vk, &comm_4, 4, point, value, &proof).unwrap());
+        assert!(!KZG_Bls12_381::check_windowed(&vk, &comm_4, 0, point, value, &proof).unwrap());
+        assert!(!KZG_Bls12_381::check_windowed(&vk, &comm_0, 4, point, value, &proof).unwrap());
+        let wrong = value + Fr::one();
+        assert!(!KZG_Bls12_381::check_windowed(&vk, &comm_4, 4, point, wrong, &proof).unwrap());
+
+        assert!(KZG_Bls12_381::commit_windowed(&powers, &p, 5).is_err());
+        assert!(matches!(
+            KZG_Bls12_381::check_windowed(&vk, &comm_4, degree, point, value, &proof),
+            Err(Error::WindowOffsetTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn from_ptau_g1_g2_test() {
+        let rng = &mut test_rng();
+        // A toy ceremony with tau = 5
+        let degree = 8;
+        let tau = Fr::from(5u64);
+        let g = <Bls12_381 as PairingEngine>::G1Affine::prime_subgroup_generator();
+        let h = <Bls12_381 as PairingEngine>::G2Affine::prime_subgroup_generator();
+        let mut g1_powers = vec![g];
+        for i in 1..=degree {
+            g1_powers.push(g1_powers[i - 1].mul(tau).into_affine());
+        }
+        let beta_h = h.mul(tau).into_affine();
+
+        let pp = UniversalParams::<Bls12_381>::from_ptau_g1_g2(&g1_powers, h, beta_h, rng)
+            .unwrap();
+        assert_eq!(pp.powers_of_g, g1_powers);
+        assert_eq!(pp.powers_of_gamma_g.len(), degree + 1);
+        // Each gamma power is gamma times the power of g, for the same gamma
+        let gamma_g = pp.powers_of_gamma_g[&0];
+        assert_eq!(pp.powers_of_gamma_g[&1], gamma_g.mul(tau).into_affine());
+
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let p = UniPoly_381::rand(degree, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        let point = Fr::rand(rng);
+        let proof = KZG_Bls12_381::open(&powers, &p, point).unwrap();
+        assert!(KZG_Bls12_381::check(&vk, &comm, point, p.evaluate(&point), &proof).unwrap());
+        let (comm, rand) = KZG_Bls12_381::commit_hiding(&powers, &p, 1, rng).unwrap();
+        let proof = KZG_Bls12_381::open_hiding(&powers, &p, point, &rand).unwrap();
+        let value = p.evaluate(&point);
+        assert!(KZG_Bls12_381::check_hiding(&vk, &comm, point, value, &proof).unwrap());
+
+        assert!(matches!(
+            UniversalParams::<Bls12_381>::from_ptau_g1_g2(&[], h, beta_h, rng),
+            Err(Error::InvalidTrustedSetup(_))
+        ));
+        assert!(matches!(
+            UniversalParams::<Bls12_381>::from_ptau_g1_g2(&g1_powers[1..], h, beta_h, rng),
+            Err(Error::InvalidTrustedSetup(_))
+        ));
+    }
+
+    #[test]
+    fn verify_well_formed_test() {
+        let rng = &mut test_rng();
+        let pp = KZG_Bls12_381::setup(16, rng).unwrap();
+        pp.verify_well_formed().unwrap();
+
+        let mut corrupted = pp.clone();
+        corrupted.powers_of_g[7] = corrupted.powers_of_g[7] + pp.powers_of_g[0];
+        assert!(matches!(
+            corrupted.verify_well_formed(),
+            Err(Error::InvalidTrustedSetup(_))
+        ));
+
+        // Consecutive powers of the wrong beta
+        let mut swapped = pp.clone();
+        swapped.powers_of_g.reverse();
+        assert!(swapped.verify_well_formed().is_err());
+
+        let mut empty = pp;
+        empty.powers_of_g.clear();
+        assert!(empty.verify_well_formed().is_err());
+
+        let pp = KZG_Bls12_381::setup_with_g2_powers(16, 17, &[8], rng).unwrap();
+        pp.verify_well_formed().unwrap();
+        let corruptions: Vec<fn(&mut UniversalParams<Bls12_381>)> = vec![
+            |pp| pp.powers_of_g.iter_mut().for_each(|p| *p = Zero::zero()),
+            |pp| pp.powers_of_gamma_g.values_mut().for_each(|p| *p = Zero::zero()),
+            |pp| {
+                pp.h = Zero::zero();
+                pp.powers_of_h[0] = pp.h;
+                pp.prepared_h = pp.h.into();
+            },
+            |pp| pp.prepared_beta_h = pp.h.into(),
+            |pp| pp.powers_of_h[0] = pp.powers_of_h[2],
+            |pp| pp.powers_of_h[9] = pp.powers_of_h[10],
+            |pp| {
+                let p = pp.powers_of_gamma_g[&4] + pp.powers_of_gamma_g[&0];
+                pp.powers_of_gamma_g.insert(4, p);
+            },
+            |pp| {
+                let p = pp.powers_of_h[7];
+                pp.degree_bound_powers_of_h.insert(8, p);
+            },
+            |pp| {
+                let p = pp.powers_of_h[0];
+                pp.degree_bound_powers_of_h.insert(17, p);
+            },
+        ];
+        for corrupt in corruptions {
+            let mut bad = pp.clone();
+            corrupt(&mut bad);
+            assert!(matches!(
+                bad.verify_well_formed(),
+                Err(Error::InvalidTrustedSetup(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn setup_cached_test() {
+        let rng = &mut test_rng();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("srs.bin");
+        let assert_same = |a: &UniversalParams<Bls12_381>, b: &UniversalParams<Bls12_381>| {
+            assert_eq!(a.powers_of_g, b.powers_of_g);
+            assert_eq!(a.powers_of_gamma_g, b.powers_of_gamma_g);
+            assert_eq!(a.powers_of_h, b.powers_of_h);
+            assert_eq!((a.h, a.beta_h), (b.h, b.beta_h));
+        };
+
+        let pp = KZG_Bls12_381::setup_cached(16, &path, rng).unwrap();
+        assert!(path.exists());
+        let loaded = KZG_Bls12_381::setup_cached(16, &path, rng).unwrap();
+        assert_same(&pp, &loaded);
+
+        // More powers than needed are truncated on load
+        let smaller = KZG_Bls12_381::setup_cached(8, &path, rng).unwrap();
+        let mut truncated = pp.clone();
+        truncated.truncate(8);
+        assert_same(&smaller, &truncated);
+        assert_eq!(smaller.powers_of_g.len(), 9);
+        assert_eq!(smaller.powers_of_gamma_g.len(), 10);
+
+        // Too few are regenerated, and the file overwritten
+        let larger = KZG_Bls12_381::setup_cached(32, &path, rng).unwrap();
+        assert_eq!(larger.powers_of_g.len(), 33);
+        assert_ne!(larger.powers_of_g[..17], pp.powers_of_g[..]);
+        let reloaded = KZG_Bls12_381::setup_cached(32, &path, rng).unwrap();
+        assert_same(&larger, &reloaded);
+    }
+
+    #[test]
+    fn equals_public_test() {
+        let rng = &mut test_rng();
+        let degree = 16;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm, rand) = KZG_Bls12_381::commit_hiding(&powers, &p, 2, rng).unwrap();
+        let proof = KZG_Bls12_381::prove_equals_public(&powers, &rand, &p).unwrap();
+        assert_eq!(proof.blinding_polynomial.coeffs.len(), 3);
+        assert!(KZG_Bls12_381::verify_equals_public(&powers, &comm, &p, &proof).unwrap());
+
+        let q = UniPoly_381::rand(degree, rng);
+        assert!(!KZG_Bls12_381::verify_equals_public(&powers, &comm, &q, &proof).unwrap());
+        let (_, other_rand) = KZG_Bls12_381::commit_hiding(&powers, &p, 2, rng).unwrap();
+        let wrong_blinding = KZG_Bls12_381::prove_equals_public(&powers, &other_rand, &p).unwrap();
+        assert!(!KZG_Bls12_381::verify_equals_public(&powers, &comm, &p, &wrong_blinding).unwrap());
+    }
+
+    #[test]
+    fn commit_bytes_test() {
+        let rng = &mut test_rng();
+        let degree = 32;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        // Fills all 33 coefficients, the last one partly
+        let mut bytes = vec![0u8; 32 * 31 + 5];
+        rng.fill_bytes(&mut bytes);
+        let packed = pack_bytes_to_field::<Fr>(&bytes);
+        assert_eq!(packed.len(), degree + 1);
+        assert_eq!(
+            KZG_Bls12_381::commit_bytes(&powers, &bytes).unwrap(),
+            KZG_Bls12_381::commit(&powers, &UniPoly_381::from_coefficients_vec(packed)).unwrap()
+        );
+        bytes.extend_from_slice(&[1; 31]);
+        assert!(KZG_Bls12_381::commit_bytes(&powers, &bytes).is_err());
+    }
+
+    #[test]
+    fn batch_commit_test() {
+        let rng = &mut test_rng();
+        let degree = 32;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let polys: Vec<_> = (0..10).map(|i| UniPoly_381::rand(3 * i, rng)).collect();
+        let comms = KZG_Bls12_381::batch_commit(&powers, &polys).unwrap();
+        assert_eq!(comms.len(), polys.len());
+        for (p, c) in polys.iter().zip(&comms) {
+            assert_eq!(*c, KZG_Bls12_381::commit(&powers, p).unwrap());
+        }
+
+        let mut too_long = polys;
+        too_long.push(UniPoly_381::rand(degree + 1, rng));
+        assert!(matches!(
+            KZG_Bls12_381::batch_commit(&powers, &too_long),
+            Err(Error::PolynomialExceedsTrimmedDegree { .. })
+        ));
+    }
+
+    #[test]
+    fn domain_point_test() {
+        let rng = &mut test_rng();
+        let degree = 16;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let domain = Radix2EvaluationDomain::<Fr>::new(degree).unwrap();
+        let p = UniPoly_381::rand(degree, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        for j in [0, 1, 7, degree - 1] {
+            let point = DomainPoint::new(&domain, j);
+            let proof = KZG_Bls12_381::open_at_domain_point(&powers, &p, point).unwrap();
+            let direct = KZG_Bls12_381::open(&powers, &p, domain.element(j)).unwrap();
+            assert_eq!(proof.w, direct.w);
+            let value = p.evaluate(&domain.element(j));
+            assert!(
+                KZG_Bls12_381::check_at_domain_point(&vk, &comm, point, value, &proof).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn domain_sum_test() {
+        let rng = &mut test_rng();
+        let degree = 16;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let domain = Radix2EvaluationDomain::<Fr>::new(32).unwrap();
+        let p = UniPoly_381::rand(degree, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        let (sum, proof) = KZG_Bls12_381::prove_domain_sum(&powers, &p, &domain).unwrap();
+        assert_eq!(sum, domain.elements().map(|x| p.evaluate(&x)).sum::<Fr>());
+        assert!(KZG_Bls12_381::verify_domain_sum(&vk, &comm, &domain, sum, &proof).unwrap());
+        assert!(
+            !KZG_Bls12_381::verify_domain_sum(&vk, &comm, &domain, sum + Fr::one(), &proof)
+                .unwrap()
+        );
+
+        let small_domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        assert!(matches!(
+            KZG_Bls12_381::prove_domain_sum(&powers, &p, &small_domain),
+            Err(Error::ExceedsDomainSize {
+                poly_degree: 16,
+                domain_size: 16
+            })
+        ));
+        assert!(matches!(
+            KZG_Bls12_381::verify_domain_sum(&vk, &comm, &small_domain, sum, &proof),
+            Err(Error::DomainNotLargerThanDegree {
+                domain_size: 16,
+                supported_degree: 16
+            })
+        ));
+
+        // A prover holding the whole SRS could commit past the trimmed degree
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, 8).unwrap();
+        let p = UniPoly_381::rand(8, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        let (sum, proof) = KZG_Bls12_381::prove_domain_sum(&powers, &p, &small_domain).unwrap();
+        assert!(KZG_Bls12_381::verify_domain_sum(&vk, &comm, &small_domain, sum, &proof).is_err());
+    }
+
+    #[test]
+    fn vanishes_on_domain_test() {
+        let rng = &mut test_rng();
+        let degree = 32;
+        let pp = KZG_Bls12_381::setup_with_g2_powers(degree, degree + 1, &[], rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        let q = UniPoly_381::rand(degree - 16, rng);
+        let p = q.mul_by_vanishing_poly(domain);
+        assert!(domain.elements().all(|x| p.evaluate(&x).is_zero()));
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        let proof = KZG_Bls12_381::prove_vanishes_on_domain(&powers, &p, &domain).unwrap();
+        assert_eq!(proof.w, KZG_Bls12_381::commit(&powers, &q).unwrap().0);
+        assert!(KZG_Bls12_381::verify_vanishes_on_domain(&vk, &comm, &domain, &proof).unwrap());
+
+        // Off by a constant, `p + 1` is nonzero on the whole domain
+        let shifted = &p + &UniPoly_381::from_coefficients_vec(vec![Fr::one()]);
+        let shifted_comm = KZG_Bls12_381::commit(&powers, &shifted).unwrap();
+        assert!(matches!(
+            KZG_Bls12_381::prove_vanishes_on_domain(&powers, &shifted, &domain),
+            Err(Error::DoesNotVanishOnDomain { domain_size: 16 })
+        ));
+        assert!(
+            !KZG_Bls12_381::verify_vanishes_on_domain(&vk, &shifted_comm, &domain, &proof)
+                .unwrap()
+        );
+
+        let big_domain = Radix2EvaluationDomain::<Fr>::new(64).unwrap();
+        assert!(matches!(
+            KZG_Bls12_381::verify_vanishes_on_domain(&vk, &comm, &big_domain, &proof),
+            Err(Error::MissingG2Power {
+                power: 64,
+                num_powers: 33
+            })
+        ));
+    }
+
+    #[test]
+    fn extension_field_test() {
+        use ark_ff::{field_new, fields::Fp2};
+
+        // `u^2 = 7`, a non-residue mod r. As -1 is a square mod r, `u` has a non-square
+        // norm -7, so it's also a non-residue of the extension
+        #[derive(Clone, Debug)]
+        struct Fr2Parameters;
+        impl Fp2Parameters for Fr2Parameters {
+            type Fp = Fr;
+            const NONRESIDUE: Fr = field_new!(Fr, "7");
+            const QUADRATIC_NONRESIDUE: (Fr, Fr) = (field_new!(Fr, "0"), field_new!(Fr, "1"));
+            const FROBENIUS_COEFF_FP2_C1: &'static [Fr] =
+                &[field_new!(Fr, "1"), field_new!(Fr, "-1")];
+        }
+        type Fr2 = Fp2<Fr2Parameters>;
+
+        let rng = &mut test_rng();
+        let degree = 16;
+        let pp = KZG_Bls12_381::setup_with_g2_powers(degree, degree + 1, &[], rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let p: Vec<Fr2> = (0..=degree).map(|_| Fr2::rand(rng)).collect();
+        let comms = KZG_Bls12_381::commit_extension_field(&powers, &p).unwrap();
+        let point = Fr2::rand(rng);
+        let value = p.iter().rev().fold(Fr2::zero(), |acc, c| acc * point + c);
+        let proof = KZG_Bls12_381::open_extension_field(&powers, &comms, &p, point).unwrap();
+        assert_eq!(proof.c0_value + Fr2::new(Fr::zero(), Fr::one()) * proof.c1_value, value);
+        assert!(KZG_Bls12_381::check_extension_field(&vk, &comms, point, value, &proof).unwrap());
+        assert!(
+            !KZG_Bls12_381::check_extension_field(&vk, &comms, point, value + Fr2::one(), &proof)
+                .unwrap()
+        );
+
+        // Moving value between the components keeps the total but not the remainders
+        let mut shifted = proof.clone();
+        shifted.c0_value += Fr2::new(Fr::zero(), Fr::one());
+        shifted.c1_value -= Fr2::one();
+        assert!(
+            !KZG_Bls12_381::check_extension_field(&vk, &comms, point, value, &shifted).unwrap()
+        );
+
+        let base_point = Fr2::new(Fr::rand(rng), Fr::zero());
+        assert!(matches!(
+            KZG_Bls12_381::open_extension_field(&powers, &comms, &p, base_point),
+            Err(Error::PointInBaseField)
+        ));
+
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (_, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        assert!(matches!(
+            KZG_Bls12_381::check_extension_field(&vk, &comms, point, value, &proof),
+            Err(Error::MissingG2Power {
+                power: 2,
+                num_powers: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn hiding_test() {
+        let rng = &mut test_rng();
+        let degree = 16;
+        let hiding_bound = 2;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm, rand) = KZG_Bls12_381::commit_hiding(&powers, &p, hiding_bound, rng).unwrap();
+        assert_eq!(rand.blinding_polynomial.degree(), hiding_bound);
+        assert_ne!(comm, KZG_Bls12_381::commit(&powers, &p).unwrap());
+
+        let mut comms = Vec::new();
+        let mut points = Vec::new();
+        let mut values = Vec::new();
+        let mut proofs = Vec::new();
+        for _ in 0..hiding_bound {
+            let point = Fr::rand(rng);
+            let value = p.evaluate(&point);
+            let proof = KZG_Bls12_381::open_hiding(&powers, &p, point, &rand).unwrap();
+            assert!(KZG_Bls12_381::check_hiding(&vk, &comm, point, value, &proof).unwrap());
+            let wrong = value + Fr::one();
+            assert!(!KZG_Bls12_381::check_hiding(&vk, &comm, point, wrong, &proof).unwrap());
+            // Without the blinding value the opening doesn't match the commitment
+            assert!(!KZG_Bls12_381::check(&vk, &comm, point, value, &proof.proof).unwrap());
+            let unblinded = KZG_Bls12_381::unblind(&vk, &comm, &proof);
+            assert!(KZG_Bls12_381::check_prepared(&vk, &unblinded, point, value, &proof.proof)
+                .unwrap());
+            assert!(KZG_Bls12_381::check_low_mem(&vk, &unblinded, point, value, &proof.proof)
+                .unwrap());
+            comms.push(unblinded);
+            points.push(point);
+            values.push(value);
+            proofs.push(proof.proof);
+        }
+
+        // Batches mixing unblinded hiding claims and ordinary ones
+        let q = UniPoly_381::rand(degree, rng);
+        let point = Fr::rand(rng);
+        comms.push(KZG_Bls12_381::commit(&powers, &q).unwrap());
+        points.push(point);
+        values.push(q.evaluate(&point));
+        proofs.push(KZG_Bls12_381::open(&powers, &q, point).unwrap());
+        assert!(KZG_Bls12_381::batch_check(&vk, &comms, &points, &values, &proofs, rng).unwrap());
+        let prepared: Vec<_> = comms.iter().map(PreparedCommitment::prepare).collect();
+        assert!(KZG_Bls12_381::batch_check_prepared(
+            &vk, &prepared, &points, &values, &proofs, rng
+        )
+        .unwrap());
+        let (agg_comm, agg_point, agg_value, agg_proof) =
+            KZG_Bls12_381::aggregate_proofs(&comms, &points, &values, &proofs, Fr::rand(rng))
+                .unwrap();
+        assert!(KZG_Bls12_381::check(&vk, &agg_comm, agg_point, agg_value, &agg_proof).unwrap());
+
+        // Fresh randomness gives a different commitment to the same polynomial
+        let (other, _) = KZG_Bls12_381::commit_hiding(&powers, &p, hiding_bound, rng).unwrap();
+        assert_ne!(comm, other);
+
+        assert!(matches!(
+            KZG_Bls12_381::commit_hiding(&powers, &p, 0, rng),
+            Err(Error::HidingBoundIsZero)
+        ));
+        assert!(matches!(
+            KZG_Bls12_381::commit_hiding(&powers, &p, degree + 1, rng),
+            Err(Error::HidingBoundTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn commit_beyond_trimmed_degree_test() {
+        let rng = &mut test_rng();
+        let pp = KZG_Bls12_381::setup(256, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, 64).unwrap();
+
+        let p = DensePoly::<Fr>::rand(128, rng);
+        assert!(matches!(
+            KZG_Bls12_381::commit(&powers, &p),
+            Err(Error::PolynomialExceedsTrimmedDegree {
+                poly_degree: 128,
+                supported: 64
+            })
+        ));
+
+        let no_powers = Powers::<Bls12_381> {
+            powers_of_g: Cow::Owned(Vec::new()),
+            powers_of_gamma_g: Cow::Owned(Vec::new()),
+        };
+        assert!(matches!(
+            KZG_Bls12_381::commit(&no_powers, &DensePoly::<Fr>::zero()),
+            Err(Error::NoPowers)
+        ));
+    }
+
+    #[test]
+    fn degree_bound_test() {
+        let rng = &mut test_rng();
+        let max_degree = 32;
+        let pp = KZG_Bls12_381::setup_with_g2_powers(max_degree, 2, &[8, 16], rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, 16).unwrap();
+
+        let p = UniPoly_381::rand(10, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+
+        let proof = KZG_Bls12_381::prove_degree_bound(&pp, &p, 16).unwrap();
+        assert!(KZG_Bls12_381::verify_degree_bound(&vk, &comm, 16, &proof).unwrap());
+        // A proof for a looser bound says nothing about a tighter one
+        assert!(!KZG_Bls12_381::verify_degree_bound(&vk, &comm, 8, &proof).unwrap());
+
+        // The honest prover can't shift a degree-10 polynomial by `D - 8`
+        assert!(KZG_Bls12_381::prove_degree_bound(&pp, &p, 8).is_err());
+        // Neither can a cheating prover that shifts only the low coefficients
+        let truncated = DensePoly::from_coefficients_slice(&p.coeffs[..=8]);
+        let forged = KZG_Bls12_381::prove_degree_bound(&pp, &truncated, 8).unwrap();
+        assert!(!KZG_Bls12_381::verify_degree_bound(&vk, &comm, 8, &forged).unwrap());
+        // Nor one that shifts by the degree of the trimmed key rather than of `pp`
+        let mut shifted_coeffs = vec![Fr::zero(); 16 - 8];
+        shifted_coeffs.extend_from_slice(&p.coeffs);
+        let shifted = DensePoly::from_coefficients_vec(shifted_coeffs);
+        let (larger_powers, _) = KZG_Bls12_381::trim(&pp, 24).unwrap();
+        let Commitment(w) = KZG_Bls12_381::commit(&larger_powers, &shifted).unwrap();
+        let forged = Proof { w };
+        assert!(!KZG_Bls12_381::verify_degree_bound(&vk, &comm, 8, &forged).unwrap());
+
+        // Bounds whose G2 power wasn't made at setup are an error, not a failed check
+        assert!(matches!(
+            KZG_Bls12_381::verify_degree_bound(&vk, &comm, 12, &proof),
+            Err(Error::MissingDegreeBoundPower(12))
+        ));
+        assert!(matches!(
+            KZG_Bls12_381::verify_degree_bound(&vk, &comm, 33, &proof),
+            Err(Error::UnsupportedDegreeBound(33))
+        ));
+    }
+
+    #[test]
+    fn value_and_derivative_test() {
+        let rng = &mut test_rng();
+        let degree = 32;
+        let pp = KZG_Bls12_381::setup_with_g2_powers(degree, degree + 1, &[], rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        for _ in 0..10 {
+            let p = UniPoly_381::rand(degree, rng);
+            let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+            let point = Fr::rand(rng);
+            let (value, derivative, proof) =
+                KZG_Bls12_381::open_value_and_derivative(&powers, &p, point).unwrap();
+
+            // p'(x) = \sum_i i c_i x^{i - 1}
+            let derivative_poly = UniPoly_381::from_coefficients_vec(
+                p.coeffs
+                    .iter()
+                    .enumerate()
+                    .skip(1)
+                    .map(|(i, c)| Fr::from(i as u64) * c)
+                    .collect(),
+            );
+            assert_eq!(value, p.evaluate(&point));
+            assert_eq!(derivative, derivative_poly.evaluate(&point));
+
+            assert!(KZG_Bls12_381::check_value_and_derivative(
+                &vk, &comm, point, value, derivative, &proof
+            )
+            .unwrap());
+            assert!(!KZG_Bls12_381::check_value_and_derivative(
+                &vk,
+                &comm,
+                point,
+                value,
+                derivative + Fr::one(),
+                &proof
+            )
+            .unwrap());
+        }
+
+        let p = UniPoly_381::rand(degree, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        let point = Fr::rand(rng);
+        let (value, derivative, proof) =
+            KZG_Bls12_381::open_value_and_derivative(&powers, &p, point).unwrap();
+        let (_, vk) = KZG_Bls12_381::trim(&KZG_Bls12_381::setup(degree, rng).unwrap(), degree)
+            .unwrap();
+        assert!(matches!(
+            KZG_Bls12_381::check_value_and_derivative(&vk, &comm, point, value, derivative, &proof),
+            Err(Error::MissingG2Power {
+                power: 2,
+                num_powers: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn open_over_coset_test() {
+        let rng = &mut test_rng();
+        let degree = 200;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let domain = Radix2EvaluationDomain::<Fr>::new(64).unwrap();
+        let shift = Fr::multiplicative_generator();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        let (evals, proof) =
+            KZG_Bls12_381::open_over_coset(&powers, &p, &comm, &domain, shift).unwrap();
+
+        assert_eq!(evals.len(), 64);
+        for (i, e) in evals.iter().enumerate() {
+            assert_eq!(*e, p.evaluate(&(shift * domain.element(i))));
+        }
+        assert!(KZG_Bls12_381::check_over_coset(&vk, &comm, &domain, shift, &evals, &proof)
+            .unwrap());
+
+        let mut bad_evals = evals.clone();
+        bad_evals[3] += Fr::one();
+        assert!(
+            !KZG_Bls12_381::check_over_coset(&vk, &comm, &domain, shift, &bad_evals, &proof)
+                .unwrap()
+        );
+        assert_eq!(
+            KZG_Bls12_381::check_over_coset(&vk, &comm, &domain, shift, &evals[1..], &proof)
+                .unwrap_err()
+                .to_string(),
+            "Expected 64 evaluations, got 63"
+        );
+
+        // With z fixed in advance, the constant quotient (p(z) - r(z)) / Z(z) and the
+        // opening of p at z would pass the check for any evaluations. The challenge
+        // depends on the quotient commitment, so the forger can't pick z first
+        let z = Fr::rand(rng);
+        let bad_r = DensePoly::from_coefficients_vec(domain.coset_ifft(&bad_evals));
+        let z_z = z.pow([64]) - shift.pow([64]);
+        let forged_q = DensePoly::from_coefficients_vec(vec![
+            (p.evaluate(&z) - bad_r.evaluate(&z)) / z_z,
+        ]);
+        let forged: CosetProof<Bls12_381> = CosetProof {
+            w_quotient: KZG_Bls12_381::commit(&powers, &forged_q).unwrap().0,
+            w_challenge: KZG_Bls12_381::open(&powers, &p, z).unwrap().w,
+        };
+        let lhs = comm.0.into_projective()
+            - vk.g.mul(bad_r.evaluate(&z))
+            - forged.w_quotient.mul(z_z);
+        let rhs = vk.beta_h.into_projective() - vk.h.mul(z);
+        assert_eq!(
+            Bls12_381::pairing(lhs, vk.h),
+            Bls12_381::pairing(forged.w_challenge, rhs)
+        );
+        assert!(
+            !KZG_Bls12_381::check_over_coset(&vk, &comm, &domain, shift, &bad_evals, &forged)
+                .unwrap()
+        );
+
+        // Polynomials smaller than the domain have no quotient
+        let small = UniPoly_381::rand(10, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &small).unwrap();
+        let (evals, proof) =
+            KZG_Bls12_381::open_over_coset(&powers, &small, &comm, &domain, shift).unwrap();
+        assert!(KZG_Bls12_381::check_over_coset(&vk, &comm, &domain, shift, &evals, &proof)
+            .unwrap());
+    }
+
+    #[test]
+    fn same_poly_test() {
+        let rng = &mut test_rng();
+        let degree = 32;
+        // Two independent setups, so two different taus
+        let old_pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let new_pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (old_powers, old_vk) = KZG_Bls12_381::trim(&old_pp, degree).unwrap();
+        let (new_powers, new_vk) = KZG_Bls12_381::trim(&new_pp, degree).unwrap();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let old_comm = KZG_Bls12_381::commit(&old_powers, &p).unwrap();
+        let new_comm = KZG_Bls12_381::commit(&new_powers, &p).unwrap();
+        assert_ne!(old_comm, new_comm);
+
+        let challenge = Fr::rand(rng);
+        let (value, proofs) =
+            KZG_Bls12_381::prove_same_poly(&old_powers, &new_powers, &p, challenge).unwrap();
+        assert!(KZG_Bls12_381::verify_same_poly(
+            &old_vk, &new_vk, &old_comm, &new_comm, challenge, value, &proofs
+        )
+        .unwrap());
+
+        // A different polynomial under the new SRS can't share the value
+        let q = UniPoly_381::rand(degree, rng);
+        let q_comm = KZG_Bls12_381::commit(&new_powers, &q).unwrap();
+        let q_proof = KZG_Bls12_381::open(&new_powers, &q, challenge).unwrap();
+        let mixed = [proofs[0].clone(), q_proof];
+        assert!(!KZG_Bls12_381::verify_same_poly(
+            &old_vk, &new_vk, &old_comm, &q_comm, challenge, value, &mixed
+        )
+        .unwrap());
+        assert!(!KZG_Bls12_381::verify_same_poly(
+            &old_vk,
+            &new_vk,
+            &old_comm,
+            &q_comm,
+            challenge,
+            q.evaluate(&challenge),
+            &mixed
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn shift_relation_test() {
+        let rng = &mut test_rng();
+        let degree = 32;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let omega = Radix2EvaluationDomain::<Fr>::new(64).unwrap().group_gen;
+
+        let p = UniPoly_381::rand(degree, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        let (rotated_comm, proof) =
+            KZG_Bls12_381::commit_shift_relation(&powers, &p, omega).unwrap();
+
+        // Rotating explicitly, through evaluations at `\omega x` over a larger domain
+        let domain = Radix2EvaluationDomain::<Fr>::new(64).unwrap();
+        let evals: Vec<_> = domain
+            .elements()
+            .map(|x| p.evaluate(&(omega * x)))
+            .collect();
+        let rotated = UniPoly_381::from_coefficients_vec(evals_to_coeffs(&evals, &domain));
+        assert_eq!(rotated_comm, KZG_Bls12_381::commit(&powers, &rotated).unwrap());
+        assert!(
+            KZG_Bls12_381::check_shift_relation(&vk, &comm, &rotated_comm, omega, &proof).unwrap()
+        );
+
+        // Neither an unrelated commitment nor the wrong rotation passes
+        let q = UniPoly_381::rand(degree, rng);
+        let q_comm = KZG_Bls12_381::commit(&powers, &q).unwrap();
+        assert!(
+            !KZG_Bls12_381::check_shift_relation(&vk, &comm, &q_comm, omega, &proof).unwrap()
+        );
+        let omega_2 = omega.square();
+        assert!(
+            !KZG_Bls12_381::check_shift_relation(&vk, &comm, &rotated_comm, omega_2, &proof)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn versioned_serialization_test() {
+        fn round_trip<T: CanonicalSerialize + CanonicalDeserialize>(value: &T) {
+            let mut bytes = Vec::new();
+            serialize_versioned(value, &mut bytes).unwrap();
+            assert_eq!(bytes[0], SERIALIZATION_VERSION);
+            let back: T = deserialize_versioned(&bytes[..]).unwrap();
+            let mut expected = Vec::new();
+            value.serialize(&mut expected).unwrap();
+            let mut got = Vec::new();
+            back.serialize(&mut got).unwrap();
+            assert_eq!(expected, got);
+        }
+
+        let rng = &mut test_rng();
+        let degree = 16;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let p = UniPoly_381::rand(degree, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        let proof = KZG_Bls12_381::open(&powers, &p, Fr::rand(rng)).unwrap();
+        round_trip(&pp);
+        round_trip(&powers);
+        round_trip(&comm);
+        round_trip(&proof);
+
+        let mut bytes = Vec::new();
+        serialize_versioned(&comm, &mut bytes).unwrap();
+        bytes[0] += 1;
+        assert!(matches!(
+            deserialize_versioned::<Commitment<Bls12_381>, _>(&bytes[..]),
+            Err(Error::UnknownSerializationVersion(v)) if v == SERIALIZATION_VERSION + 1
+        ));
+    }
+
+    #[cfg(feature = "debug-msm")]
+    #[test]
+    fn commit_debug_test() {
+        let rng = &mut test_rng();
+        for degree in [10, 200] {
+            let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+            let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+            let p = UniPoly_381::rand(degree, rng);
+            let (comm, partial_sums) = KZG_Bls12_381::commit_debug(&powers, &p).unwrap();
+            assert_eq!(comm, KZG_Bls12_381::commit(&powers, &p).unwrap());
+            let sum: <Bls12_381 as PairingEngine>::G1Projective = partial_sums.iter().sum();
+            assert_eq!(sum.into_affine(), comm.0);
+        }
+    }
+
+    #[test]
+    fn commit_cost_estimate_test() {
+        assert!(KZG_Bls12_381::commit_cost_estimate(1) > 0);
+        // Below the dip at `2^18`
+        let costs: Vec<_> = (1..1 << 16)
+            .map(KZG_Bls12_381::commit_cost_estimate)
+            .collect();
+        assert!(costs.windows(2).all(|w| w[0] <= w[1]));
+        // The window grows from 3 to 5 bits at 32 scalars
+        assert_eq!(KZG_Bls12_381::commit_cost_estimate(30), 85 * (31 + 14) + 255 + 85);
+        assert_eq!(KZG_Bls12_381::commit_cost_estimate(31), 51 * (32 + 62) + 255 + 51);
+    }
+
+    #[test]
+    fn commit_from_iter_test() {
+        let rng = &mut test_rng();
+        let degree = 500;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let mut p = UniPoly_381::rand(degree, rng);
+        let c = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        assert_eq!(
+            c,
+            KZG_Bls12_381::commit_from_iter(&powers, p.coeffs.iter().copied()).unwrap()
+        );
+        // Chunks that don't divide the length
+        assert_eq!(
+            c,
+            KZG_Bls12_381::commit_from_iter_chunked(&powers, p.coeffs.iter().copied(), 64)
+                .unwrap()
+        );
+
+        // Leading zeros are skipped as they stream past
+        for coeff in p.coeffs.iter_mut().take(100) {
+            *coeff = Fr::zero();
+        }
+        let c = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        assert_eq!(
+            c,
+            KZG_Bls12_381::commit_from_iter_chunked(&powers, p.coeffs.iter().copied(), 64)
+                .unwrap()
+        );
+
+        assert!(KZG_Bls12_381::commit_from_iter(
+            &powers,
+            vec![Fr::one(); degree + 2].into_iter()
+        )
+        .is_err());
+
+        // An iterator that yields more than its len() is an error, not a panic
+        let coeffs = MisreportedLen(vec![Fr::one(); degree + 2].into_iter(), 1);
+        assert!(matches!(
+            KZG_Bls12_381::commit_from_iter_chunked(&powers, coeffs, 64),
             Err(Error::TooManyCoefficients {
-                num_coefficients,
-                num_powers,
+                num_coefficients: 502,
+                num_powers: 501
             })
-        } else {
-            Ok(())
+        ));
+    }
+
+    #[test]
+    fn open_from_iter_test() {
+        let rng = &mut test_rng();
+        let degree = 500;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let point = Fr::rand(rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        let w = KZG_Bls12_381::open(&powers, &p, point).unwrap().w;
+        let streamed = [
+            KZG_Bls12_381::open_from_iter(&powers, p.coeffs.iter().rev().copied(), point),
+            // Chunks that don't divide the length
+            KZG_Bls12_381::open_from_iter_chunked(
+                &powers,
+                p.coeffs.iter().rev().copied(),
+                point,
+                64,
+            ),
+        ];
+        for (value, proof) in streamed.into_iter().map(Result::unwrap) {
+            assert_eq!(value, p.evaluate(&point));
+            assert_eq!(proof.w, w);
+            assert!(KZG_Bls12_381::check(&vk, &comm, point, value, &proof).unwrap());
         }
+
+        // A constant has a zero witness
+        let (value, proof) =
+            KZG_Bls12_381::open_from_iter(&powers, std::iter::once(Fr::one()), point).unwrap();
+        assert_eq!(value, Fr::one());
+        assert!(proof.w.is_zero());
+
+        assert!(matches!(
+            KZG_Bls12_381::open_from_iter(&powers, vec![Fr::one(); degree + 2].into_iter(), point),
+            Err(Error::TooManyCoefficients { .. })
+        ));
+        // The degree of each coefficient comes from len(), so it must be exact
+        let coeffs = MisreportedLen(vec![Fr::one(); 12].into_iter(), 10);
+        assert!(matches!(
+            KZG_Bls12_381::open_from_iter(&powers, coeffs, point),
+            Err(Error::WrongCoefficientCount {
+                reported: 10,
+                yielded: 12
+            })
+        ));
+        let coeffs = MisreportedLen(vec![Fr::one(); 10].into_iter(), 12);
+        assert!(matches!(
+            KZG_Bls12_381::open_from_iter(&powers, coeffs, point),
+            Err(Error::WrongCoefficientCount {
+                reported: 12,
+                yielded: 10
+            })
+        ));
     }
 
-    pub(crate) fn check_degrees_and_bounds<'a>(
-        supported_degree: usize,
-        max_degree: usize,
-        enforced_degree_bounds: Option<&[usize]>,
-        p: &'a LabeledPolynomial<E::Fr, P>,
-    ) -> Result<(), Error> {
-        if let Some(bound) = p.degree_bound() {
-            let enforced_degree_bounds =
-                enforced_degree_bounds.ok_or(Error::UnsupportedDegreeBound(bound))?;
+    #[test]
+    fn multi_points_test() {
+        let rng = &mut test_rng();
+        let degree = 100;
+        let pp = KZG_Bls12_381::setup_with_g2_powers(degree, degree + 1, &[], rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
 
-            if enforced_degree_bounds.binary_search(&bound).is_err() {
-                Err(Error::UnsupportedDegreeBound(bound))
-            } else if bound < p.degree() || bound > max_degree {
-                return Err(Error::IncorrectDegreeBound {
-                    poly_degree: p.degree(),
-                    degree_bound: p.degree_bound().unwrap(),
-                    supported_degree,
-                    label: p.label().to_string(),
-                });
-            } else {
-                Ok(())
-            }
-        } else {
-            Ok(())
+        let p = UniPoly_381::rand(degree, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        let points: Vec<_> = (0..5).map(|_| Fr::rand(rng)).collect();
+        let (values, proof) = KZG_Bls12_381::open_multi_points(&powers, &p, &points).unwrap();
+        for (z, v) in points.iter().zip(values.iter()) {
+            assert_eq!(p.evaluate(z), *v);
         }
+        assert!(KZG_Bls12_381::check_multi_points(&vk, &comm, &points, &values, &proof).unwrap());
+
+        let mut bad_values = values.clone();
+        bad_values[2] += Fr::one();
+        assert!(
+            !KZG_Bls12_381::check_multi_points(&vk, &comm, &points, &bad_values, &proof).unwrap()
+        );
+
+        let dup_points = vec![points[0], points[0]];
+        assert!(KZG_Bls12_381::check_multi_points(
+            &vk,
+            &comm,
+            &dup_points,
+            &values[..2],
+            &proof
+        )
+        .is_err());
+
+        // A key with no powers of h is an error, not an underflow
+        let mut empty_vk = vk.clone();
+        empty_vk.powers_of_h.clear();
+        assert!(matches!(
+            KZG_Bls12_381::check_multi_points(&empty_vk, &comm, &points, &values, &proof),
+            Err(Error::MissingG2Power {
+                power: 5,
+                num_powers: 0
+            })
+        ));
     }
-}
 
-fn skip_leading_zeros_and_convert_to_bigints<F: PrimeField, P: UVPolynomial<F>>(
-    p: &P,
-) -> (usize, Vec<F::BigInt>) {
-    let mut num_leading_zeros = 0;
-    while num_leading_zeros < p.coeffs().len() && p.coeffs()[num_leading_zeros].is_zero() {
-        num_leading_zeros += 1;
+    #[test]
+    fn open_subset_test() {
+        let rng = &mut test_rng();
+        let degree = 63;
+        let pp = KZG_Bls12_381::setup_with_g2_powers(degree, degree + 1, &[], rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let domain = Radix2EvaluationDomain::<Fr>::new(degree + 1).unwrap();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        let disclosed: Vec<_> = [3, 17, 40].iter().map(|&i| domain.element(i)).collect();
+        let (values, proof) = KZG_Bls12_381::open_subset(&powers, &p, &disclosed).unwrap();
+        assert!(KZG_Bls12_381::check_subset(&vk, &comm, &disclosed, &values, &proof).unwrap());
+
+        // Adding an undisclosed point, even with its true value, is rejected
+        let mut points = disclosed.clone();
+        points.push(domain.element(50));
+        let mut with_fourth = values.clone();
+        with_fourth.push(p.evaluate(&points[3]));
+        assert!(
+            !KZG_Bls12_381::check_subset(&vk, &comm, &points, &with_fourth, &proof).unwrap()
+        );
+        // As is swapping a disclosed point for an undisclosed one
+        points.swap_remove(0);
+        with_fourth.swap_remove(0);
+        assert!(
+            !KZG_Bls12_381::check_subset(&vk, &comm, &points, &with_fourth, &proof).unwrap()
+        );
     }
-    let coeffs = convert_to_bigints(&p.coeffs()[num_leading_zeros..]);
-    (num_leading_zeros, coeffs)
-}
 
-fn convert_to_bigints<F: PrimeField>(p: &[F]) -> Vec<F::BigInt> {
-    let coeffs = ark_std::cfg_iter!(p)
-        .map(|s| s.into_repr())
-        .collect::<Vec<_>>();
-    coeffs
-}
+    #[test]
+    fn segmented_test() {
+        let rng = &mut test_rng();
+        let chunk_degree = 100;
+        let pp = KZG_Bls12_381::setup(chunk_degree, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, chunk_degree).unwrap();
 
-#[cfg(test)]
-mod tests {
-    #![allow(non_camel_case_types)]
-    use super::*;
+        let p = UniPoly_381::rand(500, rng);
+        assert!(KZG_Bls12_381::commit(&powers, &p).is_err());
+        let comms = KZG_Bls12_381::commit_segmented(&powers, &p, chunk_degree).unwrap();
+        assert_eq!(comms.len(), 5);
 
-    use ark_bls12_377::Bls12_377;
-    use ark_bls12_381::Bls12_381;
-    use ark_bls12_381::Fr;
-    use ark_ec::PairingEngine;
-    use ark_poly::univariate::DensePolynomial as DensePoly;
-    use ark_poly::EvaluationDomain;
-    use ark_poly::Polynomial;
-    use ark_poly::Radix2EvaluationDomain;
-    use ark_poly_commit::PCCommitment;
-    use crate::test_rng;
+        // Only the coefficients either side of the boundary between the first two
+        // segments are nonzero
+        let mut coeffs = vec![Fr::zero(); 202];
+        coeffs[chunk_degree] = Fr::rand(rng);
+        coeffs[chunk_degree + 1] = Fr::rand(rng);
+        let boundary = UniPoly_381::from_coefficients_vec(coeffs);
+        let boundary_comms =
+            KZG_Bls12_381::commit_segmented(&powers, &boundary, chunk_degree).unwrap();
 
-    type UniPoly_381 = DensePoly<<Bls12_381 as PairingEngine>::Fr>;
-    type UniPoly_377 = DensePoly<<Bls12_377 as PairingEngine>::Fr>;
-    type KZG_Bls12_381 = KZG10<Bls12_381, UniPoly_381>;
+        for (p, comms) in [(&p, &comms), (&boundary, &boundary_comms)] {
+            let point = Fr::rand(rng);
+            let (value, proof) =
+                KZG_Bls12_381::open_segmented(&powers, p, chunk_degree, point).unwrap();
+            assert_eq!(value, p.evaluate(&point));
+            assert!(KZG_Bls12_381::check_segmented(
+                &vk,
+                comms,
+                chunk_degree,
+                point,
+                value,
+                &proof
+            )
+            .unwrap());
+            assert!(!KZG_Bls12_381::check_segmented(
+                &vk,
+                comms,
+                chunk_degree,
+                point,
+                value + Fr::one(),
+                &proof
+            )
+            .unwrap());
+        }
+    }
 
     #[test]
-    fn add_commitments_test() {
+    fn shared_points_test() {
         let rng = &mut test_rng();
-        let p = DensePoly::from_coefficients_slice(&[
-            Fr::rand(rng),
-            Fr::rand(rng),
-            Fr::rand(rng),
-            Fr::rand(rng),
-            Fr::rand(rng),
-        ]);
-        let f = Fr::rand(rng);
-        let mut f_p = DensePoly::zero();
-        f_p += (f, &p);
+        let degree = 50;
+        let pp = KZG_Bls12_381::setup_with_g2_powers(degree, degree + 1, &[], rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
 
-        let degree = 4;
+        let polys: Vec<_> = (0..5).map(|_| UniPoly_381::rand(degree, rng)).collect();
+        let comms: Vec<_> = polys
+            .iter()
+            .map(|p| KZG_Bls12_381::commit(&powers, p).unwrap())
+            .collect();
+        let points: Vec<_> = (0..3).map(|_| Fr::rand(rng)).collect();
+        let (values, proof) =
+            KZG_Bls12_381::open_shared_points(&powers, &polys, &comms, &points).unwrap();
+        assert_eq!(values[4][2], polys[4].evaluate(&points[2]));
+        assert!(
+            KZG_Bls12_381::batch_check_shared_points(&vk, &comms, &points, &values, &proof)
+                .unwrap()
+        );
+
+        let mut tampered = values.clone();
+        tampered[3][1] += Fr::one();
+        assert!(
+            !KZG_Bls12_381::batch_check_shared_points(&vk, &comms, &points, &tampered, &proof)
+                .unwrap()
+        );
+        assert!(KZG_Bls12_381::batch_check_shared_points(
+            &vk,
+            &comms,
+            &points,
+            &values[..4],
+            &proof
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn is_nontrivial_test() {
+        let rng = &mut test_rng();
+        let degree = 16;
         let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
         let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
 
-        let comm = KZG10::commit(&powers, &p).unwrap();
-        let f_comm = KZG10::commit(&powers, &f_p).unwrap();
-        let mut f_comm_2 = Commitment::empty();
-        f_comm_2 += (f, &comm);
+        let zero = KZG_Bls12_381::commit(&powers, &UniPoly_381::zero()).unwrap();
+        assert!(!KZG_Bls12_381::is_nontrivial(&zero));
+        let comm = KZG_Bls12_381::commit(&powers, &UniPoly_381::rand(degree, rng)).unwrap();
+        assert!(KZG_Bls12_381::is_nontrivial(&comm));
+    }
 
-        assert_eq!(f_comm, f_comm_2);
+    #[test]
+    fn witness_commitment_test() {
+        let rng = &mut test_rng();
+        let degree = 32;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let point = Fr::rand(rng);
+        let w = KZG_Bls12_381::witness_commitment(&powers, &p, point).unwrap();
+        let proof = KZG_Bls12_381::open(&powers, &p, point).unwrap();
+        assert_eq!(w.into_affine(), proof.w);
     }
 
-    fn end_to_end_test_template<E, P>() -> Result<(), Error>
-    where
-        E: PairingEngine,
-        P: UVPolynomial<E::Fr, Point = E::Fr>,
-        for<'a, 'b> &'a P: Div<&'b P, Output = P>,
-    {
+    #[test]
+    fn check_prepared_test() {
         let rng = &mut test_rng();
-        for _ in 0..100 {
-            let mut degree = 0;
-            while degree <= 1 {
-                degree = usize::rand(rng) % 20;
-            }
-            let pp = KZG10::<E, P>::setup(degree, rng)?;
-            let (ck, vk) = KZG10::<E, P>::trim(&pp, degree)?;
-            let p = P::rand(degree, rng);
-            let comm = KZG10::<E, P>::commit(&ck, &p)?;
-            let point = E::Fr::rand(rng);
-            let value = p.evaluate(&point);
-            let proof = KZG10::<E, P>::open(&ck, &p, point)?;
-            assert!(
-                KZG10::<E, P>::check(&vk, &comm, point, value, &proof)?,
-                "proof was incorrect for max_degree = {}, polynomial_degree = {}",
-                degree,
-                p.degree(),
-            );
-        }
-        Ok(())
+        let degree = 32;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        let point = Fr::rand(rng);
+        let value = p.evaluate(&point);
+        let proof = KZG_Bls12_381::open(&powers, &p, point).unwrap();
+        assert!(KZG_Bls12_381::check_prepared(&vk, &comm, point, value, &proof).unwrap());
+        assert!(
+            !KZG_Bls12_381::check_prepared(&vk, &comm, point, value + Fr::one(), &proof).unwrap()
+        );
     }
 
-    fn linear_polynomial_test_template<E, P>() -> Result<(), Error>
-    where
-        E: PairingEngine,
-        P: UVPolynomial<E::Fr, Point = E::Fr>,
-        for<'a, 'b> &'a P: Div<&'b P, Output = P>,
-    {
+    #[test]
+    fn check_low_mem_test() {
         let rng = &mut test_rng();
-        for _ in 0..100 {
-            let degree = 50;
-            let pp = KZG10::<E, P>::setup(degree, rng)?;
-            let (ck, vk) = KZG10::<E, P>::trim(&pp, 2)?;
-            let p = P::rand(1, rng);
-            let comm = KZG10::<E, P>::commit(&ck, &p)?;
-            let point = E::Fr::rand(rng);
-            let value = p.evaluate(&point);
-            let proof = KZG10::<E, P>::open(&ck, &p, point)?;
-            assert!(
-                KZG10::<E, P>::check(&vk, &comm, point, value, &proof)?,
-                "proof was incorrect for max_degree = {}, polynomial_degree = {}",
-                degree,
-                p.degree(),
+        let degree = 32;
+        let pp = KZG_Bls12_381::setup(degree, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        let point = Fr::rand(rng);
+        let proof = KZG_Bls12_381::open(&powers, &p, point).unwrap();
+        for value in [p.evaluate(&point), Fr::rand(rng)] {
+            assert_eq!(
+                KZG_Bls12_381::check_low_mem(&vk, &comm, point, value, &proof).unwrap(),
+                KZG_Bls12_381::check(&vk, &comm, point, value, &proof).unwrap()
             );
         }
-        Ok(())
+        assert!(KZG_Bls12_381::check_low_mem(&vk, &comm, point, p.evaluate(&point), &proof)
+            .unwrap());
     }
 
-    fn batch_check_test_template<E, P>() -> Result<(), Error>
-    where
-        E: PairingEngine,
-        P: UVPolynomial<E::Fr, Point = E::Fr>,
-        for<'a, 'b> &'a P: Div<&'b P, Output = P>,
-    {
+    #[test]
+    fn commit_palindromic_test() {
         let rng = &mut test_rng();
-        for _ in 0..10 {
-            let mut degree = 0;
-            while degree <= 1 {
-                degree = usize::rand(rng) % 20;
-            }
-            let pp = KZG10::<E, P>::setup(degree, rng)?;
-            let (ck, vk) = KZG10::<E, P>::trim(&pp, degree)?;
-            let mut comms = Vec::new();
-            let mut values = Vec::new();
-            let mut points = Vec::new();
-            let mut proofs = Vec::new();
-            for _ in 0..10 {
-                let p = P::rand(degree, rng);
-                let comm = KZG10::<E, P>::commit(&ck, &p)?;
-                let point = E::Fr::rand(rng);
-                let value = p.evaluate(&point);
-                let proof = KZG10::<E, P>::open(&ck, &p, point)?;
+        let pp = KZG_Bls12_381::setup(64, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, 64).unwrap();
 
-                assert!(KZG10::<E, P>::check(&vk, &comm, point, value, &proof)?);
-                comms.push(comm);
-                values.push(value);
-                points.push(point);
-                proofs.push(proof);
-            }
-            assert!(KZG10::<E, P>::batch_check(
-                &vk, &comms, &points, &values, &proofs, rng
-            )?);
+        // Both with and without a middle coefficient
+        for degree in [63, 64] {
+            let half_coeffs: Vec<_> = (0..=degree / 2).map(|_| Fr::rand(rng)).collect();
+            let coeffs: Vec<_> = (0..=degree)
+                .map(|i| half_coeffs[i.min(degree - i)])
+                .collect();
+            let p = UniPoly_381::from_coefficients_vec(coeffs.clone());
+
+            assert_eq!(
+                KZG_Bls12_381::commit(&powers, &p).unwrap(),
+                KZG_Bls12_381::commit_palindromic(&powers, &p).unwrap()
+            );
+
+            let mut skewed = coeffs;
+            skewed[degree - 3] += Fr::one();
+            let skewed = UniPoly_381::from_coefficients_vec(skewed);
+            assert!(matches!(
+                KZG_Bls12_381::commit_palindromic(&powers, &skewed),
+                Err(Error::NotPalindromic { index: 3, .. })
+            ));
         }
-        Ok(())
+        assert!(KZG_Bls12_381::commit_palindromic(&powers, &UniPoly_381::zero())
+            .unwrap()
+            .0
+            .is_zero());
     }
 
     #[test]
-    fn end_to_end_test() {
-        end_to_end_test_template::<Bls12_377, UniPoly_377>().expect("test failed for bls12-377");
-        end_to_end_test_template::<Bls12_381, UniPoly_381>().expect("test failed for bls12-381");
+    fn bit_reverse_permutation_test() {
+        assert_eq!(bit_reverse_permutation(1), vec![0]);
+        assert_eq!(bit_reverse_permutation(8), vec![0, 4, 2, 6, 1, 5, 3, 7]);
+        let perm = bit_reverse_permutation(64);
+        for i in 0..64 {
+            assert_eq!(perm[perm[i]], i);
+        }
     }
 
     #[test]
-    fn linear_polynomial_test() {
-        linear_polynomial_test_template::<Bls12_377, UniPoly_377>()
-            .expect("test failed for bls12-377");
-        linear_polynomial_test_template::<Bls12_381, UniPoly_381>()
-            .expect("test failed for bls12-381");
+    fn open_from_evals_test() {
+        let rng = &mut test_rng();
+        let n = 64;
+        let pp = KZG_Bls12_381::setup(n - 1, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, n - 1).unwrap();
+        let domain = Radix2EvaluationDomain::<Fr>::new(n).unwrap();
+
+        let p = UniPoly_381::rand(n - 1, rng);
+        let evals = coeffs_to_evals(&p.coeffs, &domain);
+        let comm = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        for point in [domain.element(0), domain.element(5), Fr::rand(rng)] {
+            let proof = KZG_Bls12_381::open_from_evals(&powers, &evals, &domain, point).unwrap();
+            assert_eq!(proof.w, KZG_Bls12_381::open(&powers, &p, point).unwrap().w);
+            assert!(KZG_Bls12_381::check(&vk, &comm, point, p.evaluate(&point), &proof).unwrap());
+        }
+        assert!(KZG_Bls12_381::open_from_evals(&powers, &evals[1..], &domain, Fr::one()).is_err());
     }
+
     #[test]
-    fn batch_check_test() {
-        batch_check_test_template::<Bls12_377, UniPoly_377>().expect("test failed for bls12-377");
-        batch_check_test_template::<Bls12_381, UniPoly_381>().expect("test failed for bls12-381");
+    fn commit_lagrange_bitreversed_test() {
+        let rng = &mut test_rng();
+        let n = 32;
+        let pp = KZG_Bls12_381::setup(n - 1, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, n - 1).unwrap();
+        let domain = Radix2EvaluationDomain::<Fr>::new(n).unwrap();
+
+        let p = UniPoly_381::rand(n - 1, rng);
+        let evals = coeffs_to_evals(&p.coeffs, &domain);
+        let bitreversed: Vec<_> = bit_reverse_permutation(n)
+            .into_iter()
+            .map(|i| evals[i])
+            .collect();
+
+        let c = KZG_Bls12_381::commit(&powers, &p).unwrap();
+        assert_eq!(c, KZG_Bls12_381::commit_lagrange(&powers, &domain, &evals).unwrap());
+        assert_eq!(
+            c,
+            KZG_Bls12_381::commit_lagrange_bitreversed(&powers, &domain, &bitreversed).unwrap()
+        );
     }
 
     #[test]
-    fn test_degree_is_too_large() {
+    fn trim_multi_test() {
         let rng = &mut test_rng();
+        let pp = KZG_Bls12_381::setup(64, rng).unwrap();
+        let degrees = [1, 7, 16, 64];
+        let keys = KZG_Bls12_381::trim_multi(&pp, &degrees).unwrap();
+        assert_eq!(keys.len(), degrees.len());
+        for (&degree, (powers, vk)) in degrees.iter().zip(&keys) {
+            assert!(matches!(powers.powers_of_g, Cow::Borrowed(_)));
+            let (owned, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+            assert_eq!(powers.powers_of_g, owned.powers_of_g);
+            assert_eq!(powers.powers_of_gamma_g, owned.powers_of_gamma_g);
 
-        let max_degree = 123;
-        let pp = KZG_Bls12_381::setup(max_degree, rng).unwrap();
-        let (powers, _) = KZG_Bls12_381::trim(&pp, max_degree).unwrap();
+            let p = UniPoly_381::rand(degree, rng);
+            let comm = KZG_Bls12_381::commit(powers, &p).unwrap();
+            assert_eq!(comm, KZG_Bls12_381::commit(&owned, &p).unwrap());
+            let point = Fr::rand(rng);
+            let proof = KZG_Bls12_381::open(powers, &p, point).unwrap();
+            assert!(KZG_Bls12_381::check(vk, &comm, point, p.evaluate(&point), &proof).unwrap());
+            assert!(KZG_Bls12_381::commit(powers, &UniPoly_381::rand(degree.max(2) + 1, rng))
+                .is_err());
+        }
+    }
 
-        let p = DensePoly::<Fr>::rand(max_degree + 1, rng);
-        assert!(p.degree() > max_degree);
-        assert!(KZG_Bls12_381::check_degree_is_too_large(p.degree(), powers.size()).is_err());
+    #[test]
+    fn commit_coset_test() {
+        let rng = &mut test_rng();
+        let n = 32;
+        let pp = KZG_Bls12_381::setup(n - 1, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, n - 1).unwrap();
+        let domain = Radix2EvaluationDomain::<Fr>::new(n).unwrap();
+
+        // arkworks' coset FFTs are over the coset of the multiplicative generator
+        let evals: Vec<Fr> = (0..n).map(|_| Fr::rand(rng)).collect();
+        let coeffs = domain.coset_ifft(&evals);
+        assert_eq!(
+            KZG_Bls12_381::commit_coset(&powers, &domain, &evals, Fr::multiplicative_generator())
+                .unwrap(),
+            KZG_Bls12_381::commit(&powers, &UniPoly_381::from_coefficients_vec(coeffs)).unwrap()
+        );
+
+        let p = UniPoly_381::rand(n - 1, rng);
+        let shift = Fr::rand(rng);
+        let evals: Vec<Fr> = domain.elements().map(|x| p.evaluate(&(shift * x))).collect();
+        assert_eq!(
+            KZG_Bls12_381::commit_coset(&powers, &domain, &evals, shift).unwrap(),
+            KZG_Bls12_381::commit(&powers, &p).unwrap()
+        );
+        assert!(matches!(
+            KZG_Bls12_381::commit_coset(&powers, &domain, &evals, Fr::zero()),
+            Err(Error::ZeroCosetShift)
+        ));
     }
 
     #[test]
@@ -510,7 +4120,7 @@ mod tests {
         let (mut commits, mut col0_opens): (Vec<_>, Vec<_>) = grid
             .iter()
             .map(|row| {
-                let coeffs = domain_n.ifft(&row);
+                let coeffs = evals_to_coeffs(row, &domain_n);
                 let poly = DensePoly { coeffs };
                 (
                     KZG10::commit(&powers, &poly)
@@ -529,8 +4139,7 @@ mod tests {
         let mut extended_grid = vec![vec![Fr::zero(); N]; 2 * N];
         for j in 0..N {
             let mut col_evals = (0..N).map(|i| grid[i][j].clone()).collect::<Vec<_>>();
-            domain_n.ifft_in_place(&mut col_evals);
-            domain_2n.fft_in_place(&mut col_evals);
+            extend_evals_in_place(&mut col_evals, &domain_n, &domain_2n);
             assert_eq!(col_evals.len(), 2 * N);
             for i in 0..(2 * N) {
                 extended_grid[i][j] = col_evals[i];
@@ -538,16 +4147,14 @@ mod tests {
         }
 
         // Extend commitments
-        domain_n.ifft_in_place(&mut commits);
-        domain_2n.fft_in_place(&mut commits);
+        extend_evals_in_place(&mut commits, &domain_n, &domain_2n);
 
         // Extend openings
-        domain_n.ifft_in_place(&mut col0_opens);
-        domain_2n.fft_in_place(&mut col0_opens);
+        extend_evals_in_place(&mut col0_opens, &domain_n, &domain_2n);
 
         // Check commitments
         for i in 0..extended_grid.len() {
-            let coeffs = domain_n.ifft(&extended_grid[i]);
+            let coeffs = evals_to_coeffs(&extended_grid[i], &domain_n);
             let res_commit = KZG10::commit(&powers, &DensePoly { coeffs }).expect("Failed commit");
             assert_eq!(res_commit.0, commits[i].into_affine());
             assert!(<KZG10<Bls12_381, DensePoly<Fr>>>::check(
@@ -555,9 +4162,7 @@ mod tests {
                 &res_commit,
                 domain_n.element(0),
                 extended_grid[i][0],
-                &Proof {
-                    w: col0_opens[i].into_affine()
-                },
+                &Proof { w: col0_opens[i].into_affine() },
             )
             .expect("Failed to check"));
         }
@@ -1,12 +1,14 @@
 use std::marker::PhantomData;
 
-use crate::{test_rng, TestRng};
+use crate::{bench_rng_for, HomomorphicPcBench, RngTag};
 use ark_bls12_381::Bls12_381;
 use ark_bn254::Bn254;
+use ark_bw6_761::BW6_761;
 use ark_ec::PairingEngine;
 use ark_poly::{univariate::DensePolynomial, Polynomial};
 use ark_serialize::CanonicalSerialize;
-use ark_std::{One, UniformRand};
+use ark_std::{One, UniformRand, Zero};
+use rand::rngs::StdRng;
 
 use crate::PcBench;
 
@@ -14,27 +16,56 @@ use super::kzg::*;
 
 pub type KzgBls12_381Bench = KzgPcBench<Bls12_381>;
 pub type KzgBn254Bench = KzgPcBench<Bn254>;
+pub type KzgBw6_761Bench = KzgPcBench<BW6_761>;
 
 pub struct Setup<UP> {
     params: UP,
-    rng: TestRng,
+    rng: StdRng,
 }
 
 pub struct KzgPcBench<E>(PhantomData<E>);
 
+impl<E: PairingEngine> KzgPcBench<E> {
+    /// A random polynomial of degree `degree` that vanishes at each of `roots`, built
+    /// by multiplying a random polynomial of degree `degree - roots.len()` by the
+    /// factors `x - r`.
+    pub fn rand_poly_with_roots(
+        s: &mut Setup<UniversalParams<E>>,
+        degree: usize,
+        roots: &[E::Fr],
+    ) -> DensePolynomial<E::Fr> {
+        assert!(roots.len() <= degree, "More roots than the degree allows");
+        let mut coeffs: Vec<E::Fr> = (0..=degree - roots.len())
+            .map(|_| E::Fr::rand(&mut s.rng))
+            .collect();
+        for r in roots {
+            // (x - r) q(x): shift up by one and subtract r q(x)
+            coeffs.insert(0, E::Fr::zero());
+            for i in 0..coeffs.len() - 1 {
+                let c = coeffs[i + 1];
+                coeffs[i] -= *r * c;
+            }
+        }
+        DensePolynomial { coeffs }
+    }
+}
+
 impl<E: PairingEngine> PcBench for KzgPcBench<E> {
     type Setup = Setup<UniversalParams<E>>;
-    type Trimmed = (Powers<E>, VerifierKey<E>);
+    type Trimmed = (Powers<'static, E>, VerifierKey<E>);
     type Poly = DensePolynomial<E::Fr>;
     type Point = E::Fr;
     type Eval = E::Fr;
     type Commit = Commitment<E>;
     type Proof = Proof<E>;
+    // Dominated by the MSM committing to the witness
+    const OPEN_COMPLEXITY: &'static str = "O(n)";
+    const PROOF_SIZE_CLASS: &'static str = "O(1)";
     fn setup(max_degree: usize) -> Self::Setup {
+        let mut rng = bench_rng_for(RngTag::Setup);
         Setup {
-            params: <KZG10<E, Self::Poly>>::setup(max_degree, &mut test_rng())
-                .expect("Setup works"),
-            rng: test_rng(),
+            params: <KZG10<E, Self::Poly>>::setup(max_degree, &mut rng).expect("Setup works"),
+            rng,
         }
     }
 
@@ -55,6 +86,10 @@ impl<E: PairingEngine> PcBench for KzgPcBench<E> {
         E::Fr::one().serialized_size() - 1
     }
 
+    fn proof_size(proof: &Self::Proof) -> usize {
+        proof.serialized_size()
+    }
+
     fn commit(t: &Self::Trimmed, _s: &mut Self::Setup, p: &Self::Poly) -> Self::Commit {
         <KZG10<E, Self::Poly>>::commit(&t.0, &p).expect("Commit failed")
     }
@@ -78,3 +113,55 @@ impl<E: PairingEngine> PcBench for KzgPcBench<E> {
         <KZG10<E, Self::Poly>>::check(&t.1, &c, *pt, *value, proof).expect("Check failed")
     }
 }
+
+impl<E: PairingEngine> HomomorphicPcBench for KzgPcBench<E> {
+    fn add_commitments(a: &Self::Commit, b: &Self::Commit) -> Self::Commit {
+        let mut sum = a.clone();
+        sum += (E::Fr::one(), b);
+        sum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_works, PcBench};
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+
+    #[test]
+    fn test_bw6_761_kzg() {
+        test_works::<KzgBw6_761Bench>();
+    }
+
+    // The BW6-761 scalar field is the 377 bit base field of BLS12-377
+    #[test]
+    fn test_bw6_761_ser_size() {
+        assert_eq!(KzgBw6_761Bench::bytes_per_elem(), 47);
+    }
+
+    #[test]
+    fn rand_poly_with_roots_works() {
+        type B = KzgBls12_381Bench;
+        type Fr = ark_bls12_381::Fr;
+        let degree = 32;
+        let mut s = B::setup(degree);
+        let t = B::trim(&s, degree);
+        let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        let roots: Vec<Fr> = [0, 3, 5, 15].iter().map(|&i| domain.element(i)).collect();
+
+        let p = B::rand_poly_with_roots(&mut s, degree, &roots);
+        assert_eq!(p.degree(), degree);
+        assert!(roots.iter().all(|r| p.evaluate(r).is_zero()));
+
+        let c = B::commit(&t, &mut s, &p);
+        for pt in roots.iter().copied().chain([Fr::rand(&mut s.rng)]) {
+            let proof = B::open(&t, &mut s, &p, &pt);
+            assert!(B::verify(&t, &c, &proof, &p.evaluate(&pt), &pt));
+        }
+
+        // As many roots as the degree leaves only a random constant factor
+        let q = B::rand_poly_with_roots(&mut s, roots.len(), &roots);
+        assert_eq!(q.degree(), roots.len());
+        assert!(roots.iter().all(|r| q.evaluate(r).is_zero()));
+    }
+}
@@ -1,13 +1,17 @@
-use ark_poly_04::univariate::DensePolynomial;
-use ark_std_04::UniformRand;
+use ark_poly_04::{univariate::DensePolynomial, DenseUVPolynomial};
+use ark_std_04::{One, UniformRand, Zero};
 use std::usize;
 
 use ark_ec_04::{pairing::Pairing, CurveGroup};
+use ark_serialize_04::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
 use ark_std_04::rand::RngCore;
 
 use super::{
-    gen_curve_powers, gen_powers, lagrange_interp, linear_combination, poly_div_q_r,
-    vanishing_polynomial, Error,
+    div_by_linear, gen_curve_powers, gen_powers, lagrange_interp, linear_combination,
+    poly_div_q_r, vanishing_polynomial, BuildableSetup, Error, SetupBuilder,
 };
 
 pub struct Setup<E: Pairing> {
@@ -20,7 +24,94 @@ pub struct Commitment<E: Pairing>(E::G1Affine);
 #[derive(Debug)]
 pub struct Proof<E: Pairing>(E::G1Affine);
 
+/// One claim for [`Setup::verify_batch`], with the same arguments as [`Setup::verify`].
+pub struct Instance<'a, E: Pairing> {
+    pub commits: &'a [Commitment<E>],
+    pub points: &'a [E::ScalarField],
+    pub evals: &'a [Vec<E::ScalarField>],
+    pub proof: &'a Proof<E>,
+    pub challenge: E::ScalarField,
+}
+
+impl<E: Pairing> Proof<E> {
+    /// Size of the compressed proof in bytes
+    pub fn size_in_bytes(&self) -> usize {
+        self.0.compressed_size()
+    }
+}
+
+// The `ark_serialize` derives expand to paths into the 0.3 crate, so these are by hand
+impl<E: Pairing> CanonicalSerialize for Commitment<E> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.0.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.0.serialized_size(compress)
+    }
+}
+
+impl<E: Pairing> Valid for Commitment<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.0.check()
+    }
+}
+
+impl<E: Pairing> CanonicalDeserialize for Commitment<E> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        E::G1Affine::deserialize_with_mode(reader, compress, validate).map(Commitment)
+    }
+}
+
+impl<E: Pairing> CanonicalSerialize for Proof<E> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.0.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.0.serialized_size(compress)
+    }
+}
+
+impl<E: Pairing> Valid for Proof<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.0.check()
+    }
+}
+
+impl<E: Pairing> CanonicalDeserialize for Proof<E> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        E::G1Affine::deserialize_with_mode(reader, compress, validate).map(Proof)
+    }
+}
+
+impl<E: Pairing> BuildableSetup for Setup<E> {
+    fn from_params(max_degree: usize, max_eval_points: usize, rng: &mut impl RngCore) -> Self {
+        Setup::new(max_degree, max_eval_points, rng)
+    }
+}
+
 impl<E: Pairing> Setup<E> {
+    pub fn builder() -> SetupBuilder<Self> {
+        SetupBuilder::default()
+    }
+
     pub fn new(max_degree: usize, max_pts: usize, rng: &mut impl RngCore) -> Setup<E> {
         let num_scalars = max_degree + 1;
 
@@ -56,6 +147,53 @@ impl<E: Pairing> Setup<E> {
         Ok(Proof(self.commit(q)?.0))
     }
 
+    /// Same as [`Self::open`], but folds each polynomial into the batched polynomial as
+    /// it arrives, so only one of them has to be held in memory at a time.
+    pub fn open_streaming(
+        &self,
+        polys: impl IntoIterator<Item = Vec<E::ScalarField>>,
+        points: &[E::ScalarField],
+        challenge: E::ScalarField,
+    ) -> Result<Proof<E>, Error> {
+        let mut fsum: Vec<E::ScalarField> = Vec::new();
+        let mut gamma = E::ScalarField::one();
+        let mut num_polys = 0;
+        for poly in polys {
+            if fsum.len() < poly.len() {
+                fsum.resize(poly.len(), E::ScalarField::zero());
+            }
+            for (f, c) in fsum.iter_mut().zip(&poly) {
+                *f += gamma * c;
+            }
+            gamma *= challenge;
+            num_polys += 1;
+        }
+        if num_polys == 0 {
+            return Err(Error::NoPolynomialsGiven);
+        }
+
+        let z_s = vanishing_polynomial(points);
+        let fsum = DensePolynomial::from_coefficients_vec(fsum);
+        let (q, _) = poly_div_q_r(fsum.into(), z_s.into())?;
+        Ok(Proof(self.commit(q)?.0))
+    }
+
+    /// Same as [`Self::open`] at the single point `point`. The vanishing polynomial is
+    /// just `x - point`, so the combined polynomial is divided synthetically.
+    pub fn open_single_point(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point: E::ScalarField,
+        challenge: E::ScalarField,
+    ) -> Result<Proof<E>, Error> {
+        let gammas = gen_powers::<E::ScalarField>(challenge, self.powers_of_g1.len());
+        let fsum = linear_combination::<E::ScalarField>(polys, &gammas)
+            .ok_or(Error::NoPolynomialsGiven)?;
+
+        let (q, _) = div_by_linear(&fsum, point);
+        Ok(Proof(self.commit(q)?.0))
+    }
+
     pub fn verify(
         &self,
         commits: &[Commitment<E>],
@@ -64,6 +202,51 @@ impl<E: Pairing> Setup<E> {
         proof: &Proof<E>,
         challenge: E::ScalarField,
     ) -> Result<bool, Error> {
+        let (lhs, zeros) = self.verify_terms(commits, pts, evals, challenge)?;
+        let g2 = self.powers_of_g2[0];
+
+        Ok(E::pairing(lhs, g2) == E::pairing(proof.0, zeros))
+    }
+
+    /// Checks all of `instances` at once. Each check `e(lhs, g2) == e(proof, Z_S(x) g2)`
+    /// is scaled by a random 128-bit coefficient and they are summed, so all the `g2`
+    /// pairings share one Miller loop and there is one final exponentiation in total.
+    pub fn verify_batch<R: RngCore>(
+        &self,
+        instances: &[Instance<E>],
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        let mut total_lhs = E::G1::zero();
+        let mut g1s = Vec::with_capacity(instances.len() + 1);
+        let mut g2s = Vec::with_capacity(instances.len() + 1);
+        let mut randomizer = E::ScalarField::one();
+        for instance in instances {
+            let (lhs, zeros) = self.verify_terms(
+                instance.commits,
+                instance.points,
+                instance.evals,
+                instance.challenge,
+            )?;
+            total_lhs += lhs * randomizer;
+            g1s.push(-(instance.proof.0 * randomizer));
+            g2s.push(zeros);
+            randomizer = u128::rand(rng).into();
+        }
+        g1s.push(total_lhs);
+        g2s.push(self.powers_of_g2[0].into());
+
+        Ok(E::multi_pairing(g1s, g2s).is_zero())
+    }
+
+    // The two sides of the check: the combined commitments minus the combined
+    // interpolating polynomials in G1, and the vanishing polynomial in G2
+    fn verify_terms(
+        &self,
+        commits: &[Commitment<E>],
+        pts: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        challenge: E::ScalarField,
+    ) -> Result<(E::G1, E::G2), Error> {
         let zeros = vanishing_polynomial(pts);
         let zeros = super::curve_msm::<E::G2>(&self.powers_of_g2, &zeros)?;
 
@@ -81,37 +264,155 @@ impl<E: Pairing> Setup<E> {
         let cms = commits.iter().map(|i| i.0).collect::<Vec<_>>();
         let gamma_cm_pt = super::curve_msm::<E::G1>(&cms, gammas.as_ref())?;
 
-        let g2 = self.powers_of_g2[0];
-
-        Ok(E::pairing(gamma_cm_pt - gamma_ris_pt, g2) == E::pairing(proof.0, zeros))
+        Ok((gamma_cm_pt - gamma_ris_pt, zeros))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use ark_bls12_381_04::{Bls12_381, Fr};
-    use ark_poly_04::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_poly_04::{univariate::DensePolynomial, DenseUVPolynomial};
+    use ark_serialize_04::{CanonicalDeserialize, CanonicalSerialize, Compress};
     use ark_std_04::UniformRand;
-    use crate::test_rng;
-    use super::Setup;
+    use rand::RngCore;
+    use crate::{ark::kzg_multiproof::test_fixture::Fixture, bench_rng, test_rng};
+    use super::{Commitment, Error, Instance, Proof, Setup};
+
+    /// `n_polys` random polynomials of `degree`, opened together at `n_points` points
+    fn opened(
+        s: &Setup<Bls12_381>,
+        n_points: usize,
+        n_polys: usize,
+        degree: usize,
+        rng: &mut impl RngCore,
+    ) -> (Fixture<Commitment<Bls12_381>>, Fr, Proof<Bls12_381>) {
+        let f = Fixture::new(n_points, n_polys, degree, rng, |p| s.commit(p));
+        let challenge = Fr::rand(rng);
+        let proof = s.open(&f.coeffs, &f.points, challenge).expect("Open failed");
+        (f, challenge, proof)
+    }
 
     #[test]
     fn test_basic_open_works() {
         let s = Setup::<Bls12_381>::new(256, 32, &mut test_rng());
-        let points = (0..30)
-            .map(|_| Fr::rand(&mut test_rng()))
-            .collect::<Vec<_>>();
-        let polys = (0..20)
-            .map(|_| DensePolynomial::<Fr>::rand(50, &mut test_rng()))
+        let (f, challenge, open) = opened(&s, 30, 20, 50, &mut test_rng());
+        assert_eq!(Ok(true), s.verify(&f.commits, &f.points, &f.evals, &open, challenge));
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let rng = &mut test_rng();
+        let s = Setup::<Bls12_381>::new(32, 4, rng);
+        let (f, challenge, proof) = opened(&s, 4, 3, 32, rng);
+
+        let mut bytes = Vec::new();
+        f.commits.serialize_compressed(&mut bytes).unwrap();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            bytes.len(),
+            f.commits.compressed_size() + proof.serialized_size(Compress::Yes)
+        );
+        let mut reader = &bytes[..];
+        let commits = Vec::<Commitment<Bls12_381>>::deserialize_compressed(&mut reader).unwrap();
+        let proof = Proof::<Bls12_381>::deserialize_compressed(&mut reader).unwrap();
+        assert!(reader.is_empty());
+        assert_eq!(Ok(true), s.verify(&commits, &f.points, &f.evals, &proof, challenge));
+    }
+
+    #[test]
+    fn test_open_single_point_matches_open() {
+        let s = Setup::<Bls12_381>::new(64, 4, &mut test_rng());
+        let (f, challenge, general) = opened(&s, 1, 10, 50, &mut test_rng());
+
+        let single = s.open_single_point(&f.coeffs, f.points[0], challenge).unwrap();
+        assert_eq!(single.0, general.0);
+        assert_eq!(Ok(true), s.verify(&f.commits, &f.points, &f.evals, &single, challenge));
+    }
+
+    #[test]
+    fn test_open_streaming_matches_open() {
+        let s = Setup::<Bls12_381>::new(64, 8, &mut test_rng());
+        let points = (0..8).map(|_| Fr::rand(&mut test_rng())).collect::<Vec<_>>();
+        let coeffs = (0..16)
+            .map(|i| DensePolynomial::<Fr>::rand(20 + i, &mut test_rng()).coeffs)
             .collect::<Vec<_>>();
-        let evals: Vec<Vec<_>> = polys
+        let challenge = Fr::rand(&mut test_rng());
+
+        let streamed = s
+            .open_streaming(coeffs.iter().cloned(), &points, challenge)
+            .unwrap();
+        let batched = s.open(&coeffs, &points, challenge).unwrap();
+        assert_eq!(streamed.0, batched.0);
+        assert_eq!(
+            s.open_streaming(std::iter::empty(), &points, challenge).err(),
+            Some(Error::NoPolynomialsGiven)
+        );
+    }
+
+    type Claim = (Vec<Commitment<Bls12_381>>, Vec<Fr>, Vec<Vec<Fr>>, Proof<Bls12_381>, Fr);
+
+    fn instances(claims: &[Claim]) -> Vec<Instance<'_, Bls12_381>> {
+        claims
             .iter()
-            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .map(|(commits, points, evals, proof, challenge)| Instance {
+                commits,
+                points,
+                evals,
+                proof,
+                challenge: *challenge,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        let rng = &mut test_rng();
+        let s = Setup::<Bls12_381>::new(32, 4, rng);
+        let claims: Vec<Claim> = (0..10)
+            .map(|i| {
+                let (f, challenge, proof) = opened(&s, 1 + i % 4, 3, 32, rng);
+                (f.commits, f.points, f.evals, proof, challenge)
+            })
             .collect();
-        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
-        let commits = coeffs.iter().map(|p| s.commit(p).expect("Commit failed")).collect::<Vec<_>>();
-        let challenge = Fr::rand(&mut test_rng());
-        let open = s.open(&coeffs, &points, challenge).expect("Open failed");
-        assert_eq!(Ok(true), s.verify(&commits, &points, &evals, &open, challenge));
+        assert_eq!(Ok(true), s.verify_batch(&instances(&claims), rng));
+
+        let mut bad = claims;
+        bad[6].2[1][0] += Fr::from(1u64);
+        assert_eq!(Ok(false), s.verify_batch(&instances(&bad), rng));
+    }
+
+    #[test]
+    fn test_builder_matches_new() {
+        let built = Setup::<Bls12_381>::builder()
+            .max_degree(64)
+            .max_eval_points(8)
+            .build(&mut bench_rng(1))
+            .unwrap();
+        let new = Setup::<Bls12_381>::new(64, 8, &mut bench_rng(1));
+        assert_eq!(built.powers_of_g1, new.powers_of_g1);
+        assert_eq!(built.powers_of_g2, new.powers_of_g2);
+        assert_eq!(built.powers_of_g2.len(), 9);
+
+        let default_points = Setup::<Bls12_381>::builder()
+            .max_degree(64)
+            .build(&mut test_rng())
+            .unwrap();
+        assert_eq!(default_points.powers_of_g2.len(), 2);
+
+        assert_eq!(
+            Setup::<Bls12_381>::builder().build(&mut test_rng()).err(),
+            Some(Error::MaxDegreeNotSet)
+        );
+        assert_eq!(
+            Setup::<Bls12_381>::builder()
+                .max_degree(8)
+                .max_eval_points(64)
+                .build(&mut test_rng())
+                .err(),
+            Some(Error::TooManyEvalPoints {
+                max_eval_points: 64,
+                max_degree: 8
+            })
+        );
     }
 }
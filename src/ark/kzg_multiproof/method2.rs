@@ -6,11 +6,15 @@ use std::{
 };
 
 use ark_ec_04::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_serialize_04::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
 use ark_std_04::rand::RngCore;
 
 use super::{
     gen_curve_powers, gen_powers, lagrange_interp, linear_combination, poly_div_q_r,
-    vanishing_polynomial, Error,
+    vanishing_polynomial, BuildableSetup, Error, SetupBuilder,
 };
 
 pub struct Setup<E: Pairing> {
@@ -23,7 +27,89 @@ pub struct Commitment<E: Pairing>(E::G1Affine);
 #[derive(Debug)]
 pub struct Proof<E: Pairing>(E::G1Affine, E::G1Affine);
 
+impl<E: Pairing> Proof<E> {
+    /// Size of the compressed proof in bytes
+    pub fn size_in_bytes(&self) -> usize {
+        self.0.compressed_size() + self.1.compressed_size()
+    }
+}
+
+// By hand for the same reason as in `method1`
+impl<E: Pairing> CanonicalSerialize for Commitment<E> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.0.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.0.serialized_size(compress)
+    }
+}
+
+impl<E: Pairing> Valid for Commitment<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.0.check()
+    }
+}
+
+impl<E: Pairing> CanonicalDeserialize for Commitment<E> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        E::G1Affine::deserialize_with_mode(reader, compress, validate).map(Commitment)
+    }
+}
+
+impl<E: Pairing> CanonicalSerialize for Proof<E> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.0.serialize_with_mode(&mut writer, compress)?;
+        self.1.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.0.serialized_size(compress) + self.1.serialized_size(compress)
+    }
+}
+
+impl<E: Pairing> Valid for Proof<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.0.check()?;
+        self.1.check()
+    }
+}
+
+impl<E: Pairing> CanonicalDeserialize for Proof<E> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let w_1 = E::G1Affine::deserialize_with_mode(&mut reader, compress, validate)?;
+        let w_2 = E::G1Affine::deserialize_with_mode(reader, compress, validate)?;
+        Ok(Proof(w_1, w_2))
+    }
+}
+
+impl<E: Pairing> BuildableSetup for Setup<E> {
+    fn from_params(max_degree: usize, max_eval_points: usize, rng: &mut impl RngCore) -> Self {
+        Setup::new(max_degree, max_eval_points, rng)
+    }
+}
+
 impl<E: Pairing> Setup<E> {
+    pub fn builder() -> SetupBuilder<Self> {
+        SetupBuilder::default()
+    }
+
     pub fn new(max_degree: usize, max_pts: usize, rng: &mut impl RngCore) -> Setup<E> {
         let num_scalars = max_degree + 1;
 
@@ -115,38 +201,59 @@ impl<E: Pairing> Setup<E> {
 
 #[cfg(test)]
 mod tests {
-    use super::Setup;
-    use crate::test_rng;
+    use super::{Commitment, Proof, Setup};
+    use crate::{ark::kzg_multiproof::test_fixture::Fixture, test_rng};
     use ark_bls12_381_04::{Bls12_381, Fr};
-    use ark_poly_04::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_serialize_04::{CanonicalDeserialize, CanonicalSerialize, Compress};
     use ark_std_04::UniformRand;
+    use rand::RngCore;
+
+    /// `n_polys` random polynomials of `degree`, opened together at `n_points` points
+    fn opened(
+        s: &Setup<Bls12_381>,
+        n_points: usize,
+        n_polys: usize,
+        degree: usize,
+        rng: &mut impl RngCore,
+    ) -> (Fixture<Commitment<Bls12_381>>, (Fr, Fr), Proof<Bls12_381>) {
+        let f = Fixture::new(n_points, n_polys, degree, rng, |p| s.commit(p));
+        let (gamma, chal_z) = (Fr::rand(rng), Fr::rand(rng));
+        let proof = s
+            .open(&f.coeffs, &f.points, gamma, chal_z)
+            .expect("Open failed");
+        (f, (gamma, chal_z), proof)
+    }
 
     #[test]
     fn test_basic_open_works() {
         let s = Setup::<Bls12_381>::new(256, 32, &mut test_rng());
-        let points = (0..30)
-            .map(|_| Fr::rand(&mut test_rng()))
-            .collect::<Vec<_>>();
-        let polys = (0..20)
-            .map(|_| DensePolynomial::<Fr>::rand(50, &mut test_rng()))
-            .collect::<Vec<_>>();
-        let evals: Vec<Vec<_>> = polys
-            .iter()
-            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
-            .collect();
-        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
-        let commits = coeffs
-            .iter()
-            .map(|p| s.commit(p).expect("Commit failed"))
-            .collect::<Vec<_>>();
-        let challenge1 = Fr::rand(&mut test_rng());
-        let challenge2 = Fr::rand(&mut test_rng());
-        let open = s
-            .open(&coeffs, &points, challenge1, challenge2)
-            .expect("Open failed");
+        let (f, (challenge1, challenge2), open) = opened(&s, 30, 20, 50, &mut test_rng());
+        assert_eq!(
+            Ok(true),
+            s.verify(&f.commits, &f.points, &f.evals, &open, challenge1, challenge2)
+        );
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let rng = &mut test_rng();
+        let s = Setup::<Bls12_381>::new(32, 4, rng);
+        let (f, (gamma, chal_z), proof) = opened(&s, 4, 3, 32, rng);
+
+        let mut bytes = Vec::new();
+        f.commits.serialize_uncompressed(&mut bytes).unwrap();
+        proof.serialize_uncompressed(&mut bytes).unwrap();
+        assert_eq!(
+            bytes.len(),
+            f.commits.uncompressed_size() + proof.serialized_size(Compress::No)
+        );
+        let mut reader = &bytes[..];
+        let commits = Vec::<Commitment<Bls12_381>>::deserialize_uncompressed(&mut reader).unwrap();
+        let proof = Proof::<Bls12_381>::deserialize_uncompressed(&mut reader).unwrap();
+        assert!(reader.is_empty());
         assert_eq!(
             Ok(true),
-            s.verify(&commits, &points, &evals, &open, challenge1, challenge2)
+            s.verify(&commits, &f.points, &f.evals, &proof, gamma, chal_z)
         );
     }
 }
@@ -6,6 +6,7 @@ use ark_poly_04::{
 };
 use ark_std_04::ops::{Add, Mul};
 use rand::RngCore;
+use std::marker::PhantomData;
 
 pub mod method1;
 pub mod method2;
@@ -18,6 +19,68 @@ pub enum Error {
     },
     DivisorIsZero,
     NoPolynomialsGiven,
+    MaxDegreeNotSet,
+    TooManyEvalPoints {
+        max_eval_points: usize,
+        max_degree: usize,
+    },
+}
+
+/// A setup that [`SetupBuilder`] can construct.
+pub trait BuildableSetup: Sized {
+    fn from_params(max_degree: usize, max_eval_points: usize, rng: &mut impl RngCore) -> Self;
+}
+
+/// Builds a [`method1::Setup`] or [`method2::Setup`] from named parameters, so the
+/// degree and number of points can't be swapped by accident.
+///
+/// ```ignore
+/// let s = method1::Setup::<Bls12_381>::builder()
+///     .max_degree(256)
+///     .max_eval_points(32)
+///     .build(&mut rng)?;
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SetupBuilder<S> {
+    max_degree: Option<usize>,
+    max_eval_points: usize,
+    _setup: PhantomData<S>,
+}
+
+impl<S: BuildableSetup> Default for SetupBuilder<S> {
+    fn default() -> Self {
+        SetupBuilder {
+            max_degree: None,
+            max_eval_points: 1,
+            _setup: PhantomData,
+        }
+    }
+}
+
+impl<S: BuildableSetup> SetupBuilder<S> {
+    /// Required
+    pub fn max_degree(mut self, max_degree: usize) -> Self {
+        self.max_degree = Some(max_degree);
+        self
+    }
+
+    /// The most points a proof can open at. Defaults to 1.
+    pub fn max_eval_points(mut self, max_eval_points: usize) -> Self {
+        self.max_eval_points = max_eval_points;
+        self
+    }
+
+    pub fn build(self, rng: &mut impl RngCore) -> Result<S, Error> {
+        let max_degree = self.max_degree.ok_or(Error::MaxDegreeNotSet)?;
+        // The vanishing polynomial of `k` points needs `k + 1` powers in G2
+        if self.max_eval_points > max_degree {
+            return Err(Error::TooManyEvalPoints {
+                max_eval_points: self.max_eval_points,
+                max_degree,
+            });
+        }
+        Ok(S::from_params(max_degree, self.max_eval_points, rng))
+    }
 }
 
 pub(crate) fn gen_powers<F: Field>(element: F, len: usize) -> Vec<F> {
@@ -65,6 +128,19 @@ pub(crate) fn poly_div_q_r<F: Field>(
     Ok((q.coeffs, r.coeffs))
 }
 
+/// Divides by `x - point` with synthetic division, returning q, r
+pub(crate) fn div_by_linear<F: Field>(coeffs: &[F], point: F) -> (Vec<F>, F) {
+    let mut q = vec![F::zero(); coeffs.len().saturating_sub(1)];
+    let mut r = F::zero();
+    for (i, &c) in coeffs.iter().enumerate().rev() {
+        if i < q.len() {
+            q[i] = r;
+        }
+        r = c + point * r;
+    }
+    (q, r)
+}
+
 pub(crate) fn linear_combination<F: Field>(
     polynomials: &[impl AsRef<[F]>],
     challenges: &[F],
@@ -161,3 +237,52 @@ pub(crate) fn lagrange_interp<F: FftField>(
     let polys = gen_lagrange_polynomials(points);
     do_lagrange_interpolation(evals, points, &inverses, &polys)
 }
+
+#[cfg(test)]
+pub(crate) mod test_fixture {
+    use ark_bls12_381_04::Fr;
+    use ark_poly_04::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std_04::UniformRand;
+    use rand::RngCore;
+
+    use super::Error;
+
+    /// Random polynomials, their evaluations at random points and their commitments,
+    /// shared by the tests of both methods
+    pub(crate) struct Fixture<C> {
+        pub points: Vec<Fr>,
+        pub evals: Vec<Vec<Fr>>,
+        pub coeffs: Vec<Vec<Fr>>,
+        pub commits: Vec<C>,
+    }
+
+    impl<C> Fixture<C> {
+        pub fn new(
+            n_points: usize,
+            n_polys: usize,
+            degree: usize,
+            rng: &mut impl RngCore,
+            commit: impl Fn(&[Fr]) -> Result<C, Error>,
+        ) -> Self {
+            let points: Vec<Fr> = (0..n_points).map(|_| Fr::rand(rng)).collect();
+            let polys: Vec<_> = (0..n_polys)
+                .map(|_| DensePolynomial::<Fr>::rand(degree, rng))
+                .collect();
+            let evals = polys
+                .iter()
+                .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+                .collect();
+            let coeffs: Vec<Vec<Fr>> = polys.into_iter().map(|p| p.coeffs).collect();
+            let commits = coeffs
+                .iter()
+                .map(|p| commit(p).expect("Commit failed"))
+                .collect();
+            Fixture {
+                points,
+                evals,
+                coeffs,
+                commits,
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 
-use crate::test_rng;
+use crate::{bench_rng_for, RngTag};
 use ark_ec_04::pairing::Pairing;
 use ark_ff_04::One;
 use ark_poly_04::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
@@ -23,17 +23,20 @@ impl<E: Pairing, const N_PTS: usize, const N_POLY: usize> PcBench
     type Eval = Vec<Vec<E::ScalarField>>;
     type Commit = Vec<method1::Commitment<E>>;
     type Proof = (method1::Proof<E>, E::ScalarField);
+    // Dividing by the vanishing polynomial of the `t = N_PTS` points costs `O(n t)`
+    const OPEN_COMPLEXITY: &'static str = "O(n t)";
+    const PROOF_SIZE_CLASS: &'static str = "O(1)";
 
     fn setup(_max_degree: usize) -> Self::Setup {
         ()
     }
 
     fn trim(_: &Self::Setup, supported_degree: usize) -> Self::Trimmed {
-        method1::Setup::<E>::new(supported_degree, N_PTS, &mut test_rng())
+        method1::Setup::<E>::new(supported_degree, N_PTS, &mut bench_rng_for(RngTag::Setup))
     }
 
     fn rand_poly(_: &mut Self::Setup, d: usize) -> (Self::Poly, Self::Point, Self::Eval) {
-        let mut rng = test_rng();
+        let mut rng = bench_rng_for(RngTag::Inputs);
         let polys = (0..N_POLY)
             .map(|_| DensePolynomial::<E::ScalarField>::rand(d, &mut rng))
             .collect::<Vec<_>>();
@@ -54,6 +57,11 @@ impl<E: Pairing, const N_PTS: usize, const N_POLY: usize> PcBench
         (E::ScalarField::one().serialized_size(Compress::Yes) - 1) * N_PTS * N_POLY
     }
 
+    // The challenge isn't part of the proof
+    fn proof_size(proof: &Self::Proof) -> usize {
+        proof.0.size_in_bytes()
+    }
+
     fn commit(t: &Self::Trimmed, _: &mut Self::Setup, p: &Self::Poly) -> Self::Commit {
         p.iter().map(|pi| t.commit(pi).unwrap()).collect()
     }
@@ -66,7 +74,7 @@ impl<E: Pairing, const N_PTS: usize, const N_POLY: usize> PcBench
     ) -> Self::Proof {
         let refs: Vec<&Vec<E::ScalarField>> =
             p.iter().map(|poly: &Vec<E::ScalarField>| poly).collect();
-        let chal = E::ScalarField::rand(&mut test_rng());
+        let chal = E::ScalarField::rand(&mut bench_rng_for(RngTag::Challenge));
         (t.open(refs.as_ref(), pt, chal).unwrap(), chal)
     }
 
@@ -93,17 +101,20 @@ impl<E: Pairing, const N_PTS: usize, const N_POLY: usize> PcBench
     type Eval = Vec<Vec<E::ScalarField>>;
     type Commit = Vec<method2::Commitment<E>>;
     type Proof = (method2::Proof<E>, E::ScalarField, E::ScalarField);
+    // Dividing by the vanishing polynomial of the `t = N_PTS` points costs `O(n t)`
+    const OPEN_COMPLEXITY: &'static str = "O(n t)";
+    const PROOF_SIZE_CLASS: &'static str = "O(1)";
 
     fn setup(_max_degree: usize) -> Self::Setup {
         ()
     }
 
     fn trim(_: &Self::Setup, supported_degree: usize) -> Self::Trimmed {
-        method2::Setup::<E>::new(supported_degree, N_PTS, &mut test_rng())
+        method2::Setup::<E>::new(supported_degree, N_PTS, &mut bench_rng_for(RngTag::Setup))
     }
 
     fn rand_poly(_: &mut Self::Setup, d: usize) -> (Self::Poly, Self::Point, Self::Eval) {
-        let mut rng = test_rng();
+        let mut rng = bench_rng_for(RngTag::Inputs);
         let polys = (0..N_POLY)
             .map(|_| DensePolynomial::<E::ScalarField>::rand(d, &mut rng))
             .collect::<Vec<_>>();
@@ -124,6 +135,11 @@ impl<E: Pairing, const N_PTS: usize, const N_POLY: usize> PcBench
         (E::ScalarField::one().serialized_size(Compress::Yes) - 1) * N_PTS * N_POLY
     }
 
+    // The challenge isn't part of the proof
+    fn proof_size(proof: &Self::Proof) -> usize {
+        proof.0.size_in_bytes()
+    }
+
     fn commit(t: &Self::Trimmed, _: &mut Self::Setup, p: &Self::Poly) -> Self::Commit {
         p.iter().map(|pi| t.commit(pi).unwrap()).collect()
     }
@@ -136,8 +152,9 @@ impl<E: Pairing, const N_PTS: usize, const N_POLY: usize> PcBench
     ) -> Self::Proof {
         let refs: Vec<&Vec<E::ScalarField>> =
             p.iter().map(|poly: &Vec<E::ScalarField>| poly).collect();
-        let chal1 = E::ScalarField::rand(&mut test_rng());
-        let chal2 = E::ScalarField::rand(&mut test_rng());
+        let mut rng = bench_rng_for(RngTag::Challenge);
+        let chal1 = E::ScalarField::rand(&mut rng);
+        let chal2 = E::ScalarField::rand(&mut rng);
         (t.open(refs.as_ref(), pt, chal1, chal2).unwrap(), chal1, chal2)
     }
 
@@ -0,0 +1,274 @@
+//! A Ligero style polynomial commitment: no trusted setup and no pairings, only
+//! Reed-Solomon codes and SHA-256.
+//!
+//! The `n` coefficients are laid out row by row in a `num_rows x num_cols` matrix `M`
+//! with `num_cols` about `sqrt(n)`, so `p(z) = b^T M a` for `b_i = z^{i num_cols}` and
+//! `a_j = z^j`. Each row is Reed-Solomon encoded with rate `1 / rho_inv`, and the
+//! commitment is the Merkle root over the columns of the encoded matrix.
+//!
+//! To open at `z`, the prover sends `u = b^T M`, from which `p(z) = u^T a`, along with
+//! `v = r^T M` for a random `r`. The verifier then checks a random subset of encoded
+//! columns against the Merkle root and against the encodings of `u` and `v`. The
+//! check against `v` is the proximity test that the committed rows are codewords.
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+
+use super::challenge::{ChallengeGen, Sha256ChallengeGen, LIGERO_COLUMN_DST, LIGERO_ROW_DST};
+use super::merkle::{hash_leaf, Hash, MerkleTree};
+
+#[derive(Debug, Clone)]
+pub struct Ligero<F: PrimeField> {
+    num_rows: usize,
+    num_cols: usize,
+    num_openings: usize,
+    domain: Radix2EvaluationDomain<F>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commitment {
+    pub root: Hash,
+}
+
+#[derive(Debug, Clone)]
+pub struct Proof<F: PrimeField> {
+    /// `r^T M` for the proximity test
+    pub well_formed: Vec<F>,
+    /// `b^T M` for the evaluation point
+    pub eval_combination: Vec<F>,
+    /// The opened columns of the encoded matrix, in the order they were sampled
+    pub columns: Vec<Vec<F>>,
+    pub paths: Vec<Vec<Hash>>,
+}
+
+impl<F: PrimeField> Proof<F> {
+    /// Size of the serialized proof in bytes
+    pub fn size_in_bytes(&self) -> usize {
+        let num_elems = self.well_formed.len()
+            + self.eval_combination.len()
+            + self.columns.iter().map(Vec::len).sum::<usize>();
+        let num_hashes: usize = self.paths.iter().map(Vec::len).sum();
+        num_elems * F::one().serialized_size() + num_hashes * std::mem::size_of::<Hash>()
+    }
+}
+
+impl<F: PrimeField> Ligero<F> {
+    /// Parameters for polynomials up to degree `max_degree`, with rows encoded at rate
+    /// `1 / rho_inv`, opening enough columns for `security_bits` bits of soundness.
+    pub fn new(max_degree: usize, rho_inv: usize, security_bits: usize) -> Self {
+        let n = max_degree + 1;
+        let num_cols = ((n as f64).sqrt().ceil() as usize).next_power_of_two();
+        let num_rows = n.div_ceil(num_cols);
+        let domain = Radix2EvaluationDomain::new(rho_inv * num_cols)
+            .expect("Failed to make encoding domain");
+        // Each opened column catches a matrix that is far from the code with
+        // probability at least `delta`, the unique decoding radius over three
+        let delta = (1.0 - 1.0 / rho_inv as f64) / 3.0;
+        let num_openings = (security_bits as f64 / -(1.0 - delta).log2()).ceil() as usize;
+        Ligero {
+            num_rows,
+            num_cols,
+            num_openings,
+            domain,
+        }
+    }
+
+    pub fn num_openings(&self) -> usize {
+        self.num_openings
+    }
+
+    pub fn commit(&self, coeffs: &[F]) -> Commitment {
+        let (_, tree) = self.encode(coeffs);
+        Commitment { root: tree.root() }
+    }
+
+    /// Opens `coeffs` at `point`. The encoded matrix is rebuilt from `coeffs`, so this
+    /// costs as much as a commitment on top of the opening itself.
+    pub fn open(&self, coeffs: &[F], point: F) -> Proof<F> {
+        let (encoded, tree) = self.encode(coeffs);
+        let root = tree.root();
+        let rows = self.rows(coeffs);
+
+        let r = row_challenges(&root, self.num_rows);
+        let b = self.row_powers(point);
+        let well_formed = combine_rows(&rows, &r);
+        let eval_combination = combine_rows(&rows, &b);
+
+        let indices = self.column_indices(&root, point, &well_formed, &eval_combination);
+        let columns = indices
+            .iter()
+            .map(|&c| encoded.iter().map(|row| row[c]).collect())
+            .collect();
+        let paths = indices.iter().map(|&c| tree.open(c)).collect();
+        Proof {
+            well_formed,
+            eval_combination,
+            columns,
+            paths,
+        }
+    }
+
+    pub fn verify(&self, comm: &Commitment, point: F, value: F, proof: &Proof<F>) -> bool {
+        if proof.well_formed.len() != self.num_cols
+            || proof.eval_combination.len() != self.num_cols
+            || proof.columns.len() != self.num_openings
+            || proof.paths.len() != self.num_openings
+            || proof.columns.iter().any(|c| c.len() != self.num_rows)
+        {
+            return false;
+        }
+
+        let claimed: F = proof
+            .eval_combination
+            .iter()
+            .rev()
+            .fold(F::zero(), |acc, c| acc * point + c);
+        if claimed != value {
+            return false;
+        }
+
+        let r = row_challenges(&comm.root, self.num_rows);
+        let b = self.row_powers(point);
+        let encoded_well_formed = self.domain.fft(&proof.well_formed);
+        let encoded_eval = self.domain.fft(&proof.eval_combination);
+        let indices = self.column_indices(
+            &comm.root,
+            point,
+            &proof.well_formed,
+            &proof.eval_combination,
+        );
+
+        indices
+            .iter()
+            .zip(proof.columns.iter())
+            .zip(proof.paths.iter())
+            .all(|((&c, column), path)| {
+                MerkleTree::verify(&comm.root, &hash_column(column), c, path)
+                    && dot(&r, column) == encoded_well_formed[c]
+                    && dot(&b, column) == encoded_eval[c]
+            })
+    }
+
+    fn rows(&self, coeffs: &[F]) -> Vec<Vec<F>> {
+        assert!(
+            coeffs.len() <= self.num_rows * self.num_cols,
+            "Polynomial is too large for these parameters"
+        );
+        (0..self.num_rows)
+            .map(|i| {
+                let mut row = vec![F::zero(); self.num_cols];
+                let start = (i * self.num_cols).min(coeffs.len());
+                let end = ((i + 1) * self.num_cols).min(coeffs.len());
+                row[..end - start].copy_from_slice(&coeffs[start..end]);
+                row
+            })
+            .collect()
+    }
+
+    fn encode(&self, coeffs: &[F]) -> (Vec<Vec<F>>, MerkleTree) {
+        let encoded: Vec<Vec<F>> = self
+            .rows(coeffs)
+            .iter()
+            .map(|row| self.domain.fft(row))
+            .collect();
+        let leaves = (0..self.domain.size())
+            .map(|c| hash_column(&encoded.iter().map(|row| row[c]).collect::<Vec<_>>()))
+            .collect();
+        (encoded, MerkleTree::new(leaves))
+    }
+
+    // `b_i = z^{i num_cols}`
+    fn row_powers(&self, point: F) -> Vec<F> {
+        let step = point.pow([self.num_cols as u64]);
+        let mut powers = vec![F::one(); self.num_rows];
+        for i in 1..self.num_rows {
+            powers[i] = powers[i - 1] * step;
+        }
+        powers
+    }
+
+    fn column_indices(&self, root: &Hash, point: F, well_formed: &[F], eval: &[F]) -> Vec<usize> {
+        let mut transcript = root.to_vec();
+        transcript.extend(serialize(&[point]));
+        transcript.extend(serialize(well_formed));
+        transcript.extend(serialize(eval));
+        (0..self.num_openings)
+            .map(|i| {
+                let msg = indexed(&transcript, i);
+                let c: F = Sha256ChallengeGen::hash_to_field(LIGERO_COLUMN_DST, &msg);
+                // The domain size is a power of two, so this is uniform
+                (c.into_repr().as_ref()[0] % self.domain.size() as u64) as usize
+            })
+            .collect()
+    }
+}
+
+fn indexed(msg: &[u8], i: usize) -> Vec<u8> {
+    let mut msg = msg.to_vec();
+    msg.extend_from_slice(&(i as u64).to_le_bytes());
+    msg
+}
+
+// The proximity test coefficients only depend on the commitment
+fn row_challenges<F: PrimeField>(root: &Hash, num_rows: usize) -> Vec<F> {
+    (0..num_rows)
+        .map(|i| Sha256ChallengeGen::hash_to_field(LIGERO_ROW_DST, &indexed(root, i)))
+        .collect()
+}
+
+fn combine_rows<F: PrimeField>(rows: &[Vec<F>], coeffs: &[F]) -> Vec<F> {
+    let mut out = vec![F::zero(); rows[0].len()];
+    for (row, c) in rows.iter().zip(coeffs) {
+        for (o, x) in out.iter_mut().zip(row) {
+            *o += *c * x;
+        }
+    }
+    out
+}
+
+fn dot<F: PrimeField>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b).map(|(x, y)| *x * y).sum()
+}
+
+fn serialize<F: PrimeField>(elems: &[F]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for e in elems {
+        e.serialize(&mut bytes).expect("Serializing to a Vec can't fail");
+    }
+    bytes
+}
+
+fn hash_column<F: PrimeField>(column: &[F]) -> Hash {
+    hash_leaf(&serialize(column))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng;
+    use ark_bls12_381::Fr;
+    use ark_ff::{One, UniformRand};
+    use ark_poly::{univariate::DensePolynomial, Polynomial, UVPolynomial};
+
+    #[test]
+    fn test_open_verify() {
+        let rng = &mut test_rng();
+        let degree = 256;
+        let ligero = Ligero::<Fr>::new(degree, 4, 128);
+        let p = DensePolynomial::<Fr>::rand(degree, rng);
+        let comm = ligero.commit(&p.coeffs);
+        let point = Fr::rand(rng);
+        let value = p.evaluate(&point);
+        let proof = ligero.open(&p.coeffs, point);
+        assert!(ligero.verify(&comm, point, value, &proof));
+        assert!(!ligero.verify(&comm, point, value + Fr::one(), &proof));
+
+        let mut bad_column = proof.clone();
+        bad_column.columns[0][0] += Fr::one();
+        assert!(!ligero.verify(&comm, point, value, &bad_column));
+
+        // Opening a different polynomial against the same commitment
+        let q = DensePolynomial::<Fr>::rand(degree, rng);
+        let q_proof = ligero.open(&q.coeffs, point);
+        assert!(!ligero.verify(&comm, point, q.evaluate(&point), &q_proof));
+    }
+}
@@ -0,0 +1,93 @@
+use std::marker::PhantomData;
+
+use ark_ff::PrimeField;
+use ark_poly::{univariate::DensePolynomial, Polynomial, UVPolynomial};
+use rand::rngs::StdRng;
+
+use crate::{bench_rng_for, PcBench, RngTag};
+
+use super::ligero::{Commitment, Ligero, Proof};
+
+pub type LigeroBls12_381Bench = LigeroBench<ark_bls12_381::Fr>;
+pub type LigeroBn254Bench = LigeroBench<ark_bn254::Fr>;
+
+// Rows are encoded at rate 1/4
+const RHO_INV: usize = 4;
+const SECURITY_BITS: usize = 128;
+
+pub struct LigeroBench<F>(PhantomData<F>);
+
+impl<F: PrimeField> PcBench for LigeroBench<F> {
+    // There's no trusted setup, only randomness for the polynomials
+    type Setup = StdRng;
+    type Trimmed = Ligero<F>;
+    type Poly = DensePolynomial<F>;
+    type Point = F;
+    type Eval = F;
+    type Commit = Commitment;
+    type Proof = Proof<F>;
+    // Opening re-encodes the coefficient matrix
+    const OPEN_COMPLEXITY: &'static str = "O(n log n)";
+    const PROOF_SIZE_CLASS: &'static str = "O(sqrt(n))";
+
+    fn setup(_max_degree: usize) -> Self::Setup {
+        bench_rng_for(RngTag::Setup)
+    }
+
+    fn trim(_: &Self::Setup, supported_degree: usize) -> Self::Trimmed {
+        Ligero::new(supported_degree, RHO_INV, SECURITY_BITS)
+    }
+
+    fn rand_poly(s: &mut Self::Setup, d: usize) -> (Self::Poly, Self::Point, Self::Eval) {
+        let poly = Self::Poly::rand(d, s);
+        let pt = F::rand(s);
+        let eval = poly.evaluate(&pt);
+        (poly, pt, eval)
+    }
+
+    fn bytes_per_elem() -> usize {
+        F::one().serialized_size() - 1
+    }
+
+    fn proof_size(proof: &Self::Proof) -> usize {
+        proof.size_in_bytes()
+    }
+
+    fn commit(t: &Self::Trimmed, _: &mut Self::Setup, p: &Self::Poly) -> Self::Commit {
+        t.commit(&p.coeffs)
+    }
+
+    fn open(
+        t: &Self::Trimmed,
+        _: &mut Self::Setup,
+        p: &Self::Poly,
+        pt: &Self::Point,
+    ) -> Self::Proof {
+        t.open(&p.coeffs, *pt)
+    }
+
+    fn verify(
+        t: &Self::Trimmed,
+        c: &Self::Commit,
+        proof: &Self::Proof,
+        value: &Self::Eval,
+        pt: &Self::Point,
+    ) -> bool {
+        t.verify(c, *pt, *value, proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_works;
+
+    #[test]
+    fn bls12_381_works() {
+        test_works::<super::LigeroBls12_381Bench>();
+    }
+
+    #[test]
+    fn bn254_works() {
+        test_works::<super::LigeroBn254Bench>();
+    }
+}
@@ -1,15 +1,22 @@
 use ark_bls12_381::Bls12_381;
 use ark_bn254::Bn254;
+use ark_bw6_761::BW6_761;
 use ark_ec::PairingEngine;
 use ark_poly_commit::marlin_pc::MarlinKZG10;
 
-use super::pc_impl::{ArkPcBench, Poly};
+use super::pc_impl::{ArkPcBench, ArkScheme, Poly};
 
 type PolyOf<E> = Poly<<E as PairingEngine>::Fr>;
 type MarlinBenchFor<E> = ArkPcBench<<E as PairingEngine>::Fr, MarlinKZG10<E, PolyOf<E>>>;
 
+impl<E: PairingEngine> ArkScheme for MarlinKZG10<E, PolyOf<E>> {
+    const OPEN_COMPLEXITY: &'static str = "O(n)";
+    const PROOF_SIZE_CLASS: &'static str = "O(1)";
+}
+
 pub type MarlinBls12_381Bench = MarlinBenchFor<Bls12_381>;
 pub type MarlinBn254Bench = MarlinBenchFor<Bn254>;
+pub type MarlinBw6_761Bench = MarlinBenchFor<BW6_761>;
 
 #[cfg(test)]
 mod tests {
@@ -26,6 +33,28 @@ mod tests {
         test_works::<MarlinBn254Bench>();
     }
 
+    #[test]
+    fn test_bw6_761_marlin() {
+        test_works::<MarlinBw6_761Bench>();
+    }
+
+    #[test]
+    fn test_fixed_opening_challenge() {
+        use ark_serialize::CanonicalSerialize;
+
+        let mut s = MarlinBls12_381Bench::setup(64);
+        let t = MarlinBls12_381Bench::trim(&s, 64);
+        let (poly, point, _) = MarlinBls12_381Bench::rand_poly(&mut s, 64);
+        let (proof_a, chal_a) = MarlinBls12_381Bench::open(&t, &mut s, &poly, &point);
+        let (proof_b, chal_b) = MarlinBls12_381Bench::open(&t, &mut s, &poly, &point);
+        assert_eq!(chal_a, chal_b);
+
+        let (mut bytes_a, mut bytes_b) = (Vec::new(), Vec::new());
+        proof_a.serialize(&mut bytes_a).unwrap();
+        proof_b.serialize(&mut bytes_b).unwrap();
+        assert_eq!(bytes_a, bytes_b);
+    }
+
     #[test]
     fn test_bls12_381_ser_size() {
         assert_eq!(MarlinBls12_381Bench::bytes_per_elem(), 31);
@@ -35,4 +64,9 @@ mod tests {
     fn test_bn254_ser_size() {
         assert_eq!(MarlinBn254Bench::bytes_per_elem(), 31);
     }
+
+    #[test]
+    fn test_bw6_761_ser_size() {
+        assert_eq!(MarlinBw6_761Bench::bytes_per_elem(), 47);
+    }
 }
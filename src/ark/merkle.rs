@@ -0,0 +1,97 @@
+//! A binary SHA-256 Merkle tree over a power of two number of leaves.
+use sha2::{Digest, Sha256};
+
+pub type Hash = [u8; 32];
+
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    // Heap layout: the root is at 1 and the children of `i` are at `2i` and `2i + 1`,
+    // so the leaves are the second half
+    nodes: Vec<Hash>,
+}
+
+pub fn hash_leaf(data: &[u8]) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([0u8]);
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+fn hash_node(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([1u8]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+impl MerkleTree {
+    /// Builds the tree over already hashed `leaves`, see [`hash_leaf`].
+    pub fn new(leaves: Vec<Hash>) -> Self {
+        let n = leaves.len();
+        assert!(n.is_power_of_two(), "Number of leaves must be a power of two");
+        let mut nodes = vec![[0u8; 32]; n];
+        nodes.extend(leaves);
+        for i in (1..n).rev() {
+            nodes[i] = hash_node(&nodes[2 * i], &nodes[2 * i + 1]);
+        }
+        MerkleTree { nodes }
+    }
+
+    pub fn num_leaves(&self) -> usize {
+        self.nodes.len() / 2
+    }
+
+    pub fn root(&self) -> Hash {
+        // With a single leaf this is the leaf itself
+        self.nodes[1]
+    }
+
+    /// The sibling hashes from leaf `index` up to the root.
+    pub fn open(&self, index: usize) -> Vec<Hash> {
+        let mut i = self.num_leaves() + index;
+        let mut path = Vec::new();
+        while i > 1 {
+            path.push(self.nodes[i ^ 1]);
+            i /= 2;
+        }
+        path
+    }
+
+    /// Checks that `leaf` is at `index` in the tree with root `root`.
+    pub fn verify(root: &Hash, leaf: &Hash, index: usize, path: &[Hash]) -> bool {
+        if index >> path.len() != 0 {
+            return false;
+        }
+        let mut i = index;
+        let mut cur = *leaf;
+        for sibling in path {
+            cur = if i & 1 == 0 {
+                hash_node(&cur, sibling)
+            } else {
+                hash_node(sibling, &cur)
+            };
+            i /= 2;
+        }
+        cur == *root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_verify() {
+        let leaves: Vec<_> = (0u8..16).map(|i| hash_leaf(&[i])).collect();
+        let tree = MerkleTree::new(leaves.clone());
+        let root = tree.root();
+        for (i, leaf) in leaves.iter().enumerate() {
+            let path = tree.open(i);
+            assert_eq!(path.len(), 4);
+            assert!(MerkleTree::verify(&root, leaf, i, &path));
+            assert!(!MerkleTree::verify(&root, leaf, i ^ 1, &path));
+            assert!(!MerkleTree::verify(&root, &hash_leaf(&[99]), i, &path));
+        }
+    }
+}
@@ -1,9 +1,20 @@
 pub mod marlin_bench;
 pub mod kzg_bench;
+pub mod challenge;
 pub mod enc_bench;
+pub mod fri;
+pub mod fri_bench;
 pub mod kzg;
 pub mod pc_impl;
 pub mod grid_bench;
+pub mod ipa_bench;
+pub mod ligero;
+pub mod ligero_bench;
+pub mod merkle;
+pub mod packing;
+pub mod pedersen;
+pub mod pedersen_bench;
+pub mod representation;
 
 pub mod kzg_multiproof;
 pub mod kzg_multiproof_bench;
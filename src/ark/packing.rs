@@ -0,0 +1,98 @@
+//! Packing byte data into field elements to commit to it, several bytes per element.
+//!
+//! A field element holds any `bytes_per_elem` little-endian bytes, one byte less than
+//! its serialized size so the integer stays below the modulus. This is the same
+//! capacity the benches count data in, see [`crate::PcBench::bytes_per_elem`].
+//! Fewer bytes per element can be packed with the `_with_width` variants.
+use ark_ff::{BigInteger, PrimeField};
+
+/// The number of bytes packed into each element of `F` by default.
+pub fn bytes_per_elem<F: PrimeField>() -> usize {
+    F::zero().serialized_size() - 1
+}
+
+/// Packs `bytes` into field elements of [`bytes_per_elem`] bytes each, zero padding
+/// the last one.
+pub fn pack_bytes_to_field<F: PrimeField>(bytes: &[u8]) -> Vec<F> {
+    pack_bytes_to_field_with_width(bytes, bytes_per_elem::<F>())
+}
+
+/// Inverse of [`pack_bytes_to_field`], `num_bytes` being the length of the packed data.
+pub fn unpack_bytes_from_field<F: PrimeField>(elems: &[F], num_bytes: usize) -> Vec<u8> {
+    unpack_bytes_from_field_with_width(elems, num_bytes, bytes_per_elem::<F>())
+}
+
+/// Same as [`pack_bytes_to_field`], with `width` bytes per element.
+pub fn pack_bytes_to_field_with_width<F: PrimeField>(bytes: &[u8], width: usize) -> Vec<F> {
+    check_width::<F>(width);
+    bytes
+        .chunks(width)
+        .map(F::from_le_bytes_mod_order)
+        .collect()
+}
+
+/// Same as [`unpack_bytes_from_field`], with `width` bytes per element.
+pub fn unpack_bytes_from_field_with_width<F: PrimeField>(
+    elems: &[F],
+    num_bytes: usize,
+    width: usize,
+) -> Vec<u8> {
+    check_width::<F>(width);
+    assert!(
+        num_bytes <= elems.len() * width,
+        "{} elements can't hold {} bytes",
+        elems.len(),
+        num_bytes
+    );
+    let mut bytes: Vec<u8> = elems
+        .iter()
+        .flat_map(|e| e.into_repr().to_bytes_le().into_iter().take(width))
+        .collect();
+    bytes.truncate(num_bytes);
+    bytes
+}
+
+fn check_width<F: PrimeField>(width: usize) {
+    assert!(
+        0 < width && width <= bytes_per_elem::<F>(),
+        "Can't pack {} bytes in an element, at most {} fit",
+        width,
+        bytes_per_elem::<F>()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng;
+    use ark_bls12_381::Fr;
+    use rand::RngCore;
+
+    #[test]
+    fn test_round_trip() {
+        let rng = &mut test_rng();
+        assert_eq!(bytes_per_elem::<Fr>(), 31);
+        assert_eq!(bytes_per_elem::<ark_bn254::Fr>(), 31);
+        for num_bytes in [0, 1, 30, 31, 32, 62, 1000] {
+            let mut bytes = vec![0u8; num_bytes];
+            rng.fill_bytes(&mut bytes);
+            let elems = pack_bytes_to_field::<Fr>(&bytes);
+            assert_eq!(elems.len(), num_bytes.div_ceil(31));
+            assert_eq!(unpack_bytes_from_field(&elems, num_bytes), bytes);
+
+            let elems = pack_bytes_to_field_with_width::<ark_bn254::Fr>(&bytes, 8);
+            assert_eq!(elems.len(), num_bytes.div_ceil(8));
+            assert_eq!(unpack_bytes_from_field_with_width(&elems, num_bytes, 8), bytes);
+        }
+
+        // The largest packed value still fits below the modulus
+        let ones = vec![0xff; 31];
+        assert_eq!(unpack_bytes_from_field(&pack_bytes_to_field::<Fr>(&ones), 31), ones);
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 31 fit")]
+    fn test_width_too_large() {
+        pack_bytes_to_field_with_width::<Fr>(&[0; 64], 32);
+    }
+}
@@ -3,13 +3,16 @@ use std::marker::PhantomData;
 use ark_ff::Field;
 use ark_poly::{Polynomial, UVPolynomial, univariate::DensePolynomial};
 use ark_poly_commit::{LabeledPolynomial, PCRandomness, PolynomialCommitment, LabeledCommitment};
-use crate::TestRng;
+use crate::{bench_rng_for, RngTag};
+use rand::rngs::StdRng;
 
 use crate::PcBench;
 
-pub struct Setup<UniversalParams> {
+pub struct Setup<F, UniversalParams> {
     params: UniversalParams,
-    rng: TestRng,
+    rng: StdRng,
+    // Sampled once so every open in a bench run does the same work
+    opening_challenge: F,
 }
 
 pub type Poly<F> = DensePolynomial<F>;
@@ -19,22 +22,39 @@ pub type Trimmed<F, PC> = (
 );
 type Commitment<F, PC> = LabeledCommitment<<PC as PolynomialCommitment<F, Poly<F>>>::Commitment>;
 
+/// The asymptotics reported for an ark_poly_commit scheme, see [`PcBench`]
+pub trait ArkScheme {
+    const OPEN_COMPLEXITY: &'static str;
+    const PROOF_SIZE_CLASS: &'static str;
+}
+
 pub struct ArkPcBench<F: Field, PC: PolynomialCommitment<F, Poly<F>>>(PhantomData<(F, PC)>);
 
-impl<F: Field, PC: PolynomialCommitment<F, Poly<F>>> PcBench for ArkPcBench<F, PC> {
-    type Setup = Setup<PC::UniversalParams>;
+impl<F, PC> PcBench for ArkPcBench<F, PC>
+where
+    F: Field,
+    PC: PolynomialCommitment<F, Poly<F>> + ArkScheme,
+{
+    type Setup = Setup<F, PC::UniversalParams>;
     type Trimmed = Trimmed<F, PC>;
     type Poly = Poly<F>;
     type Point = F;
     type Eval = F;
     type Commit = Commitment<F, PC>;
     type Proof = (PC::Proof, Self::Point);
+    const OPEN_COMPLEXITY: &'static str = PC::OPEN_COMPLEXITY;
+    const PROOF_SIZE_CLASS: &'static str = PC::PROOF_SIZE_CLASS;
 
     fn setup(max_degree: usize) -> Self::Setup {
-        let mut rng = crate::test_rng();
+        let mut rng = bench_rng_for(RngTag::Setup);
         let params = PC::setup(max_degree, None, &mut rng).expect("Failed to init bls kzg");
+        let opening_challenge = F::rand(&mut rng);
 
-        Setup { params, rng }
+        Setup {
+            params,
+            rng,
+            opening_challenge,
+        }
     }
 
     fn trim(s: &Self::Setup, supported_degree: usize) -> Self::Trimmed {
@@ -52,6 +72,11 @@ impl<F: Field, PC: PolynomialCommitment<F, Poly<F>>> PcBench for ArkPcBench<F, P
         F::one().serialized_size() - 1 // Trim one byte for keeping in modspace
     }
 
+    // The opening challenge isn't part of the proof
+    fn proof_size(proof: &Self::Proof) -> usize {
+        proof.0.serialized_size()
+    }
+
     fn commit(t: &Self::Trimmed, _s: &mut Self::Setup, p: &Self::Poly) -> Self::Commit {
         let lp = LabeledPolynomial::new("Test".to_string(), p.clone(), None, None);
         let res = PC::commit(&t.0, &[lp], None).expect("Failed to commit");
@@ -65,7 +90,7 @@ impl<F: Field, PC: PolynomialCommitment<F, Poly<F>>> PcBench for ArkPcBench<F, P
         pt: &Self::Point,
     ) -> Self::Proof {
         let lp = LabeledPolynomial::new("Test".to_string(), p.clone(), None, None);
-        let opening_challenge = Self::Point::rand(&mut s.rng);
+        let opening_challenge = s.opening_challenge;
 
         (
             PC::open(
@@ -0,0 +1,168 @@
+//! Pedersen commitments to polynomial coefficients over any curve, meant for an
+//! embedded curve such as Jubjub so that commitments are cheap to check inside a
+//! SNARK over the outer curve's scalar field.
+//!
+//! The commitment to `p` is `C = \sum_i p_i G_i` for random bases `G_i`. An opening
+//! `p(z) = v` shows that the discrete logs of `C` in the bases `G_i` are also the
+//! coefficients that evaluate to `v` at `z`, i.e. with `a = (1, z, z^2, ...)` that the
+//! same vector `p` satisfies `<p, G> = C` and `<p, a> = v`. The prover sends
+//! `A = <k, G>` and `t = <k, a>` for a random vector `k`, and answers the Fiat-Shamir
+//! challenge `c` with `s = k + c p`. The verifier checks `<s, G> = A + c C` and
+//! `<s, a> = t + c v`. The proof is linear in the degree.
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, ProjectiveCurve};
+use ark_ff::{PrimeField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use rand::RngCore;
+
+use super::challenge::{ChallengeGen, Sha256ChallengeGen, PEDERSEN_OPENING_DST};
+
+#[derive(Debug, Clone)]
+pub struct Pedersen<G: ProjectiveCurve> {
+    bases: Vec<G::Affine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Commitment<G: ProjectiveCurve>(pub G::Affine);
+
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<G: ProjectiveCurve> {
+    /// `A = <k, G>`
+    pub nonce_commit: G::Affine,
+    /// `t = <k, a>`
+    pub nonce_eval: G::ScalarField,
+    /// `s = k + c p`
+    pub response: Vec<G::ScalarField>,
+}
+
+impl<G: ProjectiveCurve> Pedersen<G> {
+    /// Random bases for polynomials up to degree `max_degree`
+    pub fn new<R: RngCore>(max_degree: usize, rng: &mut R) -> Self {
+        let bases = (0..=max_degree).map(|_| G::rand(rng)).collect::<Vec<_>>();
+        Pedersen {
+            bases: G::batch_normalization_into_affine(&bases),
+        }
+    }
+
+    /// Keeps the bases for polynomials up to degree `supported_degree`
+    pub fn trim(&self, supported_degree: usize) -> Self {
+        assert!(
+            supported_degree < self.bases.len(),
+            "Supported degree is larger than the setup"
+        );
+        Pedersen {
+            bases: self.bases[..=supported_degree].to_vec(),
+        }
+    }
+
+    pub fn commit(&self, coeffs: &[G::ScalarField]) -> Commitment<G> {
+        Commitment(self.msm(coeffs).into_affine())
+    }
+
+    pub fn open<R: RngCore>(
+        &self,
+        coeffs: &[G::ScalarField],
+        point: G::ScalarField,
+        rng: &mut R,
+    ) -> Proof<G> {
+        let nonces: Vec<_> = (0..coeffs.len())
+            .map(|_| G::ScalarField::rand(rng))
+            .collect();
+        let nonce_commit = self.msm(&nonces).into_affine();
+        let nonce_eval = evaluate(&nonces, point);
+
+        let comm = self.commit(coeffs);
+        let value = evaluate(coeffs, point);
+        let c = challenge(&comm, point, value, &nonce_commit, nonce_eval);
+        let response = nonces
+            .iter()
+            .zip(coeffs)
+            .map(|(k, p)| *k + c * p)
+            .collect();
+        Proof {
+            nonce_commit,
+            nonce_eval,
+            response,
+        }
+    }
+
+    pub fn verify(
+        &self,
+        comm: &Commitment<G>,
+        point: G::ScalarField,
+        value: G::ScalarField,
+        proof: &Proof<G>,
+    ) -> bool {
+        if proof.response.len() > self.bases.len() {
+            return false;
+        }
+        let c = challenge(comm, point, value, &proof.nonce_commit, proof.nonce_eval);
+        evaluate(&proof.response, point) == proof.nonce_eval + c * value
+            && self.msm(&proof.response)
+                == proof.nonce_commit.into_projective() + comm.0.mul(c.into_repr())
+    }
+
+    fn msm(&self, scalars: &[G::ScalarField]) -> G {
+        assert!(
+            scalars.len() <= self.bases.len(),
+            "Polynomial is too large for these bases"
+        );
+        let scalars: Vec<_> = scalars.iter().map(|s| s.into_repr()).collect();
+        VariableBaseMSM::multi_scalar_mul(&self.bases[..scalars.len()], &scalars)
+    }
+}
+
+fn evaluate<F: PrimeField>(coeffs: &[F], point: F) -> F {
+    coeffs
+        .iter()
+        .rev()
+        .fold(F::zero(), |acc, coeff| acc * point + coeff)
+}
+
+fn challenge<G: ProjectiveCurve>(
+    comm: &Commitment<G>,
+    point: G::ScalarField,
+    value: G::ScalarField,
+    nonce_commit: &G::Affine,
+    nonce_eval: G::ScalarField,
+) -> G::ScalarField {
+    let mut bytes = Vec::new();
+    comm.serialize(&mut bytes)
+        .and_then(|_| point.serialize(&mut bytes))
+        .and_then(|_| value.serialize(&mut bytes))
+        .and_then(|_| nonce_commit.serialize(&mut bytes))
+        .and_then(|_| nonce_eval.serialize(&mut bytes))
+        .expect("Serializing to a Vec can't fail");
+    Sha256ChallengeGen::hash_to_field(PEDERSEN_OPENING_DST, &bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng;
+    use ark_ed_on_bls12_381::{EdwardsProjective, Fr};
+    use ark_ff::One;
+
+    #[test]
+    fn test_open_verify() {
+        let rng = &mut test_rng();
+        let degree = 64;
+        let pedersen = Pedersen::<EdwardsProjective>::new(degree, rng);
+        let coeffs: Vec<_> = (0..=degree).map(|_| Fr::rand(rng)).collect();
+        let comm = pedersen.commit(&coeffs);
+        let point = Fr::rand(rng);
+        let value = evaluate(&coeffs, point);
+        let proof = pedersen.open(&coeffs, point, rng);
+        assert!(pedersen.verify(&comm, point, value, &proof));
+        assert!(!pedersen.verify(&comm, point, value + Fr::one(), &proof));
+        assert!(!pedersen.verify(&comm, point + Fr::one(), value, &proof));
+
+        // Opening a different polynomial against the same commitment
+        let other: Vec<_> = (0..=degree).map(|_| Fr::rand(rng)).collect();
+        let other_proof = pedersen.open(&other, point, rng);
+        assert!(!pedersen.verify(&comm, point, evaluate(&other, point), &other_proof));
+
+        let mut bad_response = proof.clone();
+        bad_response.response[0] += Fr::one();
+        assert!(!pedersen.verify(&comm, point, value, &bad_response));
+    }
+}
@@ -0,0 +1,124 @@
+use std::marker::PhantomData;
+
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ed_on_bls12_381::EdwardsProjective;
+use ark_ff::{One, UniformRand};
+use ark_poly::{univariate::DensePolynomial, Polynomial, UVPolynomial};
+use ark_serialize::CanonicalSerialize;
+use rand::rngs::StdRng;
+
+use crate::{bench_rng_for, HomomorphicPcBench, PcBench, RngTag};
+
+use super::pedersen::{Commitment, Pedersen, Proof};
+
+/// Commitments over Jubjub, whose base field is the BLS12-381 scalar field, so they
+/// can be opened inside a circuit proven over BLS12-381
+pub type InCircuitCommitBench = PedersenBench<EdwardsProjective>;
+
+pub struct Setup<G: ProjectiveCurve> {
+    params: Pedersen<G>,
+    rng: StdRng,
+}
+
+pub struct PedersenBench<G>(PhantomData<G>);
+
+impl<G: ProjectiveCurve> PcBench for PedersenBench<G> {
+    type Setup = Setup<G>;
+    type Trimmed = Pedersen<G>;
+    type Poly = DensePolynomial<G::ScalarField>;
+    type Point = G::ScalarField;
+    type Eval = G::ScalarField;
+    type Commit = Commitment<G>;
+    type Proof = Proof<G>;
+    // Dominated by the MSM committing to the nonces
+    const OPEN_COMPLEXITY: &'static str = "O(n)";
+    // One response per coefficient
+    const PROOF_SIZE_CLASS: &'static str = "O(n)";
+
+    fn setup(max_degree: usize) -> Self::Setup {
+        let mut rng = bench_rng_for(RngTag::Setup);
+        Setup {
+            params: Pedersen::new(max_degree, &mut rng),
+            rng,
+        }
+    }
+
+    fn trim(s: &Self::Setup, supported_degree: usize) -> Self::Trimmed {
+        s.params.trim(supported_degree)
+    }
+
+    fn rand_poly(s: &mut Self::Setup, d: usize) -> (Self::Poly, Self::Point, Self::Eval) {
+        let poly = Self::Poly::rand(d, &mut s.rng);
+        let pt = G::ScalarField::rand(&mut s.rng);
+        let eval = poly.evaluate(&pt);
+        (poly, pt, eval)
+    }
+
+    fn bytes_per_elem() -> usize {
+        G::ScalarField::one().serialized_size() - 1
+    }
+
+    fn proof_size(proof: &Self::Proof) -> usize {
+        proof.serialized_size()
+    }
+
+    fn commit(t: &Self::Trimmed, _: &mut Self::Setup, p: &Self::Poly) -> Self::Commit {
+        t.commit(&p.coeffs)
+    }
+
+    fn open(
+        t: &Self::Trimmed,
+        s: &mut Self::Setup,
+        p: &Self::Poly,
+        pt: &Self::Point,
+    ) -> Self::Proof {
+        t.open(&p.coeffs, *pt, &mut s.rng)
+    }
+
+    fn verify(
+        t: &Self::Trimmed,
+        c: &Self::Commit,
+        proof: &Self::Proof,
+        value: &Self::Eval,
+        pt: &Self::Point,
+    ) -> bool {
+        t.verify(c, *pt, *value, proof)
+    }
+}
+
+impl<G: ProjectiveCurve> HomomorphicPcBench for PedersenBench<G> {
+    fn add_commitments(a: &Self::Commit, b: &Self::Commit) -> Self::Commit {
+        Commitment((a.0.into_projective() + b.0.into_projective()).into_affine())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_works;
+    use ark_ed_on_bls12_381::Fr;
+
+    #[test]
+    fn in_circuit_works() {
+        test_works::<InCircuitCommitBench>();
+    }
+
+    #[test]
+    fn in_circuit_opening() {
+        type B = InCircuitCommitBench;
+        let degree = 32;
+        let mut s = B::setup(2 * degree);
+        let t = B::trim(&s, degree);
+        let (poly, point, value) = B::rand_poly(&mut s, degree);
+        let c = B::commit(&t, &mut s, &poly);
+
+        // Openings are randomized by the nonces, and each one verifies
+        let proof = B::open(&t, &mut s, &poly, &point);
+        let other = B::open(&t, &mut s, &poly, &point);
+        assert_ne!(proof, other);
+        assert!(B::verify(&t, &c, &proof, &value, &point));
+        assert!(B::verify(&t, &c, &other, &value, &point));
+        assert!(!B::verify(&t, &c, &proof, &(value + Fr::one()), &point));
+        assert_eq!(proof.response.len(), degree + 1);
+    }
+}
@@ -0,0 +1,173 @@
+//! Conversions between the coefficient and evaluation representations of a polynomial
+//! over an FFT domain.
+//!
+//! `EvaluationDomain::fft` silently truncates inputs longer than the domain, and
+//! `ifft` of fewer evaluations than the domain size isn't an interpolation of them.
+//! These helpers check sizes up front and always zero-pad coefficients to the domain
+//! size, so every caller pads the same way. They work for any [`DomainCoeff`], so
+//! group elements such as commitments and openings are extended the same way too.
+//!
+//! [`DomainPoint`] marks opening points that are domain elements, which the grid and
+//! encoding code relies on, apart from the arbitrary points KZG can open at.
+use ark_ff::FftField;
+use ark_poly::{domain::DomainCoeff, EvaluationDomain};
+
+/// The element `\omega^j` of an evaluation domain, along with its index `j`. It can
+/// only be made from a domain, so a raw field element can't be passed where a domain
+/// element is expected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DomainPoint<F> {
+    index: usize,
+    element: F,
+}
+
+impl<F: FftField> DomainPoint<F> {
+    /// The `index`-th element of `domain`. Unlike `EvaluationDomain::element`, this
+    /// doesn't wrap around past the domain size.
+    pub fn new<D: EvaluationDomain<F>>(domain: &D, index: usize) -> Self {
+        Self::try_new(domain, index).unwrap_or_else(|| {
+            panic!(
+                "Index {} is outside a domain of size {}",
+                index,
+                domain.size()
+            )
+        })
+    }
+
+    /// Same as [`Self::new`], but `None` past the domain size, for indices that come
+    /// from untrusted input.
+    pub fn try_new<D: EvaluationDomain<F>>(domain: &D, index: usize) -> Option<Self> {
+        (index < domain.size()).then(|| DomainPoint {
+            index,
+            element: domain.element(index),
+        })
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn element(&self) -> F {
+        self.element
+    }
+}
+
+/// Evaluations over `domain` of the polynomial with coefficients `coeffs`, padded
+/// with zero coefficients up to the domain size.
+pub fn coeffs_to_evals<F, T, D>(coeffs: &[T], domain: &D) -> Vec<T>
+where
+    F: FftField,
+    T: DomainCoeff<F>,
+    D: EvaluationDomain<F>,
+{
+    assert!(
+        coeffs.len() <= domain.size(),
+        "{} coefficients don't fit a domain of size {}",
+        coeffs.len(),
+        domain.size()
+    );
+    let mut evals = coeffs.to_vec();
+    evals.resize(domain.size(), T::zero());
+    domain.fft_in_place(&mut evals);
+    evals
+}
+
+/// Coefficients of the polynomial taking the values `evals` over `domain`, one per
+/// domain element.
+pub fn evals_to_coeffs<F, T, D>(evals: &[T], domain: &D) -> Vec<T>
+where
+    F: FftField,
+    T: DomainCoeff<F>,
+    D: EvaluationDomain<F>,
+{
+    assert_eq!(
+        evals.len(),
+        domain.size(),
+        "Need exactly one evaluation per domain element"
+    );
+    domain.ifft(evals)
+}
+
+/// Replaces the evaluations `evals` over `domain` by the evaluations of the same
+/// polynomial over the larger `big_domain`, i.e. Reed-Solomon encodes them.
+pub fn extend_evals_in_place<F, T, D>(evals: &mut Vec<T>, domain: &D, big_domain: &D)
+where
+    F: FftField,
+    T: DomainCoeff<F>,
+    D: EvaluationDomain<F>,
+{
+    assert_eq!(
+        evals.len(),
+        domain.size(),
+        "Need exactly one evaluation per domain element"
+    );
+    assert_eq!(
+        big_domain.size() % domain.size(),
+        0,
+        "The domain size must divide the larger domain size"
+    );
+    domain.ifft_in_place(evals);
+    evals.resize(big_domain.size(), T::zero());
+    big_domain.fft_in_place(evals);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng;
+    use ark_bls12_381::{Fr, G1Projective};
+    use ark_ff::{UniformRand, Zero};
+    use ark_poly::{univariate::DensePolynomial, Polynomial, Radix2EvaluationDomain, UVPolynomial};
+
+    #[test]
+    fn test_round_trip() {
+        let rng = &mut test_rng();
+        let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        let coeffs: Vec<_> = (0..16).map(|_| Fr::rand(rng)).collect();
+        let evals = coeffs_to_evals(&coeffs, &domain);
+        assert_eq!(evals_to_coeffs(&evals, &domain), coeffs);
+
+        let points: Vec<_> = (0..16).map(|_| G1Projective::rand(rng)).collect();
+        let encoded = coeffs_to_evals(&points, &domain);
+        assert_eq!(evals_to_coeffs(&encoded, &domain), points);
+    }
+
+    #[test]
+    fn test_round_trip_padded() {
+        let rng = &mut test_rng();
+        let domain = Radix2EvaluationDomain::<Fr>::new(32).unwrap();
+        let p = DensePolynomial::<Fr>::rand(9, rng);
+        let evals = coeffs_to_evals(&p.coeffs, &domain);
+        assert_eq!(evals.len(), 32);
+        for (x, y) in domain.elements().zip(&evals) {
+            assert_eq!(p.evaluate(&x), *y);
+        }
+
+        let coeffs = evals_to_coeffs(&evals, &domain);
+        assert_eq!(coeffs[..10], p.coeffs[..]);
+        assert!(coeffs[10..].iter().all(Zero::is_zero));
+    }
+
+    #[test]
+    #[should_panic(expected = "outside a domain")]
+    fn test_domain_point_in_range() {
+        let domain = Radix2EvaluationDomain::<Fr>::new(8).unwrap();
+        let point = DomainPoint::new(&domain, 5);
+        assert_eq!(point.index(), 5);
+        assert_eq!(point.element(), domain.element(5));
+        assert_eq!(DomainPoint::try_new(&domain, 5), Some(point));
+        assert_eq!(DomainPoint::try_new(&domain, 8), None);
+        DomainPoint::new(&domain, 8);
+    }
+
+    #[test]
+    fn test_extend_evals() {
+        let rng = &mut test_rng();
+        let domain = Radix2EvaluationDomain::<Fr>::new(8).unwrap();
+        let big_domain = Radix2EvaluationDomain::<Fr>::new(32).unwrap();
+        let p = DensePolynomial::<Fr>::rand(7, rng);
+        let mut evals = coeffs_to_evals(&p.coeffs, &domain);
+        extend_evals_in_place(&mut evals, &domain, &big_domain);
+        assert_eq!(evals, coeffs_to_evals(&p.coeffs, &big_domain));
+    }
+}
@@ -1,7 +1,45 @@
 pub mod ark;
 pub mod plonk_kzg;
+pub mod recovery;
+pub mod report;
+#[cfg(feature = "max-rss")]
+pub mod rss;
+#[cfg(test)]
 pub(crate) use rand::thread_rng as test_rng;
-pub(crate) use rand::rngs::ThreadRng as TestRng;
+
+use ark_ff::Field;
+use ark_poly::Polynomial;
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Seed used by every benchmark setup, so that each run draws the same parameters
+/// and inputs and results can be compared across runs and schemes.
+pub const BENCH_SEED: u64 = 0;
+
+/// The RNG used by all benchmark setups. The same `seed` always yields the same
+/// stream of draws.
+pub fn bench_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// What a benchmark draws randomness for. Each kind gets its own stream from
+/// [`bench_rng_for`], since drawing two kinds from one fresh stream correlates them,
+/// e.g. a KZG trapdoor equal to the first coefficient of the committed polynomial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RngTag {
+    // Trusted setups and other public parameters
+    Setup = 1,
+    // Polynomials, grids and points fed to the schemes
+    Inputs = 2,
+    // Challenges the benchmarks pick in place of a verifier
+    Challenge = 3,
+    // Columns and cells picked by a sampling client
+    Sample = 4,
+}
+
+/// [`bench_rng`] seeded with [`BENCH_SEED`] mixed with `tag`
+pub fn bench_rng_for(tag: RngTag) -> StdRng {
+    bench_rng(BENCH_SEED ^ tag as u64)
+}
 
 pub trait PcBench {
     type Setup;
@@ -11,11 +49,25 @@ pub trait PcBench {
     type Eval;
     type Commit;
     type Proof;
+    // Asymptotic cost of `open` in the degree `n`, e.g. "O(n)"
+    const OPEN_COMPLEXITY: &'static str;
+    // Asymptotic size of `Proof` in the degree `n`, e.g. "O(1)"
+    const PROOF_SIZE_CLASS: &'static str;
     fn setup(max_degree: usize) -> Self::Setup;
     fn trim(s: &Self::Setup, supported_degree: usize) -> Self::Trimmed;
     // Random (poly, z, poly(z))
     fn rand_poly(s: &mut Self::Setup, d: usize) -> (Self::Poly, Self::Point, Self::Eval);
+    // Same as `rand_poly`, keeping `poly(z)` around so it isn't evaluated again
+    fn rand_poly_with_cached_eval(
+        s: &mut Self::Setup,
+        d: usize,
+    ) -> PreEvaluated<Self::Poly, Self::Point, Self::Eval> {
+        let (poly, point, value) = Self::rand_poly(s, d);
+        PreEvaluated { poly, point, value }
+    }
     fn bytes_per_elem() -> usize;
+    // Size of the serialized proof in bytes, compressed where the curve allows it
+    fn proof_size(proof: &Self::Proof) -> usize;
     fn commit(t: &Self::Trimmed, s: &mut Self::Setup, p: &Self::Poly) -> Self::Commit;
     fn open(
         t: &Self::Trimmed,
@@ -30,6 +82,43 @@ pub trait PcBench {
         value: &Self::Eval,
         pt: &Self::Point,
     ) -> bool;
+    // Peak resident set size in bytes while opening `p` at `pt`. It includes whatever
+    // the process already holds, see `rss` for the platforms that can measure it
+    #[cfg(feature = "max-rss")]
+    fn measure_open_memory(
+        t: &Self::Trimmed,
+        s: &mut Self::Setup,
+        p: &Self::Poly,
+        pt: &Self::Point,
+    ) -> usize {
+        rss::reset_peak_rss();
+        std::hint::black_box(Self::open(t, s, p, pt));
+        rss::peak_rss().expect("Peak RSS isn't available on this platform")
+    }
+}
+
+/// A scheme whose commitments can be added, so that
+/// `commit(p) + commit(q) = commit(p + q)`
+pub trait HomomorphicPcBench: PcBench {
+    fn add_commitments(a: &Self::Commit, b: &Self::Commit) -> Self::Commit;
+}
+
+/// A polynomial along with its value at `point`, so evaluating it there again is free.
+#[derive(Clone, Debug)]
+pub struct PreEvaluated<P, Pt, E> {
+    pub poly: P,
+    pub point: Pt,
+    pub value: E,
+}
+
+impl<F: Field, P: Polynomial<F, Point = F>> PreEvaluated<P, F, F> {
+    pub fn evaluate(&self, pt: &F) -> F {
+        if *pt == self.point {
+            self.value
+        } else {
+            self.poly.evaluate(pt)
+        }
+    }
 }
 
 pub trait ErasureEncodeBench {
@@ -41,24 +130,109 @@ pub trait ErasureEncodeBench {
     fn erasure_encode(pts: &mut Vec<Self::Point>, sub_domain: &Self::Domain, big_domain: &Self::Domain);
 }
 
+pub trait ErasureDecodeBench: ErasureEncodeBench {
+    // Inverse of `erasure_encode`: `pts` holds the `big_domain.size()` encoded points,
+    // of which only those marked in `present` are known, and is replaced by the
+    // `sub_domain.size()` points they were encoded from
+    fn erasure_decode(
+        pts: &mut Vec<Self::Point>,
+        present: &[bool],
+        sub_domain: &Self::Domain,
+        big_domain: &Self::Domain,
+    ) -> Result<(), RecoveryError>;
+}
+
 pub trait GridBench {
     type Setup: Clone;
+    type Point: Clone;
     type Grid: Clone;
     type ExtendedGrid: Clone;
     type Commits;
     type Opens;
+    // Smallest SRS max degree that `do_setup` needs to commit to a `grid_size` grid
+    fn required_srs_degree(grid_size: usize) -> usize;
     fn do_setup(size: usize) -> Self::Setup;
     fn rand_grid(size: usize) -> Self::Grid;
     fn extend_grid(s: &Self::Setup, g: &Self::Grid) -> Self::ExtendedGrid;
+    // Reed-Solomon extends each row of `g` in place, replacing its `n` coefficients with
+    // its `2n` evaluations over the extended domain
+    fn extend_rows(s: &Self::Setup, g: &mut Self::ExtendedGrid);
+    // Extends both the columns and the rows of `g` into a `2n x 2n` grid, which holds
+    // the original cell `(i, j)` at `(2i, 2j)`. `make_commits` commits to all of its `2n`
+    // rows, with the same commitments as for `extend_grid`.
+    fn extend_grid_2d(s: &Self::Setup, g: &Self::Grid) -> Self::ExtendedGrid {
+        let mut eg = Self::extend_grid(s, g);
+        Self::extend_rows(s, &mut eg);
+        eg
+    }
     fn make_commits(s: &Self::Setup, g: &Self::ExtendedGrid) -> Self::Commits;
     fn open_column(s: &Self::Setup, g: &Self::ExtendedGrid) -> Self::Opens;
+    // Opens each of the given columns, in order
+    fn open_columns(s: &Self::Setup, g: &Self::ExtendedGrid, cols: &[usize]) -> Vec<Self::Opens>;
+    // Checks the openings of every extended row at column `col`
+    fn verify_column(
+        s: &Self::Setup,
+        commits: &Self::Commits,
+        opens: &Self::Opens,
+        g: &Self::ExtendedGrid,
+        col: usize,
+    ) -> bool;
+    // Recovers the whole extended grid from any half of its `2n` rows, `None` marking
+    // the missing ones, by interpolating each column over the present rows
+    fn reconstruct(
+        s: &Self::Setup,
+        partial: &[Option<Vec<Self::Point>>],
+    ) -> Result<Self::ExtendedGrid, RecoveryError>;
     fn bytes_per_elem() -> usize;
 }
 
+/// Commitments to a square matrix whose rows are polynomials in coefficient form,
+/// with cell `(i, j)` being row `i` evaluated at the `j`-th point of a size `2n`
+/// domain, whose even points are the size `n` domain of the original columns. The
+/// matrix is extended to `2n` rows by Reed-Solomon encoding each column, and rows
+/// extended by [`GridBench::extend_grid_2d`] hold their cells directly.
+pub trait MatrixCommit: GridBench {
+    type Elem;
+    type CellProof;
+    // The value held by cell `(row, col)` of the extended matrix
+    fn cell(s: &Self::Setup, g: &Self::ExtendedGrid, row: usize, col: usize) -> Self::Elem;
+    fn extend(s: &Self::Setup, g: &Self::Grid) -> Self::ExtendedGrid {
+        Self::extend_grid(s, g)
+    }
+    // One commitment per row of the extended matrix
+    fn commit_matrix(s: &Self::Setup, g: &Self::ExtendedGrid) -> Self::Commits {
+        Self::make_commits(s, g)
+    }
+    fn open_cell(
+        s: &Self::Setup,
+        g: &Self::ExtendedGrid,
+        row: usize,
+        col: usize,
+    ) -> Self::CellProof;
+    fn verify_cell(
+        s: &Self::Setup,
+        commits: &Self::Commits,
+        row: usize,
+        col: usize,
+        value: &Self::Elem,
+        proof: &Self::CellProof,
+    ) -> bool;
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum RecoveryError {
+    #[error("Expected a column of {expected} cells, got {got}")]
+    WrongLength { expected: usize, got: usize },
+    #[error("Need {needed} cells to reconstruct, only {present} are present")]
+    NotEnoughData { present: usize, needed: usize },
+}
+
 #[cfg(test)]
 fn test_works<T: PcBench>() {
     const BASE_DEG: usize = 2usize.pow(12);
     const TRIM_DEG: usize = 2usize.pow(10);
+    assert!(!T::OPEN_COMPLEXITY.is_empty());
+    assert!(!T::PROOF_SIZE_CLASS.is_empty());
     let mut s = T::setup(BASE_DEG);
     let t = T::trim(&s, TRIM_DEG);
     let (poly, point, value) = T::rand_poly(&mut s, TRIM_DEG);
@@ -67,6 +241,63 @@ fn test_works<T: PcBench>() {
     assert!(T::verify(&t, &c, &p, &value, &point));
 }
 
+#[cfg(test)]
+fn test_matrix_works<T: MatrixCommit>()
+where
+    T::Elem: Clone + PartialEq + std::fmt::Debug,
+{
+    const SIZE: usize = 16;
+    let s = T::do_setup(SIZE);
+    let eg = T::extend(&s, &T::rand_grid(SIZE));
+    let commits = T::commit_matrix(&s, &eg);
+    for (row, col) in [(0, 0), (1, 5), (2 * SIZE - 1, SIZE - 1), (3, 2 * SIZE - 1)] {
+        let value = T::cell(&s, &eg, row, col);
+        let proof = T::open_cell(&s, &eg, row, col);
+        assert!(T::verify_cell(&s, &commits, row, col, &value, &proof));
+        let other = T::cell(&s, &eg, row, (col + 1) % (2 * SIZE));
+        assert!(!T::verify_cell(&s, &commits, row, col, &other, &proof));
+    }
+    // Indices from a verifier's input are rejected rather than panicking
+    let value = T::cell(&s, &eg, 0, 0);
+    let proof = T::open_cell(&s, &eg, 0, 0);
+    assert!(!T::verify_cell(&s, &commits, 0, 2 * SIZE, &value, &proof));
+    assert!(!T::verify_cell(&s, &commits, 2 * SIZE, 0, &value, &proof));
+}
+
+// Extends a grid, drops a random half of its rows and recovers the extended grid
+#[cfg(test)]
+fn test_grid_reconstruct_works<T>()
+where
+    T: GridBench<ExtendedGrid = Vec<Vec<<T as GridBench>::Point>>>,
+    T::Point: PartialEq + std::fmt::Debug,
+{
+    const SIZE: usize = 16;
+    let s = T::do_setup(SIZE);
+    let eg = T::extend_grid(&s, &T::rand_grid(SIZE));
+    let mut partial = vec![None; 2 * SIZE];
+    for i in rand::seq::index::sample(&mut test_rng(), 2 * SIZE, SIZE) {
+        partial[i] = Some(eg[i].clone());
+    }
+    assert_eq!(T::reconstruct(&s, &partial), Ok(eg));
+
+    let dropped = partial.iter().position(Option::is_some).unwrap();
+    partial[dropped] = None;
+    assert_eq!(
+        T::reconstruct(&s, &partial),
+        Err(RecoveryError::NotEnoughData {
+            present: SIZE - 1,
+            needed: SIZE
+        })
+    );
+    assert_eq!(
+        T::reconstruct(&s, &partial[1..]),
+        Err(RecoveryError::WrongLength {
+            expected: 2 * SIZE,
+            got: 2 * SIZE - 1
+        })
+    );
+}
+
 #[cfg(test)]
 fn test_enc_works<T: ErasureEncodeBench>() {
     let domain_a = T::make_domain(32);
@@ -76,3 +307,167 @@ fn test_enc_works<T: ErasureEncodeBench>() {
     T::erasure_encode(&mut pts, &domain_a, &domain_b);
     assert_eq!(pts.len(), 64);
 }
+
+// Encodes 32 points into 64, erases a random half and recovers the 32
+#[cfg(test)]
+fn test_dec_works<T: ErasureDecodeBench>()
+where
+    T::Point: PartialEq + std::fmt::Debug,
+{
+    let domain_a = T::make_domain(32);
+    let domain_b = T::make_domain(64);
+    let pts = T::rand_points(32);
+    let mut encoded = pts.clone();
+    T::erasure_encode(&mut encoded, &domain_a, &domain_b);
+
+    let mut present = vec![false; 64];
+    for i in rand::seq::index::sample(&mut test_rng(), 64, 32) {
+        present[i] = true;
+    }
+    let mut decoded = encoded.clone();
+    T::erasure_decode(&mut decoded, &present, &domain_a, &domain_b).unwrap();
+    assert_eq!(decoded, pts);
+
+    let first = present.iter().position(|p| *p).unwrap();
+    present[first] = false;
+    assert_eq!(
+        T::erasure_decode(&mut encoded.clone(), &present, &domain_a, &domain_b),
+        Err(RecoveryError::NotEnoughData {
+            present: 31,
+            needed: 32
+        })
+    );
+}
+
+// The `i`-th input point must come out as the `i * scale`-th encoded point
+#[cfg(test)]
+fn test_enc_index_mapping<T: ErasureEncodeBench>()
+where
+    T::Point: PartialEq + std::fmt::Debug,
+{
+    let (sub_domain, big_domain) = (T::make_domain(32), T::make_domain(128));
+    let pts = T::rand_points(32);
+    let mut encoded = pts.clone();
+    T::erasure_encode(&mut encoded, &sub_domain, &big_domain);
+    assert_eq!(encoded.len(), 128);
+    let scale = 128 / 32;
+    for (i, pt) in pts.iter().enumerate() {
+        assert_eq!(*pt, encoded[i * scale]);
+    }
+}
+
+// A 16 element domain can't hold the 32 element one, so this must panic
+#[cfg(test)]
+fn test_enc_non_dividing_domains<T: ErasureEncodeBench>() {
+    let mut pts = T::rand_points(32);
+    T::erasure_encode(&mut pts, &T::make_domain(32), &T::make_domain(16));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ark::{
+        fri_bench::*, kzg_bench::*, kzg_multiproof_bench::*, ligero_bench::*, marlin_bench::*,
+        pedersen_bench::*,
+    };
+    use rand::RngCore;
+
+    const PROOF_SIZE_DEG: usize = 256;
+
+    fn proof_size_at<T: PcBench>() -> usize {
+        report::proof_size_at::<T>(&mut T::setup(PROOF_SIZE_DEG), PROOF_SIZE_DEG)
+    }
+
+    // Guards against proofs growing by accident. Compressed G1 points are 48 bytes on
+    // BLS12-381, 32 bytes on BN254 and 96 bytes on BW6-761
+    #[test]
+    fn test_proof_sizes() {
+        type Bls = ark_bls12_381_04::Bls12_381;
+        // One G1
+        assert_eq!(proof_size_at::<plonk_kzg::PlonkKZG>(), 48);
+        assert_eq!(proof_size_at::<KzgBls12_381Bench>(), 48);
+        assert_eq!(proof_size_at::<KzgBn254Bench>(), 32);
+        assert_eq!(proof_size_at::<KzgBw6_761Bench>(), 96);
+        // One G1, and the `Option` flag for the hiding evaluation
+        assert_eq!(proof_size_at::<MarlinBls12_381Bench>(), 49);
+        assert_eq!(proof_size_at::<MarlinBn254Bench>(), 33);
+        assert_eq!(proof_size_at::<MarlinBw6_761Bench>(), 97);
+        // One G1 for method1, two for method2, whatever the number of points
+        assert_eq!(proof_size_at::<Multiproof1Bench<Bls, 5, 5>>(), 48);
+        assert_eq!(proof_size_at::<Multiproof2Bench<Bls, 5, 5>>(), 96);
+        // Two combined rows of 32 elements, then 309 opened columns of 9 elements, each
+        // with a Merkle path of 7 hashes over the 128 encoded columns
+        assert_eq!(
+            proof_size_at::<LigeroBls12_381Bench>(),
+            (2 * 32 + 309 * 9) * 32 + 309 * 7 * 32
+        );
+        // 9 rounds over 2048 evaluations. Each of the 189 queries opens a pair per round,
+        // with Merkle paths of 10 down to 2 hashes, then come 8 layer roots and the final
+        // constant
+        assert_eq!(
+            proof_size_at::<FriBls12_381Bench>(),
+            (1 + 189 * 9 * 2) * 32 + (8 + 189 * 54) * 32
+        );
+        // The nonce commitment `A` as a compressed Jubjub point and the scalar `t`, then
+        // the responses `s` as a `u64` length and one scalar per coefficient
+        assert_eq!(
+            proof_size_at::<InCircuitCommitBench>(),
+            32 + 32 + 8 + (PROOF_SIZE_DEG + 1) * 32
+        );
+    }
+
+    fn check_add_commitments<T: HomomorphicPcBench>()
+    where
+        for<'a> &'a T::Poly: std::ops::Add<&'a T::Poly, Output = T::Poly>,
+        T::Commit: PartialEq + std::fmt::Debug,
+    {
+        let mut s = T::setup(32);
+        let t = T::trim(&s, 32);
+        let (p, _, _) = T::rand_poly(&mut s, 32);
+        let (q, _, _) = T::rand_poly(&mut s, 32);
+        let sum = T::add_commitments(&T::commit(&t, &mut s, &p), &T::commit(&t, &mut s, &q));
+        assert_eq!(sum, T::commit(&t, &mut s, &(&p + &q)));
+    }
+
+    #[test]
+    fn test_add_commitments() {
+        check_add_commitments::<KzgBls12_381Bench>();
+        check_add_commitments::<KzgBn254Bench>();
+        check_add_commitments::<InCircuitCommitBench>();
+        check_add_commitments::<plonk_kzg::PlonkKZG>();
+    }
+
+    #[test]
+    fn test_cached_eval_matches_evaluate() {
+        use ark_ff::UniformRand;
+        let mut s = KzgBls12_381Bench::setup(64);
+        let cached = KzgBls12_381Bench::rand_poly_with_cached_eval(&mut s, 64);
+        assert_eq!(
+            cached.evaluate(&cached.point),
+            cached.poly.evaluate(&cached.point)
+        );
+        let other = ark_bls12_381::Fr::rand(&mut test_rng());
+        assert_eq!(cached.evaluate(&other), cached.poly.evaluate(&other));
+    }
+
+    // Benchmarks rely on this to draw the same setups and inputs on every run
+    #[test]
+    fn test_bench_rng_is_reproducible() {
+        assert_eq!(
+            bench_rng(BENCH_SEED).next_u64(),
+            bench_rng(BENCH_SEED).next_u64()
+        );
+        assert_ne!(
+            bench_rng(BENCH_SEED).next_u64(),
+            bench_rng(BENCH_SEED + 1).next_u64()
+        );
+        assert_eq!(
+            bench_rng_for(RngTag::Setup).next_u64(),
+            bench_rng_for(RngTag::Setup).next_u64()
+        );
+        let tags = [RngTag::Setup, RngTag::Inputs, RngTag::Challenge, RngTag::Sample];
+        let firsts: std::collections::HashSet<_> =
+            tags.iter().map(|&t| bench_rng_for(t).next_u64()).collect();
+        assert_eq!(firsts.len(), tags.len());
+    }
+}
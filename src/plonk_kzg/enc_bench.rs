@@ -1,7 +1,5 @@
 use dusk_plonk::{fft::EvaluationDomain, prelude::BlsScalar};
-use rand::thread_rng;
-
-use crate::ErasureEncodeBench;
+use crate::{bench_rng_for, ErasureEncodeBench, RngTag};
 
 pub struct PlonkEncBench;
 
@@ -14,9 +12,8 @@ impl ErasureEncodeBench for PlonkEncBench {
     }
 
     fn rand_points(size: usize) -> Vec<Self::Point> {
-        (0..size)
-            .map(|_| BlsScalar::random(&mut thread_rng()))
-            .collect()
+        let mut rng = bench_rng_for(RngTag::Inputs);
+        (0..size).map(|_| BlsScalar::random(&mut rng)).collect()
     }
 
     fn erasure_encode(
@@ -25,7 +22,11 @@ impl ErasureEncodeBench for PlonkEncBench {
         big_domain: &Self::Domain,
     ) {
         assert_eq!(sub_domain.size(), pts.len());
-        assert_eq!(sub_domain.size() % sub_domain.size(), 0); // Domain a must divide domain b
+        assert_eq!(
+            big_domain.size() % sub_domain.size(),
+            0,
+            "The domain size must divide the larger domain size"
+        );
         sub_domain.ifft_in_place(pts);
         pts.resize(big_domain.size(), BlsScalar::zero());
         *pts = big_domain.fft(pts);
@@ -35,11 +36,22 @@ impl ErasureEncodeBench for PlonkEncBench {
 
 #[cfg(test)]
 mod tests {
-    use crate::test_enc_works;
+    use crate::{test_enc_index_mapping, test_enc_non_dividing_domains, test_enc_works};
 
     use super::*;
     #[test]
     fn test_works() {
         test_enc_works::<PlonkEncBench>()
     }
+
+    #[test]
+    fn test_index_mapping() {
+        test_enc_index_mapping::<PlonkEncBench>()
+    }
+
+    #[test]
+    #[should_panic(expected = "must divide")]
+    fn test_non_dividing_domains() {
+        test_enc_non_dividing_domains::<PlonkEncBench>()
+    }
 }
@@ -1,50 +1,57 @@
-use crate::test_rng;
+use crate::{bench_rng_for, RngTag};
 use dusk_plonk::{
     bls12_381::G1Affine,
-    commitment_scheme::kzg10::PublicParameters,
+    commitment_scheme::kzg10::{commitment::Commitment, proof::Proof, PublicParameters},
     fft::{self, EvaluationDomain},
-    prelude::{BlsScalar, CommitKey},
+    prelude::{BlsScalar, CommitKey, OpeningKey},
 };
 use rand::distributions::uniform::SampleRange;
 
-use crate::GridBench;
+use crate::{recovery::reconstruct_rows, GridBench, MatrixCommit, RecoveryError};
 
 pub struct PlonkGridBench;
 
 #[derive(Debug, Clone)]
 pub struct Setup {
     ck: CommitKey,
+    ok: OpeningKey,
     domain_n: EvaluationDomain,
     domain_2n: EvaluationDomain,
 }
 
 impl GridBench for PlonkGridBench {
     type Setup = Setup;
+    type Point = BlsScalar;
     type Grid = Vec<Vec<BlsScalar>>;
     type ExtendedGrid = Self::Grid;
     type Commits = Vec<G1Affine>;
     type Opens = Vec<G1Affine>;
 
+    fn required_srs_degree(grid_size: usize) -> usize {
+        // Extended commitments are interpolated, only the original rows are committed.
+        // Rows extended by `extend_grid_2d` are evaluations of the same polynomials.
+        grid_size.saturating_sub(1)
+    }
+
     fn do_setup(size: usize) -> Self::Setup {
-        let mut rng = crate::test_rng();
-        let pp = PublicParameters::setup(size - 1, &mut rng).expect("Failed setup");
-        let (ck, _) = pp.trim(size - 1).expect("Failed trim");
+        let mut rng = bench_rng_for(RngTag::Setup);
+        let degree = Self::required_srs_degree(size);
+        let pp = PublicParameters::setup(degree, &mut rng).expect("Failed setup");
+        let (ck, ok) = pp.trim(degree).expect("Failed trim");
         let domain_n = EvaluationDomain::new(size).expect("Failed to make n domain");
         let domain_2n = EvaluationDomain::new(2 * size).expect("Failed to make n domain");
         Self::Setup {
             ck,
+            ok,
             domain_n,
             domain_2n,
         }
     }
 
     fn rand_grid(size: usize) -> Self::Grid {
+        let mut rng = bench_rng_for(RngTag::Inputs);
         (0..size)
-            .map(|_| {
-                (0..size)
-                    .map(|_| BlsScalar::random(&mut test_rng()))
-                    .collect()
-            })
+            .map(|_| (0..size).map(|_| BlsScalar::random(&mut rng)).collect())
             .collect()
     }
 
@@ -65,14 +72,17 @@ impl GridBench for PlonkGridBench {
         eg
     }
 
+    fn extend_rows(s: &Self::Setup, g: &mut Self::ExtendedGrid) {
+        // Rows are in coefficient form, so extending one is evaluating it over `domain_2n`
+        for row in g.iter_mut() {
+            *row = s.domain_2n.fft(row);
+        }
+    }
+
     fn make_commits(s: &Self::Setup, g: &Self::ExtendedGrid) -> Self::Commits {
         g.iter()
             .map(|row| {
-                let c =
-                    s.ck.commit(&fft::Polynomial {
-                        coeffs: row.clone(),
-                    })
-                    .expect("Commit failed");
+                let c = s.ck.commit(&Self::row_poly(s, row)).expect("Commit failed");
                 c.0
             })
             .collect()
@@ -80,18 +90,210 @@ impl GridBench for PlonkGridBench {
 
     fn open_column(s: &Self::Setup, g: &Self::ExtendedGrid) -> Self::Opens {
         let n = g.len() / 2;
-        let mut opens = vec![G1Affine::identity(); 2 * n];
-        let j = (0..n).sample_single(&mut test_rng());
-        let elem = s.domain_n.elements().nth(j).expect("Iterator ran out of elements");
-        let polys = g.iter().map(|row| fft::Polynomial{ coeffs: row.clone() }).collect::<Vec<_>>();
-        for i in 0..2*n {
-            let wp = s.ck.compute_single_witness(&polys[i], &elem);
-            opens[i] = s.ck.commit(&wp).expect("Open failed").0;
-        }
-        opens
+        let j = (0..n).sample_single(&mut bench_rng_for(RngTag::Sample));
+        Self::open_column_at(s, &Self::row_polys(s, g), j)
+    }
+
+    fn open_columns(s: &Self::Setup, g: &Self::ExtendedGrid, cols: &[usize]) -> Vec<Self::Opens> {
+        let polys = Self::row_polys(s, g);
+        cols.iter()
+            .map(|&j| Self::open_column_at(s, &polys, j))
+            .collect()
+    }
+
+    fn verify_column(
+        s: &Self::Setup,
+        commits: &Self::Commits,
+        opens: &Self::Opens,
+        g: &Self::ExtendedGrid,
+        col: usize,
+    ) -> bool {
+        let elem = match s.domain_n.elements().nth(col) {
+            Some(elem) => elem,
+            None => return false,
+        };
+        // for each row of the extended grid
+        commits.len() == g.len()
+            && opens.len() == g.len()
+            && g.iter()
+                .zip(commits.iter().zip(opens.iter()))
+                .all(|(row, (c, w))| {
+                    // `\omega_n^j = \omega_{2n}^{2j}`
+                    let value = Self::row_value(s, row, 2 * col);
+                    s.ok.check(
+                        elem,
+                        Proof {
+                            commitment_to_witness: Commitment(*w),
+                            evaluated_point: value,
+                            commitment_to_polynomial: Commitment(*c),
+                        },
+                    )
+                })
+    }
+
+    fn reconstruct(
+        s: &Self::Setup,
+        partial: &[Option<Vec<BlsScalar>>],
+    ) -> Result<Self::ExtendedGrid, RecoveryError> {
+        let points: Vec<_> = s.domain_2n.elements().collect();
+        reconstruct_rows(&points, partial, s.domain_n.size(), invert)
     }
 
     fn bytes_per_elem() -> usize {
         31
     }
 }
+
+impl MatrixCommit for PlonkGridBench {
+    type Elem = BlsScalar;
+    type CellProof = G1Affine;
+
+    fn cell(s: &Self::Setup, g: &Self::ExtendedGrid, row: usize, col: usize) -> BlsScalar {
+        Self::row_value(s, &g[row], col)
+    }
+
+    fn open_cell(s: &Self::Setup, g: &Self::ExtendedGrid, row: usize, col: usize) -> G1Affine {
+        let elem = s.domain_2n.elements().nth(col).expect("Iterator ran out of elements");
+        let poly = Self::row_poly(s, &g[row]);
+        let wp = s.ck.compute_single_witness(&poly, &elem);
+        s.ck.commit(&wp).expect("Open failed").0
+    }
+
+    fn verify_cell(
+        s: &Self::Setup,
+        commits: &Self::Commits,
+        row: usize,
+        col: usize,
+        value: &BlsScalar,
+        proof: &G1Affine,
+    ) -> bool {
+        let (elem, commit) = match (s.domain_2n.elements().nth(col), commits.get(row)) {
+            (Some(elem), Some(commit)) => (elem, commit),
+            _ => return false,
+        };
+        s.ok.check(
+            elem,
+            Proof {
+                commitment_to_witness: Commitment(*proof),
+                evaluated_point: *value,
+                commitment_to_polynomial: Commitment(*commit),
+            },
+        )
+    }
+}
+
+fn invert(x: BlsScalar) -> BlsScalar {
+    Option::from(x.invert()).expect("Inverting zero")
+}
+
+impl PlonkGridBench {
+    /// Whether `row` was extended by [`GridBench::extend_rows`], i.e. holds evaluations
+    /// over `domain_2n` rather than coefficients
+    fn is_extended_row(s: &Setup, row: &[BlsScalar]) -> bool {
+        row.len() == s.domain_2n.size()
+    }
+
+    /// The row polynomial evaluated at the `col`-th element of `domain_2n`
+    fn row_value(s: &Setup, row: &[BlsScalar], col: usize) -> BlsScalar {
+        if Self::is_extended_row(s, row) {
+            return row[col];
+        }
+        let elem = s.domain_2n.elements().nth(col).expect("Iterator ran out of elements");
+        row.iter()
+            .rev()
+            .fold(BlsScalar::zero(), |acc, coeff| acc * elem + coeff)
+    }
+
+    /// The polynomial underlying `row`, in either form
+    fn row_poly(s: &Setup, row: &[BlsScalar]) -> fft::Polynomial {
+        let mut coeffs = row.to_vec();
+        if Self::is_extended_row(s, row) {
+            s.domain_2n.ifft_in_place(&mut coeffs);
+            // The row extension has degree less than `n`, so it fits the trimmed key
+            while coeffs.last() == Some(&BlsScalar::zero()) {
+                coeffs.pop();
+            }
+        }
+        fft::Polynomial { coeffs }
+    }
+
+    fn row_polys(s: &Setup, g: &[Vec<BlsScalar>]) -> Vec<fft::Polynomial> {
+        g.iter().map(|row| Self::row_poly(s, row)).collect()
+    }
+
+    /// Opens every row of the extended grid at column `j`
+    fn open_column_at(s: &Setup, polys: &[fft::Polynomial], j: usize) -> Vec<G1Affine> {
+        let elem = s.domain_n.elements().nth(j).expect("Iterator ran out of elements");
+        polys
+            .iter()
+            .map(|poly| {
+                let wp = s.ck.compute_single_witness(poly, &elem);
+                s.ck.commit(&wp).expect("Open failed").0
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_grid_reconstruct_works, test_matrix_works};
+
+    #[test]
+    fn test_matrix_commit() {
+        test_matrix_works::<PlonkGridBench>();
+    }
+
+    #[test]
+    fn test_grid_reconstruct() {
+        test_grid_reconstruct_works::<PlonkGridBench>();
+    }
+
+    #[test]
+    fn test_extend_grid_2d() {
+        let size = 16;
+        let s = PlonkGridBench::do_setup(size);
+        let grid = PlonkGridBench::rand_grid(size);
+        let eg = PlonkGridBench::extend_grid_2d(&s, &grid);
+        assert_eq!(eg.len(), 2 * size);
+        let columns_only = PlonkGridBench::extend_grid(&s, &grid);
+        for row in 0..2 * size {
+            for col in 0..2 * size {
+                assert_eq!(
+                    PlonkGridBench::cell(&s, &eg, row, col),
+                    PlonkGridBench::cell(&s, &columns_only, row, col)
+                );
+            }
+        }
+        let commits = PlonkGridBench::make_commits(&s, &eg);
+        assert_eq!(commits, PlonkGridBench::make_commits(&s, &columns_only));
+
+        let (row, col) = (3, 2 * size - 1);
+        let value = PlonkGridBench::cell(&s, &eg, row, col);
+        let proof = PlonkGridBench::open_cell(&s, &eg, row, col);
+        assert!(PlonkGridBench::verify_cell(&s, &commits, row, col, &value, &proof));
+    }
+
+    #[test]
+    fn test_open_columns_verify() {
+        let size = 16;
+        let s = PlonkGridBench::do_setup(size);
+        let eg = PlonkGridBench::extend_grid(&s, &PlonkGridBench::rand_grid(size));
+        let commits = PlonkGridBench::make_commits(&s, &eg);
+        let cols = [0, 3, 7, size - 1];
+        let opens = PlonkGridBench::open_columns(&s, &eg, &cols);
+        assert_eq!(opens.len(), cols.len());
+        for (o, &j) in opens.iter().zip(cols.iter()) {
+            assert_eq!(o.len(), 2 * size);
+            assert!(PlonkGridBench::verify_column(&s, &commits, o, &eg, j));
+        }
+        assert!(!PlonkGridBench::verify_column(&s, &commits, &opens[0], &eg, size));
+
+        // Every row needs its commitment and its proof
+        let truncated_opens = opens[0][..size].to_vec();
+        assert!(!PlonkGridBench::verify_column(&s, &commits, &truncated_opens, &eg, 0));
+        assert!(!PlonkGridBench::verify_column(&s, &commits, &Vec::new(), &eg, 0));
+        let truncated_commits = commits[..size].to_vec();
+        assert!(!PlonkGridBench::verify_column(&s, &truncated_commits, &opens[0], &eg, 0));
+    }
+}
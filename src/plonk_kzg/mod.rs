@@ -1,6 +1,7 @@
-use crate::PcBench;
+use crate::{HomomorphicPcBench, PcBench};
 
 use dusk_plonk::{
+    bls12_381::G1Projective,
     commitment_scheme::{
         kzg10::{commitment::Commitment, proof::Proof},
         PublicParameters,
@@ -15,15 +16,17 @@ pub mod grid_bench;
 pub struct PlonkKZG;
 
 impl PcBench for PlonkKZG {
-    type Setup = (PublicParameters, crate::TestRng);
+    type Setup = (PublicParameters, rand::rngs::StdRng);
     type Trimmed = (CommitKey, OpeningKey);
     type Poly = Polynomial;
     type Point = BlsScalar; // This is the i-th root of unity
     type Eval = BlsScalar;
     type Commit = Commitment;
     type Proof = Commitment;
+    const OPEN_COMPLEXITY: &'static str = "O(n)";
+    const PROOF_SIZE_CLASS: &'static str = "O(1)";
     fn setup(max_degree: usize) -> Self::Setup {
-        let mut rng = crate::test_rng();
+        let mut rng = crate::bench_rng_for(crate::RngTag::Setup);
         (
             PublicParameters::setup(max_degree, &mut rng).expect("Failed plonk setup"),
             rng,
@@ -39,6 +42,10 @@ impl PcBench for PlonkKZG {
         31
     }
 
+    fn proof_size(proof: &Self::Proof) -> usize {
+        proof.0.to_compressed().len()
+    }
+
     fn commit(t: &Self::Trimmed, _s: &mut Self::Setup, p: &Self::Poly) -> Self::Commit {
         t.0.commit(p).unwrap()
     }
@@ -57,7 +64,7 @@ impl PcBench for PlonkKZG {
         t: &Self::Trimmed,
         c: &Self::Commit,
         proof: &Self::Proof,
-        value: &Self::Point,
+        value: &Self::Eval,
         pt: &Self::Point,
     ) -> bool {
         t.1.check(
@@ -78,6 +85,12 @@ impl PcBench for PlonkKZG {
     }
 }
 
+impl HomomorphicPcBench for PlonkKZG {
+    fn add_commitments(a: &Self::Commit, b: &Self::Commit) -> Self::Commit {
+        Commitment::from(G1Projective::from(a.0) + b.0)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::test_works;
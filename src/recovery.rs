@@ -0,0 +1,168 @@
+//! Erasure recovery shared by the encoding and grid benches: data Reed-Solomon encoded
+//! as the evaluations of a polynomial of degree less than `n` is recovered from any `n`
+//! of them with the barycentric formula `p(t) = l(t) \sum_k w_k y_k / (t - x_k)`, where
+//! `l` vanishes on the known `x_k` and `w_k = 1 / \prod_{m \ne k} (x_k - x_m)`.
+//!
+//! The helpers only need field arithmetic from `std`, so they work for both the
+//! arkworks and the dusk scalars, which pass their own inversion.
+use std::{
+    iter::{Product, Sum},
+    ops::{Mul, Sub},
+};
+
+use crate::RecoveryError;
+
+/// The arithmetic interpolation needs
+pub trait InterpolationField: Copy + Sub<Output = Self> + Mul<Output = Self> + Sum + Product {}
+
+impl<F> InterpolationField for F where
+    F: Copy + Sub<Output = F> + Mul<Output = F> + Sum + Product
+{
+}
+
+/// Interpolation through a fixed set of distinct points, whose weights are computed
+/// once and shared by every evaluation.
+pub struct Barycentric<F> {
+    xs: Vec<F>,
+    weights: Vec<F>,
+    inverse: fn(F) -> F,
+}
+
+impl<F: InterpolationField> Barycentric<F> {
+    /// `inverse` must invert any non-zero element
+    pub fn new(xs: Vec<F>, inverse: fn(F) -> F) -> Self {
+        let weights = xs
+            .iter()
+            .enumerate()
+            .map(|(k, xk)| {
+                let denom: F = xs
+                    .iter()
+                    .enumerate()
+                    .filter(|(m, _)| *m != k)
+                    .map(|(_, xm)| *xk - *xm)
+                    .product();
+                inverse(denom)
+            })
+            .collect();
+        Self { xs, weights, inverse }
+    }
+
+    /// The Lagrange coefficients `l_k(t)`, so that `p(t) = \sum_k l_k(t) y_k`. `t`
+    /// must not be one of the points.
+    pub fn coeffs_at(&self, t: F) -> Vec<F> {
+        let diffs: Vec<F> = self.xs.iter().map(|xk| t - *xk).collect();
+        let vanishing: F = diffs.iter().copied().product();
+        self.weights
+            .iter()
+            .zip(diffs)
+            .map(|(wk, d)| vanishing * *wk * (self.inverse)(d))
+            .collect()
+    }
+}
+
+/// The first `needed` present entries of `values`, each with the point it's the
+/// evaluation at.
+pub fn first_known<'a, F: Copy, T>(
+    points: &[F],
+    values: &'a [Option<T>],
+    needed: usize,
+) -> Result<Vec<(F, &'a T)>, RecoveryError> {
+    if values.len() != points.len() {
+        return Err(RecoveryError::WrongLength {
+            expected: points.len(),
+            got: values.len(),
+        });
+    }
+    let known: Vec<(F, &T)> = points
+        .iter()
+        .zip(values)
+        .filter_map(|(x, y)| y.as_ref().map(|y| (*x, y)))
+        .take(needed)
+        .collect();
+    if known.len() < needed {
+        return Err(RecoveryError::NotEnoughData {
+            present: values.iter().filter(|y| y.is_some()).count(),
+            needed,
+        });
+    }
+    Ok(known)
+}
+
+/// Fills in the missing rows of a grid whose columns hold the evaluations at `points`
+/// of polynomials of degree less than `needed`. All columns share the present rows,
+/// so the Lagrange coefficients of each missing row are computed once.
+pub fn reconstruct_rows<F: InterpolationField>(
+    points: &[F],
+    partial: &[Option<Vec<F>>],
+    needed: usize,
+    inverse: fn(F) -> F,
+) -> Result<Vec<Vec<F>>, RecoveryError> {
+    let known = first_known(points, partial, needed)?;
+    let interp = Barycentric::new(known.iter().map(|(x, _)| *x).collect(), inverse);
+    let cols = known.first().map_or(0, |(_, row)| row.len());
+
+    Ok(points
+        .iter()
+        .zip(partial)
+        .map(|(t, row)| {
+            row.clone().unwrap_or_else(|| {
+                let coeffs = interp.coeffs_at(*t);
+                (0..cols)
+                    .map(|j| coeffs.iter().zip(&known).map(|(lk, (_, yk))| *lk * yk[j]).sum())
+                    .collect()
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use ark_ff::{Field, UniformRand};
+    use ark_poly::{univariate::DensePolynomial, Polynomial, UVPolynomial};
+
+    use super::*;
+    use crate::test_rng;
+
+    fn inverse(x: Fr) -> Fr {
+        x.inverse().expect("Inverting zero")
+    }
+
+    #[test]
+    fn test_barycentric_interpolates() {
+        let rng = &mut test_rng();
+        let poly = DensePolynomial::<Fr>::rand(7, rng);
+        let xs: Vec<Fr> = (0..8).map(|_| Fr::rand(rng)).collect();
+        let interp = Barycentric::new(xs.clone(), inverse);
+        let t = Fr::rand(rng);
+        let value: Fr = interp
+            .coeffs_at(t)
+            .iter()
+            .zip(&xs)
+            .map(|(lk, xk)| *lk * poly.evaluate(xk))
+            .sum();
+        assert_eq!(value, poly.evaluate(&t));
+    }
+
+    #[test]
+    fn test_first_known() {
+        let points = [Fr::from(1u64), Fr::from(2u64), Fr::from(3u64)];
+        let values = [None, Some(5), Some(6)];
+        let known = first_known(&points, &values, 1).unwrap();
+        assert_eq!(known, vec![(points[1], &5)]);
+        assert_eq!(
+            first_known(&points, &values, 3),
+            Err(RecoveryError::NotEnoughData {
+                present: 2,
+                needed: 3
+            })
+        );
+        assert_eq!(
+            first_known(&points[1..], &values, 1),
+            Err(RecoveryError::WrongLength {
+                expected: 2,
+                got: 3
+            })
+        );
+    }
+}
@@ -0,0 +1,335 @@
+//! Post-processing of benchmark measurements.
+//!
+//! Raw byte throughput favours curves with larger scalar fields, since each element
+//! carries more bytes. Reporting field elements per second instead makes schemes
+//! over different curves (e.g. BLS12-381 vs BN254) directly comparable.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
+
+use crate::PcBench;
+
+/// Number of field elements of `element_bytes` bytes each in `bytes` bytes.
+pub fn bytes_to_elems(bytes: u64, element_bytes: usize) -> u64 {
+    assert!(element_bytes > 0, "element_bytes must be non-zero");
+    bytes / element_bytes as u64
+}
+
+/// Bytes of data in a polynomial of degree `poly_deg` whose coefficients each hold
+/// `bytes_per_elem - 1` bytes, or `None` if that doesn't fit in a `u64`. Computed in
+/// `u64` so large degrees don't wrap around on 32-bit targets.
+pub fn poly_data_bytes(poly_deg: usize, bytes_per_elem: usize) -> Option<u64> {
+    let num_coeffs = (poly_deg as u64).checked_add(1)?;
+    let data_bytes = (bytes_per_elem as u64).checked_sub(1)?;
+    num_coeffs.checked_mul(data_bytes)
+}
+
+/// Field elements processed per second, when `iters` iterations each processing
+/// `bytes` bytes of `element_bytes`-byte field elements took `elapsed` in total.
+pub fn elems_per_second(bytes: u64, element_bytes: usize, iters: u64, elapsed: Duration) -> f64 {
+    (bytes_to_elems(bytes, element_bytes) * iters) as f64 / elapsed.as_secs_f64()
+}
+
+/// Criterion's default number of samples per benchmark.
+pub const DEFAULT_SAMPLE_SIZE: usize = 100;
+
+/// The measured samples among everything recorded for a benchmark. Criterion calls
+/// the `iter_custom` routine while warming up, and then once per sample, so only the
+/// last `sample_size` calls are measurements.
+fn measured<T>(samples: &[T], sample_size: usize) -> &[T] {
+    &samples[samples.len().saturating_sub(sample_size)..]
+}
+
+/// Accumulates the measurements criterion hands to `iter_custom` for each benchmark,
+/// and reports them as field elements per second. Only the last `sample_size` calls
+/// of each benchmark are counted, see [`RatioReport`].
+#[derive(Debug)]
+pub struct ElemsPerSecondReport {
+    sample_size: usize,
+    // benchmark id -> (elements processed, time taken) of each call
+    samples: BTreeMap<String, Vec<(u64, Duration)>>,
+}
+
+impl Default for ElemsPerSecondReport {
+    fn default() -> Self {
+        Self::new(DEFAULT_SAMPLE_SIZE)
+    }
+}
+
+impl ElemsPerSecondReport {
+    /// A report for benchmarks taking `sample_size` samples each.
+    pub fn new(sample_size: usize) -> Self {
+        ElemsPerSecondReport {
+            sample_size,
+            samples: BTreeMap::new(),
+        }
+    }
+
+    /// Records that `iters` iterations of benchmark `id`, each processing `bytes`
+    /// bytes of `element_bytes`-byte field elements, took `elapsed`.
+    pub fn record(
+        &mut self,
+        id: &str,
+        bytes: u64,
+        element_bytes: usize,
+        iters: u64,
+        elapsed: Duration,
+    ) {
+        self.samples
+            .entry(id.to_string())
+            .or_default()
+            .push((bytes_to_elems(bytes, element_bytes) * iters, elapsed));
+    }
+
+    /// Mean field elements per second over the measured samples of `id`.
+    pub fn elems_per_second(&self, id: &str) -> Option<f64> {
+        let samples = measured(self.samples.get(id)?, self.sample_size);
+        let elems: u64 = samples.iter().map(|(elems, _)| elems).sum();
+        let time: Duration = samples.iter().map(|(_, time)| *time).sum();
+        Some(elems as f64 / time.as_secs_f64())
+    }
+
+    pub fn print(&self) {
+        for id in self.samples.keys() {
+            println!(
+                "{}: {:.0} field elements/s",
+                id,
+                self.elems_per_second(id).unwrap()
+            );
+        }
+    }
+}
+
+/// The operations of a scheme compared by a [`RatioReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Commit,
+    Open,
+    Verify,
+}
+
+/// `[commit, open, verify]` measurements normalized so that commit is 1.0.
+pub fn op_ratios(commit: f64, open: f64, verify: f64) -> [f64; 3] {
+    [commit, open, verify].map(|t| t / commit)
+}
+
+/// Collects the per-iteration measurement of every criterion sample of commit, open
+/// and verify for each scheme and degree, and reports the ratio of their medians.
+/// Values are in the units of the bench's `Measurement::to_f64`, which cancel out in
+/// the ratios. Criterion also calls the `iter_custom` routine while warming up, so
+/// only the last `sample_size` calls of each operation are measured samples.
+#[derive(Debug)]
+pub struct RatioReport {
+    sample_size: usize,
+    // (scheme, degree) -> per-iteration measurements of commit, open and verify
+    samples: BTreeMap<(String, usize), [Vec<f64>; 3]>,
+}
+
+impl Default for RatioReport {
+    fn default() -> Self {
+        Self::new(DEFAULT_SAMPLE_SIZE)
+    }
+}
+
+impl RatioReport {
+    /// A report for benchmarks taking `sample_size` samples each.
+    pub const fn new(sample_size: usize) -> Self {
+        RatioReport {
+            sample_size,
+            samples: BTreeMap::new(),
+        }
+    }
+
+    /// Records a criterion sample of `iters` iterations of `op` measuring `value`.
+    pub fn record(&mut self, scheme: &str, degree: usize, op: Op, iters: u64, value: f64) {
+        self.samples
+            .entry((scheme.to_string(), degree))
+            .or_default()[op as usize]
+            .push(value / iters as f64);
+    }
+
+    /// Median per-iteration measurement of `op` over its measured samples, if any.
+    pub fn median(&self, scheme: &str, degree: usize, op: Op) -> Option<f64> {
+        let samples = &self.samples.get(&(scheme.to_string(), degree))?[op as usize];
+        let mut values = measured(samples, self.sample_size).to_vec();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        let mid = values.len() / 2;
+        Some(if values.len() % 2 == 0 {
+            (values[mid - 1] + values[mid]) / 2.0
+        } else {
+            values[mid]
+        })
+    }
+
+    /// [`op_ratios`] of the medians, once all three operations are recorded.
+    pub fn ratios(&self, scheme: &str, degree: usize) -> Option<[f64; 3]> {
+        Some(op_ratios(
+            self.median(scheme, degree, Op::Commit)?,
+            self.median(scheme, degree, Op::Open)?,
+            self.median(scheme, degree, Op::Verify)?,
+        ))
+    }
+
+    pub fn print(&self) {
+        for (scheme, degree) in self.samples.keys() {
+            if let Some([commit, open, verify]) = self.ratios(scheme, *degree) {
+                println!(
+                    "{}/{}: commit:open:verify = {:.2}:{:.2}:{:.2}",
+                    scheme, degree, commit, open, verify
+                );
+            }
+        }
+    }
+}
+
+/// Size in bytes of `B`'s proof for a random polynomial of degree `degree`, opened
+/// with parameters `s` set up for at least that degree.
+pub fn proof_size_at<B: PcBench>(s: &mut B::Setup, degree: usize) -> usize {
+    let t = B::trim(s, degree);
+    let (poly, point, _) = B::rand_poly(s, degree);
+    B::proof_size(&B::open(&t, s, &poly, &point))
+}
+
+/// Proof sizes of each scheme at each degree, printed as a table. Sizes don't vary
+/// between runs, so they're computed once rather than timed.
+#[derive(Debug, Default)]
+pub struct ProofSizeReport {
+    // scheme -> degree -> proof size in bytes
+    sizes: BTreeMap<String, BTreeMap<usize, usize>>,
+}
+
+impl ProofSizeReport {
+    /// Records the size of a proof of `B` at each of `degrees`, under the name `scheme`.
+    pub fn record_scheme<B: PcBench>(&mut self, scheme: &str, degrees: &[usize]) {
+        if let Some(&max_degree) = degrees.iter().max() {
+            let mut s = B::setup(max_degree);
+            for &degree in degrees {
+                self.record(scheme, degree, proof_size_at::<B>(&mut s, degree));
+            }
+        }
+    }
+
+    pub fn record(&mut self, scheme: &str, degree: usize, bytes: usize) {
+        self.sizes
+            .entry(scheme.to_string())
+            .or_default()
+            .insert(degree, bytes);
+    }
+
+    pub fn size(&self, scheme: &str, degree: usize) -> Option<usize> {
+        self.sizes.get(scheme)?.get(&degree).copied()
+    }
+
+    /// One row per scheme and one column per degree, in bytes. Degrees a scheme
+    /// wasn't recorded at are left blank.
+    pub fn table(&self) -> String {
+        let degrees: BTreeSet<usize> =
+            self.sizes.values().flat_map(|d| d.keys()).copied().collect();
+        let width = self.sizes.keys().map(String::len).max().unwrap_or(0).max(6);
+        let mut out = format!("{:<width$}", "degree", width = width);
+        for d in &degrees {
+            out += &format!(" {:>10}", d);
+        }
+        for (scheme, sizes) in &self.sizes {
+            out += &format!("\n{:<width$}", scheme, width = width);
+            for d in &degrees {
+                match sizes.get(d) {
+                    Some(bytes) => out += &format!(" {:>10}", bytes),
+                    None => out += &format!(" {:>10}", ""),
+                }
+            }
+        }
+        out
+    }
+
+    pub fn print(&self) {
+        println!("Proof sizes in bytes\n{}", self.table());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elems_per_second() {
+        // 32 elements of 31 bytes, twice, in half a second
+        let rate = elems_per_second(32 * 31, 31, 2, Duration::from_millis(500));
+        assert_eq!(rate, 128.0);
+        // Partial elements don't count
+        assert_eq!(bytes_to_elems(32 * 31 + 30, 31), 32);
+
+        // The same elements on a smaller field give the same rate
+        let bn_rate = elems_per_second(32 * 30, 30, 2, Duration::from_millis(500));
+        assert_eq!(rate, bn_rate);
+    }
+
+    #[test]
+    fn test_poly_data_bytes() {
+        assert_eq!(poly_data_bytes(31, 32), Some(32 * 31));
+        // 2^28 elements of 31 bytes is more than a 32-bit `usize` holds
+        let deg = (1 << 28) - 1;
+        assert_eq!(poly_data_bytes(deg, 32), Some(31 << 28));
+        // Only a 64-bit `usize` is large enough for the product to overflow a `u64`
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(poly_data_bytes(usize::MAX, 1 << 40), None);
+        assert_eq!(poly_data_bytes(31, 0), None);
+    }
+
+    #[test]
+    fn test_report_accumulates() {
+        let mut report = ElemsPerSecondReport::default();
+        assert_eq!(report.elems_per_second("commit"), None);
+        report.record("commit", 10 * 31, 31, 1, Duration::from_secs(1));
+        report.record("commit", 10 * 31, 31, 3, Duration::from_secs(1));
+        assert_eq!(report.elems_per_second("commit"), Some(20.0));
+
+        // With two samples, the first call was warming up
+        let mut report = ElemsPerSecondReport::new(2);
+        report.record("commit", 10 * 31, 31, 100, Duration::from_secs(1));
+        report.record("commit", 10 * 31, 31, 1, Duration::from_secs(1));
+        report.record("commit", 10 * 31, 31, 3, Duration::from_secs(1));
+        assert_eq!(report.elems_per_second("commit"), Some(20.0));
+    }
+
+    #[test]
+    fn test_op_ratios() {
+        let ratios = op_ratios(40.0, 60.0, 2.0);
+        assert_eq!(ratios, [1.0, 1.5, 0.05]);
+
+        let mut report = RatioReport::new(3);
+        // A warm up call, then per-iteration times of 10, 20 and 90 for commit, the
+        // median is 20
+        for (iters, value) in [(1, 1000.0), (1, 10.0), (2, 40.0), (1, 90.0)] {
+            report.record("kzg", 32, Op::Commit, iters, value);
+        }
+        report.record("kzg", 32, Op::Open, 4, 120.0);
+        assert_eq!(report.ratios("kzg", 32), None);
+        report.record("kzg", 32, Op::Verify, 1, 4.0);
+        report.record("kzg", 32, Op::Verify, 1, 6.0);
+        assert_eq!(report.median("kzg", 32, Op::Commit), Some(20.0));
+        assert_eq!(report.ratios("kzg", 32), Some([1.0, 1.5, 0.25]));
+        assert_eq!(report.ratios("kzg", 64), None);
+    }
+
+    #[test]
+    fn test_proof_size_report() {
+        let mut report = ProofSizeReport::default();
+        report.record_scheme::<crate::ark::kzg_bench::KzgBls12_381Bench>("kzg", &[16, 32]);
+        report.record("fri", 32, 1000);
+        // One compressed G1 point at every degree
+        assert_eq!(report.size("kzg", 16), Some(48));
+        assert_eq!(report.size("kzg", 32), Some(48));
+        assert_eq!(report.size("fri", 16), None);
+        assert_eq!(
+            report.table(),
+            "degree         16         32\n\
+             fri                     1000\n\
+             kzg            48         48"
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! Peak resident set size of the process, for comparing how much memory the provers
+//! need. Linux reads it from `/proc/self/status` and can reset it through
+//! `/proc/self/clear_refs`. macOS asks the kernel with `task_info`, and has no way to
+//! reset it, so the peak there covers everything the process did so far.
+use std::fs;
+
+/// The peak resident set size in bytes, or `None` if this platform can't report it.
+pub fn peak_rss() -> Option<usize> {
+    platform_peak_rss()
+}
+
+/// Lowers the peak to the current resident set size, so that [`peak_rss`] only covers
+/// what runs afterwards. Returns whether the platform supports it.
+pub fn reset_peak_rss() -> bool {
+    cfg!(target_os = "linux") && fs::write("/proc/self/clear_refs", "5").is_ok()
+}
+
+#[cfg(target_os = "linux")]
+fn platform_peak_rss() -> Option<usize> {
+    // A line like `VmHWM:     1234 kB`
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+    let kb: usize = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(target_os = "macos")]
+fn platform_peak_rss() -> Option<usize> {
+    use mach2::{
+        kern_return::KERN_SUCCESS,
+        task::task_info,
+        task_info::{
+            mach_task_basic_info, task_info_t, MACH_TASK_BASIC_INFO, MACH_TASK_BASIC_INFO_COUNT,
+        },
+        traps::mach_task_self,
+    };
+
+    let mut info = std::mem::MaybeUninit::<mach_task_basic_info>::uninit();
+    let mut count = MACH_TASK_BASIC_INFO_COUNT;
+    // Safety: `info` has room for `count` words of the `MACH_TASK_BASIC_INFO` flavor,
+    // which the kernel fills in on success
+    let info = unsafe {
+        let ret = task_info(
+            mach_task_self(),
+            MACH_TASK_BASIC_INFO,
+            info.as_mut_ptr() as task_info_t,
+            &mut count,
+        );
+        if ret != KERN_SUCCESS {
+            return None;
+        }
+        info.assume_init()
+    };
+    Some(info.resident_size_max as usize)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn platform_peak_rss() -> Option<usize> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ark::kzg_bench::KzgBls12_381Bench, PcBench};
+
+    #[test]
+    fn test_open_memory_is_plausible() {
+        let degree = 1 << 10;
+        let mut s = KzgBls12_381Bench::setup(degree);
+        let t = KzgBls12_381Bench::trim(&s, degree);
+        let (poly, point, _) = KzgBls12_381Bench::rand_poly(&mut s, degree);
+        let peak = KzgBls12_381Bench::measure_open_memory(&t, &mut s, &poly, &point);
+        // At least the polynomial itself, and less than a terabyte
+        assert!(peak > (degree + 1) * 32);
+        assert!(peak < 1 << 40);
+    }
+}
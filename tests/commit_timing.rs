@@ -0,0 +1,69 @@
+//! A coarse check that `KZG10::commit_constant_time` takes as long on a polynomial
+//! whose low coefficients are all zero as on a dense one. Like `verify_timing`, this
+//! is only a tripwire, not a constant-time guarantee. Run with
+//! `cargo test --release --features ct-tests`.
+#![cfg(feature = "ct-tests")]
+
+use std::time::{Duration, Instant};
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_ff::Zero;
+use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+use poly_commit_benches::{
+    ark::kzg::{ConstantTimePowers, KZG10},
+    bench_rng, BENCH_SEED,
+};
+
+type Kzg = KZG10<Bls12_381, DensePolynomial<Fr>>;
+
+const DEGREE: usize = 256;
+const SAMPLES: usize = 200;
+// The medians may differ by at most this fraction of the dense polynomial median
+const MAX_RELATIVE_DIFFERENCE: f64 = 0.1;
+
+fn median(mut times: Vec<Duration>) -> f64 {
+    times.sort();
+    times[times.len() / 2].as_secs_f64()
+}
+
+#[test]
+fn commit_timing_variance_test() {
+    let rng = &mut bench_rng(BENCH_SEED);
+    let pp = Kzg::setup(DEGREE, rng).unwrap();
+    let (powers, _) = Kzg::trim(&pp, DEGREE).unwrap();
+    let ct_powers = ConstantTimePowers::prepare(&powers);
+    let dense = DensePolynomial::<Fr>::rand(DEGREE, rng);
+    // Only the top coefficient is nonzero, so `commit` would skip all but one power
+    let mut coeffs = vec![Fr::zero(); DEGREE];
+    coeffs.push(dense.coeffs[DEGREE]);
+    let leading_zeros = DensePolynomial::from_coefficients_vec(coeffs);
+
+    let time_commit = |p: &DensePolynomial<Fr>| {
+        let start = Instant::now();
+        let comm = Kzg::commit_constant_time(&ct_powers, p).unwrap();
+        let elapsed = start.elapsed();
+        assert_eq!(comm, Kzg::commit(&powers, p).unwrap());
+        elapsed
+    };
+    // Warm up caches and the CPU clock
+    for _ in 0..SAMPLES / 10 {
+        time_commit(&dense);
+        time_commit(&leading_zeros);
+    }
+
+    // Interleave so drift over the run hits both distributions equally
+    let (mut dense_times, mut sparse_times) = (Vec::new(), Vec::new());
+    for _ in 0..SAMPLES {
+        dense_times.push(time_commit(&dense));
+        sparse_times.push(time_commit(&leading_zeros));
+    }
+
+    let (dense, sparse) = (median(dense_times), median(sparse_times));
+    let relative_difference = (dense - sparse).abs() / dense;
+    assert!(
+        relative_difference < MAX_RELATIVE_DIFFERENCE,
+        "median commit took {:.3}ms on a dense polynomial and {:.3}ms with leading zeros",
+        dense * 1e3,
+        sparse * 1e3
+    );
+}
@@ -0,0 +1,32 @@
+use ark_bls12_381::Fr;
+use ark_ff::One;
+use poly_commit_benches::{
+    ark::grid_bench::KzgGridBenchBls12_381, bench_rng, GridBench, BENCH_SEED,
+};
+use rand::seq::index::sample;
+
+type B = KzgGridBenchBls12_381;
+
+#[test]
+fn test_das_flow() {
+    let size = 16;
+    let n_samples = 4;
+    let s = B::do_setup(size);
+    let grid = B::rand_grid(size);
+    let mut eg = B::extend_grid(&s, &grid);
+    assert_eq!(eg.len(), 2 * size);
+
+    let commits = B::make_commits(&s, &eg);
+    let cols = sample(&mut bench_rng(BENCH_SEED), size, n_samples).into_vec();
+    let opens = B::open_columns(&s, &eg, &cols);
+    for (o, &col) in opens.iter().zip(cols.iter()) {
+        assert!(B::verify_column(&s, &commits, o, &eg, col));
+    }
+
+    // A sampler holding a corrupted copy of the data must notice
+    eg[3][5] += Fr::one();
+    assert!(opens
+        .iter()
+        .zip(cols.iter())
+        .any(|(o, &col)| !B::verify_column(&s, &commits, o, &eg, col)));
+}
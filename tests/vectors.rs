@@ -0,0 +1,92 @@
+//! Known-answer vectors for KZG commitments and openings over parameters from a fixed
+//! tau. They pin both the commitment math and the serialization, so an unintended
+//! change to either fails here. When a change is intended, regenerate the vectors by
+//! printing `commit_and_open`'s output and pasting it in.
+use ark_ec::PairingEngine;
+use ark_poly::{univariate::DensePolynomial, Polynomial, UVPolynomial};
+use ark_serialize::CanonicalSerialize;
+use poly_commit_benches::ark::kzg::KZG10;
+
+const DEGREE: usize = 15;
+const TAU: u64 = 0x1234_5678_9abc_def0;
+
+// (coefficients, opening point)
+const POLYS: [(&[u64], u64); 3] = [
+    (&[1, 2, 3, 4], 5),
+    (&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1], 3),
+    (&[7, 0, 11, 0, 13, 0, 17, 0, 19, 0, 23, 0, 29, 0, 31, 37], 1 << 40),
+];
+
+fn to_hex<T: CanonicalSerialize>(value: &T) -> String {
+    let mut bytes = Vec::new();
+    value.serialize(&mut bytes).unwrap();
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// The serialized commitment and proof for each of `POLYS`
+fn commit_and_open<E: PairingEngine>() -> Vec<(String, String)> {
+    type Kzg<E> = KZG10<E, DensePolynomial<<E as PairingEngine>::Fr>>;
+    let pp = Kzg::<E>::setup_test_vector(DEGREE, E::Fr::from(TAU)).unwrap();
+    let (powers, vk) = Kzg::<E>::trim(&pp, DEGREE).unwrap();
+    POLYS
+        .iter()
+        .map(|(coeffs, point)| {
+            let p = DensePolynomial::from_coefficients_vec(
+                coeffs.iter().map(|&c| E::Fr::from(c)).collect(),
+            );
+            let point = E::Fr::from(*point);
+            let comm = Kzg::<E>::commit(&powers, &p).unwrap();
+            let proof = Kzg::<E>::open(&powers, &p, point).unwrap();
+            assert!(Kzg::<E>::check(&vk, &comm, point, p.evaluate(&point), &proof).unwrap());
+            (to_hex(&comm), to_hex(&proof))
+        })
+        .collect()
+}
+
+fn check_vectors<E: PairingEngine>(expected: &[(&str, &str)]) {
+    let got = commit_and_open::<E>();
+    assert_eq!(got.len(), expected.len());
+    for (i, ((comm, proof), (expected_comm, expected_proof))) in
+        got.iter().zip(expected).enumerate()
+    {
+        assert_eq!(comm, expected_comm, "Commitment {} changed", i);
+        assert_eq!(proof, expected_proof, "Proof {} changed", i);
+    }
+}
+
+// Compressed G1 points are 48 bytes on BLS12-381 and 32 on BN254
+#[test]
+fn bls12_381_vectors() {
+    check_vectors::<ark_bls12_381::Bls12_381>(&[
+        (
+            "0e4c596c35f109bd8166adc00e6a0b9c1ccfc799557d498f1ff1d60a2fae055ea96fb78f8a69d560bc3004f375619c07",
+            "1dfacd5185747e6111b28b638b82ec262338996dcf2e7f77bd7ee2c3dfd3c35ddd7a437426e63bae082c7207af94f904",
+        ),
+        (
+            "b970b6441e1bc76a453601526d722832aa9e86ee6a2721b594988bb014a16bd1a3e63912372451ba35b07519f3740902",
+            "d7721a6a4538b538e73747e4c8db49624e331d7d224728769ed3aafa2e961c3b518da59afc6fd0fe007b3cf55dd71385",
+        ),
+        (
+            "6ddce1804f138a3954571edbd4af56dd08a73378d73739179af73baf9b4d20da8d9a76f1cc1d13dc1d9378a0108b1c12",
+            "9921613aec91b2cc7a04898f416d06ada15bda037322bb5eaa72e515e08404330958d4b65900dafb8cbe2b626abf5a12",
+        ),
+    ]);
+}
+
+#[test]
+fn bn254_vectors() {
+    check_vectors::<ark_bn254::Bn254>(&[
+        (
+            "427664e617770220ee60b59271ff66a48ff191df911aac74be31d122b9e77c9a",
+            "fd25a308d7da8c16c0aa991cd1e53b9174b01966bd2c3defc5a058d251b332a7",
+        ),
+        (
+            "b6a5a2971adf7a2ff1390fe52443712252e6488ddad0abdd99e460add1188186",
+            "f5eba13637c669fc62118f2bba51deab684fe82a4ad4ad261e1c5f724b557fad",
+        ),
+        (
+            "796534c38a0f4a2f8fb6b9e9b9f3f5f4de01a2ef147ce4fa83c3a22437163c2a",
+            "efed8411fda8483bc65dcc55e2b4d3d3491b73e45f69ca2c2c41c5140070bd86",
+        ),
+    ]);
+}
@@ -0,0 +1,65 @@
+//! A coarse check that `KZG10::check` takes as long to reject a proof as to accept
+//! it. This is not a constant-time guarantee, only a tripwire for an early return
+//! sneaking into `check`. Run with `cargo test --release --features ct-tests`.
+#![cfg(feature = "ct-tests")]
+
+use std::time::{Duration, Instant};
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_ff::{One, UniformRand};
+use ark_poly::{univariate::DensePolynomial, Polynomial, UVPolynomial};
+use poly_commit_benches::{ark::kzg::KZG10, bench_rng, BENCH_SEED};
+
+type Kzg = KZG10<Bls12_381, DensePolynomial<Fr>>;
+
+const DEGREE: usize = 64;
+const SAMPLES: usize = 200;
+// The medians may differ by at most this fraction of the valid proof median
+const MAX_RELATIVE_DIFFERENCE: f64 = 0.1;
+
+fn median(mut times: Vec<Duration>) -> f64 {
+    times.sort();
+    times[times.len() / 2].as_secs_f64()
+}
+
+#[test]
+fn verify_timing_variance_test() {
+    let rng = &mut bench_rng(BENCH_SEED);
+    let pp = Kzg::setup(DEGREE, rng).unwrap();
+    let (powers, vk) = Kzg::trim(&pp, DEGREE).unwrap();
+    let p = DensePolynomial::<Fr>::rand(DEGREE, rng);
+    let comm = Kzg::commit(&powers, &p).unwrap();
+    let point = Fr::rand(rng);
+    let value = p.evaluate(&point);
+    let proof = Kzg::open(&powers, &p, point).unwrap();
+    let bad_value = value + Fr::one();
+
+    let time_check = |v: Fr, expected: bool| {
+        let start = Instant::now();
+        let ok = Kzg::check(&vk, &comm, point, v, &proof).unwrap();
+        let elapsed = start.elapsed();
+        assert_eq!(ok, expected);
+        elapsed
+    };
+    // Warm up caches and the CPU clock
+    for _ in 0..SAMPLES / 10 {
+        time_check(value, true);
+        time_check(bad_value, false);
+    }
+
+    // Interleave so drift over the run hits both distributions equally
+    let (mut valid, mut invalid) = (Vec::new(), Vec::new());
+    for _ in 0..SAMPLES {
+        valid.push(time_check(value, true));
+        invalid.push(time_check(bad_value, false));
+    }
+
+    let (valid, invalid) = (median(valid), median(invalid));
+    let relative_difference = (valid - invalid).abs() / valid;
+    assert!(
+        relative_difference < MAX_RELATIVE_DIFFERENCE,
+        "median check took {:.3}ms on valid and {:.3}ms on invalid proofs",
+        valid * 1e3,
+        invalid * 1e3
+    );
+}